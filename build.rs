@@ -4,6 +4,7 @@ use std::fs;
 use std::fs::File;
 use std::io::Write;
 use std::path::PathBuf;
+use std::process::Command;
 
 use glob::glob;
 use nom::bytes::complete::tag;
@@ -14,6 +15,25 @@ use nom::IResult;
 
 fn main() {
     generate_signature_maps();
+    generate_git_hash();
+}
+
+// -----------------------------------------------------------------------------
+// Build info
+// -----------------------------------------------------------------------------
+
+/// Exposes the git commit being built as the `STRATUS_GIT_HASH` environment variable, or `unknown` when git is not available.
+fn generate_git_hash() {
+    let git_hash = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|git_hash| git_hash.trim().to_owned())
+        .unwrap_or_else(|| "unknown".to_owned());
+
+    println!("cargo:rustc-env=STRATUS_GIT_HASH={}", git_hash);
 }
 
 // -----------------------------------------------------------------------------
@@ -25,7 +25,7 @@ describe("JSON-RPC", () => {
         });
         it("web3_clientVersion", async () => {
             let client = await sendExpect("web3_clientVersion");
-            match(CURRENT_NETWORK).with(Network.Stratus, () => client.deep.eq("stratus"));
+            match(CURRENT_NETWORK).with(Network.Stratus, () => client.match(/^stratus\/v/));
         });
     });
 
@@ -36,6 +36,16 @@ describe("JSON-RPC", () => {
                 .with(Network.Stratus, () => gasPrice.eq(ZERO))
                 .otherwise(() => gasPrice.not.eq(ZERO));
         });
+        it("eth_maxPriorityFeePerGas", async () => {
+            let priorityFee = await sendExpect("eth_maxPriorityFeePerGas");
+            match(CURRENT_NETWORK).with(Network.Stratus, () => priorityFee.eq(ZERO));
+        });
+        it("eth_feeHistory", async () => {
+            let history = await send("eth_feeHistory", [1, "latest", [50]]);
+            expect(history.baseFeePerGas.length).eq(2);
+            expect(history.gasUsedRatio.length).eq(1);
+            expect(history.reward.length).eq(1);
+        });
     });
 
     describe("Account", () => {
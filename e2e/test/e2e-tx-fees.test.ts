@@ -0,0 +1,42 @@
+import { expect } from "chai";
+import { TransactionReceipt } from "web3-types";
+
+import { ALICE, BOB } from "./helpers/account";
+import { CHAIN_ID_DEC, ONE, send, sendRawTransaction, sendReset, toHex } from "./helpers/rpc";
+
+describe("Transaction: fees", () => {
+    var _receipt: TransactionReceipt;
+
+    async function balanceAt(address: string, blockNumber: bigint): Promise<bigint> {
+        return BigInt(await send("eth_getBalance", [address, toHex(blockNumber)]));
+    }
+
+    it("Resets blockchain", async () => {
+        await sendReset();
+    });
+    it("Send transaction paying gas", async () => {
+        let txSigned = await ALICE.signer().signTransaction({
+            to: BOB.address,
+            value: 0,
+            chainId: CHAIN_ID_DEC,
+            nonce: 0,
+            gasPrice: 1,
+            gasLimit: 1_000_000,
+        });
+        let txHash = await sendRawTransaction(txSigned);
+        _receipt = await send("eth_getTransactionReceipt", [txHash]);
+        expect(_receipt.status).eq(ONE, "receipt.status");
+    });
+    it("Gas used is transferred from sender to block miner", async () => {
+        let blockNumber = BigInt(_receipt.blockNumber);
+        let block = await send("eth_getBlockByNumber", [toHex(blockNumber), false]);
+        let fee = BigInt(_receipt.gasUsed) * BigInt(_receipt.effectiveGasPrice!);
+        expect(fee > 0n).eq(true, "fee");
+
+        // balances are read at the block before and at the block of the transaction, so they are read from persisted history
+        let senderBefore = await balanceAt(ALICE.address, blockNumber - 1n);
+        let minerBefore = await balanceAt(block.miner, blockNumber - 1n);
+        expect(await balanceAt(ALICE.address, blockNumber)).eq(senderBefore - fee, "sender balance");
+        expect(await balanceAt(block.miner, blockNumber)).eq(minerBefore + fee, "miner balance");
+    });
+});
@@ -56,6 +56,8 @@ describe("Transaction: serial transfer", () => {
         expect(receipt.transactionIndex).eq(ZERO, "receipt.txIndex");
         expect(receipt.from).eq(ALICE.address, "receipt.from");
         expect(receipt.status).eq(ONE, "receipt.status");
+        expect(receipt.cumulativeGasUsed).eq(receipt.gasUsed, "receipt.cumulativeGasUsed");
+        expect(receipt.gasUsed).eq(_block.gasUsed, "receipt.gasUsed");
     });
     it("Sender nonce increased", async () => {
         expect(await send("eth_getTransactionCount", [ALICE, "latest"])).eq(ONE);
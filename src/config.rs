@@ -1,11 +1,29 @@
 //! Application configuration.
 
+use std::fmt::Display;
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::Duration;
 
 use anyhow::anyhow;
 use clap::Parser;
 
+use crate::eth::primitives::Address;
+use crate::eth::primitives::FeePolicy;
+use crate::eth::txpool::TransactionPoolLimits;
+
+/// Client version reported by default, including the package version and the git commit it was built from.
+const DEFAULT_CLIENT_VERSION: &str = concat!("stratus/v", env!("CARGO_PKG_VERSION"), "-", env!("STRATUS_GIT_HASH"));
+
+/// Largest contract size limit that can be configured, also used when no limit is specified.
+///
+/// The database schema limits deployed bytecodes to this size and transaction inputs to twice this size.
+pub const MAX_CONTRACT_SIZE_LIMIT: usize = 262_144;
+
+/// Largest input of a transaction, which fits the creation code of the largest contract allowed (EIP-3860).
+pub const MAX_TRANSACTION_INPUT_SIZE: usize = 2 * MAX_CONTRACT_SIZE_LIMIT;
+
 /// Application configuration entry-point.
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -18,6 +36,106 @@ pub struct Config {
     #[arg(short = 'a', long = "address", env = "ADDRESS", default_value = "0.0.0.0:3000")]
     pub address: SocketAddr,
 
+    /// Path of a geth-style genesis JSON file with the timestamp of the genesis block and the accounts that exist before any transaction.
+    #[arg(long = "genesis", env = "GENESIS")]
+    pub genesis: Option<PathBuf>,
+
+    /// Funds test accounts at genesis. Must be used only in development and test environments.
+    #[arg(long = "enable-test-accounts", env = "ENABLE_TEST_ACCOUNTS", default_value = "false")]
+    pub enable_test_accounts: bool,
+
+    /// Chain ID that transactions must be signed for, returned by `eth_chainId` and the `CHAINID` opcode.
+    #[arg(long = "chain-id", env = "CHAIN_ID", default_value = "2008")]
+    pub chain_id: u64,
+
+    /// Network ID returned by `net_version`. Defaults to the chain ID.
+    #[arg(long = "network-id", env = "NETWORK_ID")]
+    pub network_id: Option<u64>,
+
+    /// Client version returned by `web3_clientVersion`.
+    #[arg(long = "client-version", env = "CLIENT_VERSION", default_value = DEFAULT_CLIENT_VERSION)]
+    pub client_version: String,
+
+    /// Base fee policy of mined blocks: `zero`, `fixed` to use the gas price as the base fee of all blocks, or `dynamic` to adjust the base
+    /// fee after each block starting from the gas price, as defined by EIP-1559.
+    #[arg(long = "fee-policy", env = "FEE_POLICY", default_value_t = FeePolicyConfig::Zero)]
+    pub fee_policy: FeePolicyConfig,
+
+    /// Gas price in wei used as the base fee by the `fixed` fee policy or as the initial base fee by the `dynamic` fee policy.
+    #[arg(long = "gas-price", env = "GAS_PRICE", default_value = "0")]
+    pub gas_price: u64,
+
+    /// Charges senders for the gas used by their transactions at the effective gas price and credits the fees to the fee recipient.
+    ///
+    /// The fee recipient balance is modified by every transaction, so transactions of the same block are executed again on top of
+    /// each other.
+    #[arg(long = "charge-gas", env = "CHARGE_GAS", default_value = "false")]
+    pub charge_gas: bool,
+
+    /// Account credited with the fees paid by transactions when gas is charged, also returned by the `COINBASE` opcode.
+    #[arg(long = "fee-recipient", env = "FEE_RECIPIENT", value_parser = parse_fee_recipient)]
+    pub fee_recipient: Option<Address>,
+
+    /// Block production mode: `automine` mines one block per transaction, while an interval like `1s` or `500ms` mines blocks periodically.
+    #[arg(long = "block-mode", env = "BLOCK_MODE", default_value_t = BlockMode::Automine)]
+    pub block_mode: BlockMode,
+
+    /// Maximum number of transactions in a block when mining blocks periodically.
+    #[arg(long = "block-max-transactions", env = "BLOCK_MAX_TRANSACTIONS", default_value = "1000")]
+    pub block_max_transactions: usize,
+
+    /// Maximum gas used by all transactions of a block, reported in block headers and by the `GASLIMIT` opcode.
+    #[arg(long = "block-gas-limit", env = "BLOCK_GAS_LIMIT", default_value = "100000000")]
+    pub block_gas_limit: u64,
+
+    /// Maximum gas a single transaction can use. Cannot be greater than the block gas limit.
+    #[arg(long = "transaction-gas-limit", env = "TRANSACTION_GAS_LIMIT", default_value = "100000000")]
+    pub transaction_gas_limit: u64,
+
+    /// Maximum gas used by read-only calls like `eth_call` and `debug_traceCall`.
+    #[arg(long = "call-gas-cap", env = "CALL_GAS_CAP", default_value = "100000000")]
+    pub call_gas_cap: u64,
+
+    /// EVM hardfork used to execute transactions: `london`, `paris`, `shanghai` or `cancun`.
+    ///
+    /// Contracts compiled by solc 0.8.20 or newer use the `PUSH0` opcode, available since `shanghai`.
+    #[arg(long = "evm-hardfork", env = "EVM_HARDFORK", default_value_t = EvmHardfork::London)]
+    pub evm_hardfork: EvmHardfork,
+
+    /// Maximum size in bytes of the bytecode of deployed contracts, usually 24576 (EIP-170). Defaults to the largest limit allowed, 262144.
+    ///
+    /// Since `shanghai`, contract creation code is limited to twice this size (EIP-3860).
+    #[arg(long = "contract-size-limit", env = "CONTRACT_SIZE_LIMIT")]
+    pub contract_size_limit: Option<usize>,
+
+    /// Maximum number of times a transaction is executed again after conflicting with concurrent transactions.
+    #[arg(long = "conflict-max-retries", env = "CONFLICT_MAX_RETRIES", default_value = "10")]
+    pub conflict_max_retries: usize,
+
+    /// Maximum time spent executing a transaction again after conflicting with concurrent transactions, like `5s` or `500ms`.
+    #[arg(long = "conflict-timeout", env = "CONFLICT_TIMEOUT", default_value = "5s", value_parser = parse_duration)]
+    pub conflict_timeout: Duration,
+
+    /// Maximum number of transactions with a nonce gap queued in the transaction pool.
+    #[arg(long = "txpool-max-queued", env = "TXPOOL_MAX_QUEUED", default_value = "1024")]
+    pub txpool_max_queued: usize,
+
+    /// Maximum number of transactions with a nonce gap queued in the transaction pool for a single sender.
+    #[arg(long = "txpool-max-queued-per-sender", env = "TXPOOL_MAX_QUEUED_PER_SENDER", default_value = "64")]
+    pub txpool_max_queued_per_sender: usize,
+
+    /// Maximum distance between the nonce of a queued transaction and the nonce of its sender.
+    #[arg(long = "txpool-max-nonce-distance", env = "TXPOOL_MAX_NONCE_DISTANCE", default_value = "1024")]
+    pub txpool_max_nonce_distance: u64,
+
+    /// Time queued transactions of a sender are kept without new activity from the sender, like `10800s`.
+    #[arg(long = "txpool-queued-lifetime", env = "TXPOOL_QUEUED_LIFETIME", default_value = "10800s", value_parser = parse_duration)]
+    pub txpool_queued_lifetime: Duration,
+
+    /// Maximum number of blocks whose transactions are re-executed by a single `trace_filter` request.
+    #[arg(long = "trace-filter-max-blocks", env = "TRACE_FILTER_MAX_BLOCKS", default_value = "1000")]
+    pub trace_filter_max_blocks: u64,
+
     /// Number of EVM instances to run.
     #[arg(long = "evms", env = "EVMS", default_value = "1")]
     pub num_evms: usize,
@@ -29,6 +147,81 @@ pub struct Config {
     /// Number of threads to execute global blocking tasks.
     #[arg(long = "blocking-threads", env = "BLOCKING_THREADS", default_value = "1")]
     pub num_blocking_threads: usize,
+
+    /// Enables development JSON-RPC methods (`evm_*` and `hardhat_*`) used by test automation.
+    #[arg(long = "dev", env = "DEV", default_value = "false")]
+    pub dev: bool,
+}
+
+impl Config {
+    /// Checks that configured values are consistent with each other.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.transaction_gas_limit > self.block_gas_limit {
+            return Err(anyhow!(
+                "transaction gas limit {} is greater than block gas limit {}",
+                self.transaction_gas_limit,
+                self.block_gas_limit
+            ));
+        }
+        if self.contract_size_limit == Some(0) {
+            return Err(anyhow!("contract size limit must be greater than zero"));
+        }
+        if let Some(limit) = self.contract_size_limit.filter(|limit| *limit > MAX_CONTRACT_SIZE_LIMIT) {
+            return Err(anyhow!(
+                "contract size limit {} is greater than the largest limit allowed {}",
+                limit,
+                MAX_CONTRACT_SIZE_LIMIT
+            ));
+        }
+        Ok(())
+    }
+
+    /// Maximum size in bytes of contract creation code, if limited by the hardfork and the contract size limit (EIP-3860).
+    pub fn initcode_size_limit(&self) -> Option<usize> {
+        match self.contract_size_limit {
+            Some(limit) if self.evm_hardfork >= EvmHardfork::Shanghai => Some(limit.saturating_mul(2)),
+            _ => None,
+        }
+    }
+
+    /// Account that receives the fees paid by transactions, if gas is charged.
+    pub fn fee_recipient(&self) -> anyhow::Result<Option<Address>> {
+        match (self.charge_gas, &self.fee_recipient) {
+            (false, _) => Ok(None),
+            (true, Some(fee_recipient)) if fee_recipient.is_ignored() => Err(anyhow!("fee recipient cannot be the zero or the coinbase address")),
+            (true, Some(fee_recipient)) => Ok(Some(fee_recipient.clone())),
+            (true, None) => Err(anyhow!("fee recipient must be configured when gas is charged")),
+        }
+    }
+
+    /// Account returned by the `COINBASE` opcode and recorded as the miner of mined blocks.
+    ///
+    /// It is the fee recipient when gas is charged, or the default coinbase address otherwise.
+    pub fn coinbase(&self) -> Address {
+        match self.fee_recipient {
+            Some(ref fee_recipient) if self.charge_gas => fee_recipient.clone(),
+            _ => Address::COINBASE,
+        }
+    }
+
+    /// Builds the limits of transactions queued in the transaction pool.
+    pub fn txpool_limits(&self) -> TransactionPoolLimits {
+        TransactionPoolLimits {
+            max_queued: self.txpool_max_queued,
+            max_queued_per_sender: self.txpool_max_queued_per_sender,
+            max_nonce_distance: self.txpool_max_nonce_distance,
+            queued_lifetime: self.txpool_queued_lifetime,
+        }
+    }
+
+    /// Builds the policy used to calculate the base fee of mined blocks.
+    pub fn fee_policy(&self) -> FeePolicy {
+        match self.fee_policy {
+            FeePolicyConfig::Zero => FeePolicy::Zero,
+            FeePolicyConfig::Fixed => FeePolicy::Fixed(self.gas_price.into()),
+            FeePolicyConfig::Dynamic => FeePolicy::Dynamic(self.gas_price.into()),
+        }
+    }
 }
 
 /// Storage configuration.
@@ -52,3 +245,190 @@ impl FromStr for StorageConfig {
         }
     }
 }
+
+/// Base fee policy configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, strum::Display)]
+pub enum FeePolicyConfig {
+    #[strum(serialize = "zero")]
+    Zero,
+
+    #[strum(serialize = "fixed")]
+    Fixed,
+
+    #[strum(serialize = "dynamic")]
+    Dynamic,
+}
+
+impl FromStr for FeePolicyConfig {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self, Self::Err> {
+        match s {
+            "zero" => Ok(Self::Zero),
+            "fixed" => Ok(Self::Fixed),
+            "dynamic" => Ok(Self::Dynamic),
+            s => Err(anyhow!("unknown fee policy: {}", s)),
+        }
+    }
+}
+
+/// EVM hardfork configuration, ordered by activation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, strum::Display)]
+pub enum EvmHardfork {
+    #[strum(serialize = "london")]
+    London,
+
+    #[strum(serialize = "paris")]
+    Paris,
+
+    #[strum(serialize = "shanghai")]
+    Shanghai,
+
+    #[strum(serialize = "cancun")]
+    Cancun,
+}
+
+impl FromStr for EvmHardfork {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self, Self::Err> {
+        match s {
+            "london" => Ok(Self::London),
+            "paris" | "merge" => Ok(Self::Paris),
+            "shanghai" => Ok(Self::Shanghai),
+            "cancun" => Ok(Self::Cancun),
+            s => Err(anyhow!("unknown evm hardfork: {}", s)),
+        }
+    }
+}
+
+/// Block production mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlockMode {
+    /// Mines a block for each transaction as soon as it is executed.
+    Automine,
+
+    /// Mines a block periodically with the transactions executed since the previous block, or an empty block if there are none.
+    Interval(Duration),
+}
+
+impl Display for BlockMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Automine => write!(f, "automine"),
+            Self::Interval(interval) => write!(f, "{}ms", interval.as_millis()),
+        }
+    }
+}
+
+impl FromStr for BlockMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self, Self::Err> {
+        if s == "automine" {
+            return Ok(Self::Automine);
+        }
+
+        let interval = parse_duration(s).map_err(|_| anyhow!("unknown block mode: {}", s))?;
+        if interval.is_zero() {
+            return Err(anyhow!("block interval must be greater than zero: {}", s));
+        }
+        Ok(Self::Interval(interval))
+    }
+}
+
+/// Parses the fee recipient, rejecting addresses whose changes are ignored, as fees credited to them would be lost.
+fn parse_fee_recipient(s: &str) -> anyhow::Result<Address> {
+    let address = Address::from_str(s)?;
+    if address.is_ignored() {
+        return Err(anyhow!("fee recipient cannot be the zero or the coinbase address: {}", s));
+    }
+    Ok(address)
+}
+
+/// Parses a duration in milliseconds like `500ms` or in seconds like `5s`.
+fn parse_duration(s: &str) -> anyhow::Result<Duration> {
+    if let Some(millis) = s.strip_suffix("ms") {
+        Ok(Duration::from_millis(millis.parse()?))
+    } else if let Some(secs) = s.strip_suffix('s') {
+        Ok(Duration::from_secs(secs.parse()?))
+    } else {
+        Err(anyhow!("unknown duration: {}", s))
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Tests
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEE_RECIPIENT: &str = "0x00000000000000000000000000000000000000fe";
+
+    fn config(args: &[&str]) -> Config {
+        Config::parse_from(std::iter::once("stratus").chain(args.iter().copied()))
+    }
+
+    #[test]
+    fn validate_rejects_inconsistent_limits() {
+        assert!(config(&[]).validate().is_ok());
+        assert!(config(&["--block-gas-limit=30000000", "--transaction-gas-limit=30000000"]).validate().is_ok());
+        assert!(config(&["--block-gas-limit=30000000", "--transaction-gas-limit=30000001"]).validate().is_err());
+        assert!(config(&["--contract-size-limit=0"]).validate().is_err());
+        assert!(config(&["--contract-size-limit=24576"]).validate().is_ok());
+        assert!(config(&[&format!("--contract-size-limit={}", MAX_CONTRACT_SIZE_LIMIT)]).validate().is_ok());
+        assert!(config(&[&format!("--contract-size-limit={}", MAX_CONTRACT_SIZE_LIMIT + 1)]).validate().is_err());
+    }
+
+    #[test]
+    fn evm_hardfork_is_parsed_in_activation_order() {
+        assert_eq!(config(&[]).evm_hardfork, EvmHardfork::London);
+        assert_eq!(config(&["--evm-hardfork=merge"]).evm_hardfork, EvmHardfork::Paris);
+        assert_eq!(config(&["--evm-hardfork=cancun"]).evm_hardfork, EvmHardfork::Cancun);
+        assert!(Config::try_parse_from(["stratus", "--evm-hardfork=berlin"]).is_err());
+
+        assert!(EvmHardfork::London < EvmHardfork::Paris);
+        assert!(EvmHardfork::Paris < EvmHardfork::Shanghai);
+        assert!(EvmHardfork::Shanghai < EvmHardfork::Cancun);
+    }
+
+    #[test]
+    fn initcode_size_limit_applies_since_shanghai() {
+        assert_eq!(config(&["--contract-size-limit=24576"]).initcode_size_limit(), None);
+        assert_eq!(config(&["--evm-hardfork=paris", "--contract-size-limit=24576"]).initcode_size_limit(), None);
+        assert_eq!(
+            config(&["--evm-hardfork=shanghai", "--contract-size-limit=24576"]).initcode_size_limit(),
+            Some(49_152)
+        );
+        assert_eq!(
+            config(&["--evm-hardfork=cancun", "--contract-size-limit=24576"]).initcode_size_limit(),
+            Some(49_152)
+        );
+        assert_eq!(config(&["--evm-hardfork=shanghai"]).initcode_size_limit(), None);
+    }
+
+    #[test]
+    fn fee_recipient_is_required_only_when_gas_is_charged() {
+        assert_eq!(config(&[]).fee_recipient().unwrap(), None);
+        assert_eq!(config(&[&format!("--fee-recipient={}", FEE_RECIPIENT)]).fee_recipient().unwrap(), None);
+        assert!(config(&["--charge-gas"]).fee_recipient().is_err());
+
+        let config = config(&["--charge-gas", &format!("--fee-recipient={}", FEE_RECIPIENT)]);
+        let fee_recipient = Address::from_str(FEE_RECIPIENT).unwrap();
+        assert_eq!(config.fee_recipient().unwrap(), Some(fee_recipient.clone()));
+        assert_eq!(config.coinbase(), fee_recipient);
+    }
+
+    #[test]
+    fn fee_recipient_cannot_be_ignored_address() {
+        for address in [Address::ZERO, Address::COINBASE] {
+            assert!(parse_fee_recipient(&address.to_string()).is_err());
+
+            // also rejected when set without parsing the command line
+            let mut config = config(&["--charge-gas"]);
+            config.fee_recipient = Some(address);
+            assert!(config.fee_recipient().is_err());
+        }
+    }
+}
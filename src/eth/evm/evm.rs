@@ -6,12 +6,22 @@
 //! facilitates flexible EVM integrations, enabling the project to adapt to different blockchain environments
 //! or requirements while maintaining a consistent execution interface.
 
+use std::sync::Arc;
+
+use ethereum_types::U256;
+
+use crate::eth::primitives::AccessList;
 use crate::eth::primitives::Address;
+use crate::eth::primitives::BlockHeader;
 use crate::eth::primitives::Bytes;
 use crate::eth::primitives::CallInput;
 use crate::eth::primitives::Execution;
+use crate::eth::primitives::ExecutionTrace;
+use crate::eth::primitives::Gas;
 use crate::eth::primitives::Nonce;
+use crate::eth::primitives::StateOverlay;
 use crate::eth::primitives::StoragePointInTime;
+use crate::eth::primitives::Tracer;
 use crate::eth::primitives::TransactionInput;
 use crate::eth::primitives::Wei;
 use crate::ext::OptionExt;
@@ -20,6 +30,9 @@ use crate::ext::OptionExt;
 pub trait Evm: Send + Sync + 'static {
     /// Execute a transaction that deploys a contract or call a contract function.
     fn execute(&mut self, input: EvmInput) -> anyhow::Result<Execution>;
+
+    /// Execute a transaction or call like `execute`, but also collect a trace of the execution to be rendered by the tracer.
+    fn trace(&mut self, input: EvmInput, tracer: &Tracer) -> anyhow::Result<(Execution, ExecutionTrace)>;
 }
 
 /// EVM input data. Usually derived from a transaction or call.
@@ -54,13 +67,70 @@ pub struct EvmInput {
     ///
     /// It can be:
     /// * Required when executing an `eth_sendRawTransaction`.
-    /// * Not specified when performing an `eth_call`.
+    /// * Nonce in the `nonce` field, if specified, when performing an `eth_call`.
     pub nonce: Option<Nonce>,
 
+    /// Maximum gas the operation can use.
+    ///
+    /// It can be:
+    /// * Transaction gas when executing an `eth_sendRawTransaction`.
+    /// * Gas in the `gas` field, limited by the call gas cap, when performing an `eth_call`.
+    pub gas_limit: Gas,
+
+    /// Price paid for each unit of gas used when gas is charged.
+    ///
+    /// It can be:
+    /// * Transaction gas price, or the maximum fee of dynamic fee transactions, when executing an `eth_sendRawTransaction`.
+    /// * Gas price in the `gasPrice` field, or zero if not specified, when performing an `eth_call`.
+    pub gas_price: Wei,
+
+    /// Maximum fee per gas of dynamic fee transactions (EIP-1559).
+    ///
+    /// When specified, the price paid for each unit of gas is the block base fee plus the priority fee, limited by this fee.
+    pub max_fee_per_gas: Option<Wei>,
+
+    /// Maximum fee per gas paid above the block base fee by dynamic fee transactions (EIP-1559).
+    pub max_priority_fee_per_gas: Option<Wei>,
+
+    /// Accounts and slots loaded before the execution starts (EIP-2930).
+    ///
+    /// Only specified by typed transactions and calls.
+    pub access_list: AccessList,
+
     /// Block number indicating the point-in-time the EVM state will be used to compute the transaction.
     ///
     /// When not specified, assumes the current state.
     pub point_in_time: StoragePointInTime,
+
+    /// Block the EVM will use as context to compute the transaction: its number, timestamp, coinbase and gas limit.
+    ///
+    /// It can be:
+    /// * Block the transaction will be mined in when executing an `eth_sendRawTransaction`.
+    /// * Pending block, or the selected block for past states, when performing an `eth_call`.
+    ///
+    /// When not specified, assumes the block after the current one at the current time.
+    pub block: Option<BlockHeader>,
+
+    /// State modified by transactions executed before this one in the same block that is not saved in the storage yet.
+    ///
+    /// Values in the overlay take precedence over the values in the storage.
+    pub state_overlay: Arc<StateOverlay>,
+}
+
+impl EvmInput {
+    /// Gas price effectively paid when executed in a block with the given base fee.
+    ///
+    /// Dynamic fee inputs pay the base fee plus their priority fee, limited by their maximum fee.
+    pub fn effective_gas_price(&self, base_fee: &Wei) -> Wei {
+        match (&self.max_fee_per_gas, &self.max_priority_fee_per_gas) {
+            (Some(max_fee), max_priority_fee) => {
+                let max_priority_fee = max_priority_fee.clone().unwrap_or(Wei::ZERO);
+                let price = U256::from(base_fee.clone()).saturating_add(max_priority_fee.into());
+                max_fee.clone().min(price.into())
+            }
+            (None, _) => self.gas_price.clone(),
+        }
+    }
 }
 
 // -----------------------------------------------------------------------------
@@ -76,7 +146,14 @@ impl TryFrom<TransactionInput> for EvmInput {
             value: value.value,
             data: value.input,
             nonce: Some(value.nonce),
+            gas_limit: value.gas,
+            gas_price: value.gas_price,
+            max_fee_per_gas: value.max_fee_per_gas,
+            max_priority_fee_per_gas: value.max_priority_fee_per_gas,
+            access_list: value.access_list,
             point_in_time: StoragePointInTime::Present,
+            block: None,
+            state_overlay: Default::default(),
         })
     }
 }
@@ -88,8 +165,15 @@ impl From<(CallInput, StoragePointInTime)> for EvmInput {
             to: value.0.to.map_into(),
             value: value.0.value,
             data: value.0.data,
-            nonce: None,
+            nonce: value.0.nonce,
+            gas_limit: value.0.gas.unwrap_or(Gas::TRANSACTION_LIMIT),
+            gas_price: value.0.gas_price.unwrap_or(Wei::ZERO),
+            max_fee_per_gas: value.0.max_fee_per_gas,
+            max_priority_fee_per_gas: value.0.max_priority_fee_per_gas,
+            access_list: value.0.access_list,
             point_in_time: value.1,
+            block: None,
+            state_overlay: Default::default(),
         }
     }
 }
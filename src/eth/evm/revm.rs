@@ -5,16 +5,28 @@
 //! interacting with the project's storage backend to manage state. `Revm` embodies the practical application
 //! of the `Evm` trait, serving as a bridge between Ethereum's abstract operations and Stratus's storage mechanisms.
 
+use std::collections::BTreeMap;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use anyhow::anyhow;
 use chrono::Utc;
+use ethereum_types::H256;
+use ethereum_types::U256 as EthersU256;
 use itertools::Itertools;
+use revm::interpreter::opcode;
+use revm::interpreter::CallInputs;
+use revm::interpreter::CallScheme;
+use revm::interpreter::CreateInputs;
+use revm::interpreter::Gas as RevmGas;
 use revm::interpreter::InstructionResult;
+use revm::interpreter::Interpreter;
 use revm::primitives::AccountInfo;
 use revm::primitives::Address as RevmAddress;
 use revm::primitives::Bytecode as RevmBytecode;
+use revm::primitives::Bytes as RevmBytes;
 use revm::primitives::CreateScheme;
+use revm::primitives::EVMResult;
 use revm::primitives::ExecutionResult as RevmExecutionResult;
 use revm::primitives::ResultAndState as RevmResultAndState;
 use revm::primitives::SpecId;
@@ -23,61 +35,116 @@ use revm::primitives::TransactTo;
 use revm::primitives::B256;
 use revm::primitives::U256;
 use revm::Database;
+use revm::EVMData;
 use revm::Inspector;
 use revm::EVM;
 use tokio::runtime::Handle;
 
+use crate::config::Config;
+use crate::config::EvmHardfork;
+use crate::config::MAX_CONTRACT_SIZE_LIMIT;
 use crate::eth::evm::Evm;
 use crate::eth::evm::EvmInput;
 use crate::eth::primitives::Account;
 use crate::eth::primitives::Address;
+use crate::eth::primitives::BlockHeader;
+use crate::eth::primitives::BlockNumber;
 use crate::eth::primitives::Bytes;
 use crate::eth::primitives::Execution;
 use crate::eth::primitives::ExecutionAccountChanges;
 use crate::eth::primitives::ExecutionChanges;
 use crate::eth::primitives::ExecutionResult;
+use crate::eth::primitives::ExecutionTrace;
+use crate::eth::primitives::ExecutionTraceFrame;
+use crate::eth::primitives::ExecutionTraceFrameKind;
+use crate::eth::primitives::ExecutionTraceStep;
 use crate::eth::primitives::ExecutionValueChange;
 use crate::eth::primitives::Gas;
+use crate::eth::primitives::Hash;
 use crate::eth::primitives::Log;
 use crate::eth::primitives::Slot;
 use crate::eth::primitives::SlotIndex;
+use crate::eth::primitives::StateOverlay;
 use crate::eth::primitives::StoragePointInTime;
+use crate::eth::primitives::Tracer;
 use crate::eth::storage::EthStorage;
 use crate::ext::not;
 use crate::ext::OptionExt;
+use crate::if_else;
+
+/// Number of most recent blocks whose hashes are available to the `BLOCKHASH` opcode.
+const BLOCK_HASH_WINDOW: u64 = 256;
 
 /// Implementation of EVM using [`revm`](https://crates.io/crates/revm).
 pub struct Revm {
     evm: EVM<RevmDatabaseSession>,
     storage: Arc<dyn EthStorage>,
+
+    /// Charges the gas used by transactions at their gas price and credits it to the coinbase, which is the fee recipient,
+    /// including the base fee part that would otherwise be burned.
+    charge_gas: bool,
+
+    /// Coinbase and gas limit of blocks when the input does not specify the block it is executed in.
+    coinbase: Address,
+    block_gas_limit: Gas,
 }
 
 impl Revm {
     /// Creates a new instance of the Revm ready to be used.
-    pub fn new(storage: Arc<dyn EthStorage>) -> Self {
+    pub fn new(storage: Arc<dyn EthStorage>, config: &Config) -> anyhow::Result<Self> {
+        let fee_recipient = config.fee_recipient()?;
         let mut evm = EVM::new();
 
         // evm general config
-        evm.env.cfg.spec_id = SpecId::LONDON;
-        evm.env.cfg.limit_contract_code_size = Some(usize::MAX);
-        evm.env.block.coinbase = Address::COINBASE.into();
-
-        // evm tx config
-        evm.env.tx.gas_price = U256::ZERO;
-        evm.env.tx.gas_limit = 100_000_000;
+        evm.env.cfg.chain_id = config.chain_id;
+        evm.env.cfg.spec_id = config.evm_hardfork.into();
+        evm.env.cfg.limit_contract_code_size = Some(config.contract_size_limit.unwrap_or(MAX_CONTRACT_SIZE_LIMIT));
+        // fee caps are validated by the executor, and calls without gas price must still run when the base fee is not zero
+        evm.env.cfg.disable_base_fee = true;
+
+        Ok(Self {
+            evm,
+            storage,
+            charge_gas: fee_recipient.is_some(),
+            coinbase: config.coinbase(),
+            block_gas_limit: config.block_gas_limit.into(),
+        })
+    }
 
-        Self { evm, storage }
+    /// Builds the block the input is executed in when it does not specify one: the block after the current one at the current time.
+    fn next_block(&self) -> anyhow::Result<BlockHeader> {
+        let number = Handle::current().block_on(self.storage.read_current_block_number())?.successor();
+        let mut block = BlockHeader::new(number, Utc::now().timestamp() as u64);
+        block.miner = self.coinbase.clone();
+        block.gas_limit = self.block_gas_limit.clone();
+        Ok(block)
     }
-}
 
-impl Evm for Revm {
-    fn execute(&mut self, input: EvmInput) -> anyhow::Result<Execution> {
+    /// Configures the EVM and a new database session to execute the input.
+    fn prepare(&mut self, input: EvmInput) -> anyhow::Result<()> {
+        let block = match input.block {
+            Some(ref block) => block.clone(),
+            None => self.next_block()?,
+        };
+        let gas_price = input.effective_gas_price(&block.base_fee);
+
         // init session
         let evm = &mut self.evm;
-        let session = RevmDatabaseSession::new(Arc::clone(&self.storage), input.point_in_time, input.to.clone());
+        let session = RevmDatabaseSession::new(
+            Arc::clone(&self.storage),
+            input.point_in_time,
+            input.state_overlay,
+            input.to.clone(),
+            block.number,
+            *block.timestamp_in_secs,
+        );
 
         // configure evm block
-        evm.env.block.timestamp = U256::from(session.block_timestamp_in_secs);
+        evm.env.block.number = U256::from(u64::from(block.number));
+        evm.env.block.timestamp = U256::from(*block.timestamp_in_secs);
+        evm.env.block.coinbase = block.miner.into();
+        evm.env.block.gas_limit = U256::from(u64::from(block.gas_limit));
+        evm.env.block.basefee = block.base_fee.into();
 
         // configure database
         evm.database(session);
@@ -92,17 +159,27 @@ impl Evm for Revm {
         tx.nonce = input.nonce.map_into();
         tx.data = input.data.into();
         tx.value = input.value.into();
+        tx.gas_limit = input.gas_limit.into();
+        tx.gas_price = if_else!(self.charge_gas, gas_price.into(), U256::ZERO);
+        tx.access_list = input.access_list.iter().cloned().map_into().collect();
 
-        // execute evm
-        #[cfg(debug_assertions)]
-        let evm_result = evm.inspect(RevmInspector {});
-
-        #[cfg(not(debug_assertions))]
-        let evm_result = evm.transact();
+        Ok(())
+    }
 
+    /// Parses the EVM result into an execution, consuming the database session.
+    fn parse_result(&mut self, evm_result: EVMResult<anyhow::Error>) -> anyhow::Result<Execution> {
         match evm_result {
-            Ok(result) => {
-                let session = evm.take_db();
+            Ok(mut result) => {
+                // revm burns the base fee part of the gas price, but the fee recipient receives the full gas price
+                if self.charge_gas {
+                    let env = &self.evm.env;
+                    let base_fee_paid = env.tx.gas_price.min(env.block.basefee).saturating_mul(U256::from(result.result.gas_used()));
+                    if let Some(coinbase) = result.state.get_mut(&env.block.coinbase) {
+                        coinbase.info.balance = coinbase.info.balance.saturating_add(base_fee_paid);
+                    }
+                }
+
+                let session = self.evm.take_db();
                 Ok(parse_revm_execution(result, session.block_timestamp_in_secs, session.storage_changes)?)
             }
             Err(e) => {
@@ -113,6 +190,29 @@ impl Evm for Revm {
     }
 }
 
+impl Evm for Revm {
+    fn execute(&mut self, input: EvmInput) -> anyhow::Result<Execution> {
+        self.prepare(input)?;
+        let evm_result = self.evm.transact();
+        self.parse_result(evm_result)
+    }
+
+    fn trace(&mut self, input: EvmInput, tracer: &Tracer) -> anyhow::Result<(Execution, ExecutionTrace)> {
+        self.prepare(input)?;
+        let mut trace = ExecutionTrace::default();
+        let evm_result = self.evm.inspect(RevmTracer::new(&mut trace, tracer));
+        let execution = self.parse_result(evm_result)?;
+
+        // the transaction frame reports the same gas as the execution, including the intrinsic gas
+        if let Some(frame) = trace.frames.first_mut() {
+            frame.gas = self.evm.env.tx.gas_limit.into();
+            frame.gas_used = execution.gas.clone();
+        }
+
+        Ok((execution, trace))
+    }
+}
+
 // -----------------------------------------------------------------------------
 // Database
 // -----------------------------------------------------------------------------
@@ -125,6 +225,12 @@ struct RevmDatabaseSession {
     /// Point in time of the storage during the transaction execution.
     storage_point_in_time: StoragePointInTime,
 
+    /// State modified by previous transactions of the same block that takes precedence over the storage.
+    state_overlay: Arc<StateOverlay>,
+
+    /// Number of the block the transaction is executed in.
+    block_number: BlockNumber,
+
     /// Block timestamp in seconds.
     block_timestamp_in_secs: u64,
 
@@ -136,11 +242,20 @@ struct RevmDatabaseSession {
 }
 
 impl RevmDatabaseSession {
-    pub fn new(storage: Arc<dyn EthStorage>, storage_point_in_time: StoragePointInTime, to: Option<Address>) -> Self {
+    pub fn new(
+        storage: Arc<dyn EthStorage>,
+        storage_point_in_time: StoragePointInTime,
+        state_overlay: Arc<StateOverlay>,
+        to: Option<Address>,
+        block_number: BlockNumber,
+        block_timestamp_in_secs: u64,
+    ) -> Self {
         Self {
             storage,
             storage_point_in_time,
-            block_timestamp_in_secs: Utc::now().timestamp() as u64,
+            state_overlay,
+            block_number,
+            block_timestamp_in_secs,
             to,
             storage_changes: Default::default(),
         }
@@ -153,7 +268,8 @@ impl Database for RevmDatabaseSession {
     fn basic(&mut self, revm_address: RevmAddress) -> anyhow::Result<Option<AccountInfo>> {
         // retrieve account
         let address: Address = revm_address.into();
-        let account = Handle::current().block_on(self.storage.read_account(&address, &self.storage_point_in_time))?;
+        let mut account = Handle::current().block_on(self.storage.read_account(&address, &self.storage_point_in_time))?;
+        self.state_overlay.apply_to_account(&mut account);
 
         // warn if the loaded account is the `to` account and it does not have a bytecode
         if let Some(ref to_address) = self.to {
@@ -171,15 +287,25 @@ impl Database for RevmDatabaseSession {
         Ok(Some(account.into()))
     }
 
-    fn code_by_hash(&mut self, _: B256) -> anyhow::Result<RevmBytecode> {
-        todo!()
+    fn code_by_hash(&mut self, revm_code_hash: B256) -> anyhow::Result<RevmBytecode> {
+        let code_hash: Hash = revm_code_hash.into();
+        match Handle::current().block_on(self.storage.read_bytecode(&code_hash))? {
+            Some(bytecode) => Ok(bytecode.into()),
+            None => {
+                tracing::error!(reason = "reading bytecode that was never deployed", %code_hash);
+                Err(anyhow!("Bytecode with hash '{}' was expected to be deployed, but it was not", code_hash))
+            }
+        }
     }
 
     fn storage(&mut self, revm_address: RevmAddress, revm_index: U256) -> anyhow::Result<U256> {
         // retrieve slot
         let address: Address = revm_address.into();
         let index: SlotIndex = revm_index.into();
-        let slot = Handle::current().block_on(self.storage.read_slot(&address, &index, &self.storage_point_in_time))?;
+        let slot = match self.state_overlay.slot(&address, &index) {
+            Some(slot) => slot.clone(),
+            None => Handle::current().block_on(self.storage.read_slot(&address, &index, &self.storage_point_in_time))?,
+        };
 
         // track original value, except if ignored address
         if not(address.is_ignored()) {
@@ -197,41 +323,247 @@ impl Database for RevmDatabaseSession {
         Ok(slot.value.into())
     }
 
-    fn block_hash(&mut self, _: U256) -> anyhow::Result<B256> {
-        todo!()
+    fn block_hash(&mut self, revm_number: U256) -> anyhow::Result<B256> {
+        // only the most recent blocks before the current one are available, other blocks have zero hash
+        let Ok(number) = u64::try_from(revm_number) else {
+            return Ok(B256::ZERO);
+        };
+        let current = u64::from(self.block_number);
+        if number >= current || current - number > BLOCK_HASH_WINDOW {
+            return Ok(B256::ZERO);
+        }
+
+        let hash = Handle::current().block_on(self.storage.read_block_hash(&number.into()))?;
+        Ok(hash.map_into().unwrap_or(B256::ZERO))
     }
 }
 
 // -----------------------------------------------------------------------------
 // Inspector
 // -----------------------------------------------------------------------------
-struct RevmInspector;
-
-impl Inspector<RevmDatabaseSession> for RevmInspector {
-    fn step(&mut self, _interpreter: &mut revm::interpreter::Interpreter, _: &mut revm::EVMData<'_, RevmDatabaseSession>) -> InstructionResult {
-        // let arg1 = unsafe { *interpreter.instruction_pointer.add(1) };
-        // let arg2 = unsafe { *interpreter.instruction_pointer.add(2) };
-        // println!(
-        //     "{:02x} {:<9} {:<4x} {:<4x} {:?}",
-        //     interpreter.current_opcode(),
-        //     opcode::OPCODE_JUMPMAP[interpreter.current_opcode() as usize].unwrap(),
-        //     arg1,
-        //     arg2,
-        //     interpreter.stack.data(),
-        // );
-        // use revm::interpreter::opcode;
-        // match opcode::OPCODE_JUMPMAP[_interpreter.current_opcode() as usize] {
-        //     Some(opcode) => println!("{} ", opcode),
-        //     None => println!("{:#x} ", _interpreter.current_opcode() as usize),
-        // }
+
+/// Inspector that collects the call frames and, if needed, the opcode steps of an execution.
+struct RevmTracer<'a> {
+    /// Trace being collected.
+    trace: &'a mut ExecutionTrace,
+
+    /// What the tracer needs to be collected.
+    collect_steps: bool,
+    collect_stack: bool,
+    collect_storage: bool,
+
+    /// Frames entered and not exited yet, with the gas available to them.
+    open_frames: Vec<(usize, u64)>,
+
+    /// Steps started and not finished yet, with the slot being read if the step is a `SLOAD`.
+    open_steps: Vec<(usize, Option<(RevmAddress, U256)>)>,
+
+    /// Storage slots accessed so far by each contract.
+    storage: HashMap<RevmAddress, BTreeMap<H256, H256>>,
+}
+
+impl<'a> RevmTracer<'a> {
+    fn new(trace: &'a mut ExecutionTrace, tracer: &Tracer) -> Self {
+        Self {
+            trace,
+            collect_steps: tracer.needs_steps(),
+            collect_stack: tracer.needs_stack(),
+            collect_storage: tracer.needs_storage(),
+            open_frames: Vec::new(),
+            open_steps: Vec::new(),
+            storage: HashMap::new(),
+        }
+    }
+
+    fn enter_frame(&mut self, kind: ExecutionTraceFrameKind, from: Address, to: Option<Address>, value: U256, gas: u64, input: Bytes) {
+        let index = self.trace.frames.len();
+        self.trace.frames.push(ExecutionTraceFrame {
+            kind,
+            from,
+            to,
+            value: value.into(),
+            gas: gas.into(),
+            gas_used: Gas::ZERO,
+            input,
+            output: Bytes::default(),
+            error: None,
+            depth: self.open_frames.len(),
+            parent: self.open_frames.last().map(|(parent, _)| *parent),
+            logs: Vec::new(),
+        });
+        self.open_frames.push((index, gas));
+    }
+
+    fn exit_frame(&mut self, result: InstructionResult, remaining_gas: &RevmGas, output: Bytes, created: Option<Address>) {
+        let Some((index, gas)) = self.open_frames.pop() else {
+            tracing::warn!("exiting evm frame that was not entered");
+            return;
+        };
+
+        let frame = &mut self.trace.frames[index];
+        frame.gas_used = gas.saturating_sub(remaining_gas.remaining()).into();
+        frame.output = output;
+        frame.error = parse_revm_frame_error(result);
+        if frame.kind.is_create() && frame.error.is_none() {
+            frame.to = created;
+        }
+    }
+}
+
+impl<DB: Database> Inspector<DB> for RevmTracer<'_> {
+    fn step(&mut self, interpreter: &mut Interpreter, _: &mut EVMData<'_, DB>) -> InstructionResult {
+        if not(self.collect_steps) {
+            return InstructionResult::Continue;
+        }
+
+        let op = interpreter.current_opcode();
+        let stack = interpreter.stack.data();
+        let contract = interpreter.contract.address;
+
+        // track storage accessed by the current contract
+        let mut storage = None;
+        let mut slot_read = None;
+        if self.collect_storage {
+            match op {
+                opcode::SSTORE if stack.len() >= 2 => {
+                    let contract_storage = self.storage.entry(contract).or_default();
+                    contract_storage.insert(revm_u256_to_h256(stack[stack.len() - 1]), revm_u256_to_h256(stack[stack.len() - 2]));
+                    storage = Some(contract_storage.clone());
+                }
+                opcode::SLOAD if not(stack.is_empty()) => {
+                    slot_read = Some((contract, stack[stack.len() - 1]));
+                }
+                _ => {}
+            }
+        }
+
+        self.open_steps.push((self.trace.steps.len(), slot_read));
+        self.trace.steps.push(ExecutionTraceStep {
+            pc: interpreter.program_counter() as u64,
+            op: parse_revm_opcode(op),
+            gas: interpreter.gas.remaining(),
+            gas_cost: 0,
+            depth: self.open_frames.len() as u64,
+            stack: if_else!(self.collect_stack, Some(stack.iter().map(|value| revm_u256_to_u256(*value)).collect()), None),
+            storage,
+        });
+
+        InstructionResult::Continue
+    }
+
+    fn step_end(&mut self, interpreter: &mut Interpreter, _: &mut EVMData<'_, DB>, _: InstructionResult) -> InstructionResult {
+        let Some((index, slot_read)) = self.open_steps.pop() else {
+            return InstructionResult::Continue;
+        };
+
+        let step = &mut self.trace.steps[index];
+        step.gas_cost = step.gas.saturating_sub(interpreter.gas.remaining());
+
+        // after a SLOAD, the value read is in the top of the stack
+        if let (Some((contract, slot_index)), Some(slot_value)) = (slot_read, interpreter.stack.data().last()) {
+            let contract_storage = self.storage.entry(contract).or_default();
+            contract_storage.insert(revm_u256_to_h256(slot_index), revm_u256_to_h256(*slot_value));
+            step.storage = Some(contract_storage.clone());
+        }
+
         InstructionResult::Continue
     }
+
+    fn log(&mut self, _: &mut EVMData<'_, DB>, address: &RevmAddress, topics: &[B256], data: &RevmBytes) {
+        if let Some((index, _)) = self.open_frames.last() {
+            self.trace.frames[*index].logs.push(Log {
+                address: (*address).into(),
+                topics: topics.iter().map(|topic| (*topic).into()).collect(),
+                data: data.into(),
+            });
+        }
+    }
+
+    fn call(&mut self, _: &mut EVMData<'_, DB>, inputs: &mut CallInputs) -> (InstructionResult, RevmGas, RevmBytes) {
+        let kind = match inputs.context.scheme {
+            CallScheme::Call => ExecutionTraceFrameKind::Call,
+            CallScheme::StaticCall => ExecutionTraceFrameKind::StaticCall,
+            CallScheme::DelegateCall => ExecutionTraceFrameKind::DelegateCall,
+            CallScheme::CallCode => ExecutionTraceFrameKind::CallCode,
+        };
+
+        // in a DELEGATECALL, the caller is the contract that delegated the execution
+        let from = if_else!(kind == ExecutionTraceFrameKind::DelegateCall, inputs.context.address, inputs.context.caller);
+
+        self.enter_frame(
+            kind,
+            from.into(),
+            Some(inputs.contract.into()),
+            inputs.transfer.value,
+            inputs.gas_limit,
+            (&inputs.input).into(),
+        );
+        (InstructionResult::Continue, RevmGas::new(0), RevmBytes::new())
+    }
+
+    fn call_end(
+        &mut self,
+        _: &mut EVMData<'_, DB>,
+        _: &CallInputs,
+        remaining_gas: RevmGas,
+        result: InstructionResult,
+        output: RevmBytes,
+    ) -> (InstructionResult, RevmGas, RevmBytes) {
+        self.exit_frame(result, &remaining_gas, (&output).into(), None);
+        (result, remaining_gas, output)
+    }
+
+    fn create(&mut self, _: &mut EVMData<'_, DB>, inputs: &mut CreateInputs) -> (InstructionResult, Option<RevmAddress>, RevmGas, RevmBytes) {
+        let kind = match inputs.scheme {
+            CreateScheme::Create => ExecutionTraceFrameKind::Create,
+            CreateScheme::Create2 { .. } => ExecutionTraceFrameKind::Create2,
+        };
+        self.enter_frame(kind, inputs.caller.into(), None, inputs.value, inputs.gas_limit, (&inputs.init_code).into());
+        (InstructionResult::Continue, None, RevmGas::new(0), RevmBytes::new())
+    }
+
+    fn create_end(
+        &mut self,
+        _: &mut EVMData<'_, DB>,
+        _: &CreateInputs,
+        result: InstructionResult,
+        address: Option<RevmAddress>,
+        remaining_gas: RevmGas,
+        output: RevmBytes,
+    ) -> (InstructionResult, Option<RevmAddress>, RevmGas, RevmBytes) {
+        self.exit_frame(result, &remaining_gas, (&output).into(), address.map_into());
+        (result, address, remaining_gas, output)
+    }
+
+    fn selfdestruct(&mut self, contract: RevmAddress, target: RevmAddress, value: U256) {
+        // self-destructs do not execute code, so they are entered and exited at the same time
+        self.enter_frame(
+            ExecutionTraceFrameKind::SelfDestruct,
+            contract.into(),
+            Some(target.into()),
+            value,
+            0,
+            Bytes::default(),
+        );
+        self.exit_frame(InstructionResult::SelfDestruct, &RevmGas::new(0), Bytes::default(), None);
+    }
 }
 
 // -----------------------------------------------------------------------------
 // Conversion
 // -----------------------------------------------------------------------------
 
+impl From<EvmHardfork> for SpecId {
+    fn from(value: EvmHardfork) -> Self {
+        match value {
+            EvmHardfork::London => SpecId::LONDON,
+            EvmHardfork::Paris => SpecId::MERGE,
+            EvmHardfork::Shanghai => SpecId::SHANGHAI,
+            EvmHardfork::Cancun => SpecId::CANCUN,
+        }
+    }
+}
+
 fn parse_revm_execution(
     revm_result: RevmResultAndState,
     execution_block_timestamp_in_secs: u64,
@@ -319,3 +651,61 @@ fn parse_revm_state(revm_state: RevmState, mut execution_changes: ExecutionChang
     }
     Ok(execution_changes)
 }
+
+fn parse_revm_frame_error(result: InstructionResult) -> Option<String> {
+    match result {
+        InstructionResult::Continue | InstructionResult::Stop | InstructionResult::Return | InstructionResult::SelfDestruct => None,
+        InstructionResult::Revert => Some("execution reverted".to_owned()),
+        result => Some(format!("{:?}", result)),
+    }
+}
+
+fn parse_revm_opcode(op: u8) -> String {
+    match opcode::OPCODE_JUMPMAP[op as usize] {
+        Some(name) => name.to_owned(),
+        None => format!("opcode {:#x} not defined", op),
+    }
+}
+
+fn revm_u256_to_h256(value: U256) -> H256 {
+    H256::from(value.to_be_bytes::<32>())
+}
+
+fn revm_u256_to_u256(value: U256) -> EthersU256 {
+    EthersU256::from_big_endian(&value.to_be_bytes::<32>())
+}
+
+// -----------------------------------------------------------------------------
+// Tests
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use tokio::runtime::Runtime;
+
+    use super::*;
+    use crate::eth::primitives::Genesis;
+    use crate::eth::storage::InMemoryStorage;
+
+    #[test]
+    fn code_by_hash_reads_deployed_bytecode() {
+        // sessions block on the runtime of the evm thread, so they must be used outside of async code
+        let runtime = Runtime::new().unwrap();
+        let _runtime_guard = runtime.enter();
+
+        let bytecode = Bytes::from(vec![0x60, 0x00, 0x60, 0x00, 0xf3]);
+        let account = Account {
+            address: Address::from([0x11; 20]),
+            bytecode: Some(bytecode.clone()),
+            ..Account::default()
+        };
+        let code_hash = account.code_hash();
+
+        let mut genesis = Genesis::default();
+        genesis.add_accounts(vec![account]);
+        let storage: Arc<dyn EthStorage> = Arc::new(InMemoryStorage::new(&genesis));
+        let mut session = RevmDatabaseSession::new(storage, StoragePointInTime::Present, Default::default(), None, BlockNumber::from(1u64), 0);
+
+        assert_eq!(session.code_by_hash(code_hash.into()).unwrap(), RevmBytecode::from(bytecode));
+        assert!(session.code_by_hash(B256::repeat_byte(0x22)).is_err());
+    }
+}
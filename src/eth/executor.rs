@@ -5,32 +5,122 @@
 //! `EthExecutor` is designed to work with the `Evm` trait implementations to execute transactions and calls,
 //! while also interfacing with a miner component to handle block mining and a storage component to persist state changes.
 
+use std::collections::HashSet;
+use std::panic;
+use std::panic::AssertUnwindSafe;
 use std::sync::Arc;
 use std::thread;
+use std::time::Duration;
+use std::time::Instant;
 
 use anyhow::anyhow;
+use ethereum_types::U256;
+use futures::future::join_all;
+use itertools::Itertools;
 use nonempty::NonEmpty;
 use tokio::runtime::Handle;
 use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::oneshot;
 use tokio::sync::Mutex;
+use tokio::sync::RwLock;
+use tokio::time::MissedTickBehavior;
 
+use crate::config::BlockMode;
+use crate::config::Config;
+use crate::config::EvmHardfork;
+use crate::config::MAX_TRANSACTION_INPUT_SIZE;
 use crate::eth::evm::Evm;
 use crate::eth::evm::EvmInput;
 use crate::eth::miner::BlockMiner;
+use crate::eth::miner::Clock;
+use crate::eth::miner::OffsetClock;
+use crate::eth::primitives::Account;
+use crate::eth::primitives::Address;
 use crate::eth::primitives::Block;
+use crate::eth::primitives::BlockHeader;
+use crate::eth::primitives::BlockNumber;
+use crate::eth::primitives::BlockSelection;
+use crate::eth::primitives::Bytes;
 use crate::eth::primitives::CallInput;
+use crate::eth::primitives::ChainId;
 use crate::eth::primitives::Execution;
+use crate::eth::primitives::ExecutionAccountChanges;
+use crate::eth::primitives::ExecutionConflicts;
+use crate::eth::primitives::ExecutionResult;
+use crate::eth::primitives::ExecutionTrace;
+use crate::eth::primitives::ExecutionValueChange;
+use crate::eth::primitives::FeeHistory;
+use crate::eth::primitives::FeePolicy;
+use crate::eth::primitives::Gas;
+use crate::eth::primitives::Hash;
 use crate::eth::primitives::LogMined;
+use crate::eth::primitives::Nonce;
+use crate::eth::primitives::Slot;
+use crate::eth::primitives::StateOverlay;
 use crate::eth::primitives::StoragePointInTime;
+use crate::eth::primitives::Tracer;
 use crate::eth::primitives::TransactionInput;
+use crate::eth::primitives::TransactionMined;
+use crate::eth::primitives::Wei;
 use crate::eth::storage::EthStorage;
 use crate::eth::storage::EthStorageError;
+use crate::eth::txpool::TransactionPool;
+use crate::eth::EthExecutorError;
+use crate::ext::not;
+use crate::if_else;
+use crate::infra::metrics;
 
 /// Number of events in the backlog.
 const NOTIFIER_CAPACITY: usize = u16::MAX as usize;
 
-type EvmTask = (EvmInput, oneshot::Sender<anyhow::Result<Execution>>);
+/// Maximum number of blocks returned by a fee history request.
+const FEE_HISTORY_MAX_BLOCKS: u64 = 1024;
+
+/// Percentile of the priority fees paid in the latest block suggested to new transactions.
+const SUGGESTED_PRIORITY_FEE_PERCENTILE: f64 = 50.0;
+
+/// Gas forwarded for free to the callee of a call that transfers value.
+const CALL_STIPEND: u64 = 2_300;
+
+/// Relative difference between the bounds of the gas estimation search below which the upper bound is returned, the same used by geth.
+const ESTIMATE_GAS_ERROR_RATIO: f64 = 0.015;
+
+/// Task sent to background EVMs.
+enum EvmTask {
+    /// Execute a transaction or call.
+    Execute(EvmInput, oneshot::Sender<anyhow::Result<Execution>>),
+
+    /// Execute a transaction or call collecting its trace.
+    Trace(EvmInput, Tracer, oneshot::Sender<anyhow::Result<(Execution, ExecutionTrace)>>),
+}
+
+/// State restored when reverting to a snapshot taken by development methods.
+struct Snapshot {
+    block_number: BlockNumber,
+    clock_offset_in_secs: i64,
+    next_block_timestamp_in_secs: Option<u64>,
+}
+
+/// Transaction waiting to be executed and mined in the next block when mining blocks periodically.
+struct PendingTransaction {
+    input: TransactionInput,
+
+    /// Sends the execution result after the block is mined.
+    execution_tx: oneshot::Sender<anyhow::Result<Execution>>,
+}
+
+/// Changes to an account applied by development methods. Fields that are not set keep their current values.
+#[derive(Debug, Clone, Default)]
+pub struct AccountOverride {
+    pub nonce: Option<Nonce>,
+    pub balance: Option<Wei>,
+
+    /// New bytecode of the account, or `Some(None)` to remove it.
+    pub bytecode: Option<Option<Bytes>>,
+
+    pub slots: Vec<Slot>,
+}
 
 /// The EthExecutor struct is responsible for orchestrating the execution of Ethereum transactions.
 /// It holds references to the EVM, block miner, and storage, managing the overall process of
@@ -39,8 +129,40 @@ pub struct EthExecutor {
     // Channel to send transactions to background EVMs.
     evm_tx: crossbeam_channel::Sender<EvmTask>,
 
-    // Mutex-wrapped miner for creating new blockchain blocks.
-    miner: Mutex<BlockMiner>,
+    // Miner for creating new blockchain blocks, and the lock held while a block is mined and saved, so blocks are mined one at a time.
+    // The miner itself has no state, so pending blocks can be built without waiting for the lock.
+    miner: BlockMiner,
+    mining_lock: Mutex<()>,
+
+    // Mutex-wrapped pool of transactions that are not mined yet.
+    txpool: Mutex<TransactionPool>,
+
+    // How blocks are produced and the transactions waiting for the next block when mining blocks periodically.
+    block_mode: BlockMode,
+    block_max_transactions: usize,
+    block_gas_limit: Gas,
+    pending_block: Mutex<Vec<PendingTransaction>>,
+
+    // Chain transactions must be signed for.
+    chain_id: ChainId,
+
+    // Policy used to calculate the base fee of the next block.
+    fee_policy: FeePolicy,
+
+    // Whether senders pay for the gas they use, which limits the gas calls can afford when estimating gas.
+    charge_gas: bool,
+
+    // Hardfork whose rules the intrinsic gas of transactions and calls follows.
+    evm_hardfork: EvmHardfork,
+
+    // Limits for executing transactions and read-only calls.
+    transaction_gas_limit: Gas,
+    initcode_size_limit: Option<usize>,
+    call_gas_cap: Gas,
+
+    // Limits for executing a transaction again after conflicting with concurrent transactions.
+    conflict_max_retries: usize,
+    conflict_timeout: Duration,
 
     // Shared storage backend for persisting blockchain state.
     eth_storage: Arc<dyn EthStorage>,
@@ -48,20 +170,104 @@ pub struct EthExecutor {
     // Broadcast channels for notifying subscribers about new blocks and logs.
     block_notifier: broadcast::Sender<Block>,
     log_notifier: broadcast::Sender<LogMined>,
+
+    // Broadcast channel for notifying the queue promoter about senders whose queued transactions may have become executable.
+    promotion_notifier: broadcast::Sender<Address>,
+
+    // Accounts allowed to send unsigned transactions. Only changed by development methods.
+    impersonated_accounts: RwLock<HashSet<Address>>,
+
+    // Clock used to compute block timestamps. Only moved by development methods.
+    clock: OffsetClock,
+
+    // Timestamp forced for the next mined block. Only changed by development methods.
+    next_block_timestamp_in_secs: Mutex<Option<u64>>,
+
+    // Snapshots that can be reverted to, in the order they were taken. Only changed by development methods.
+    snapshots: Mutex<Vec<Snapshot>>,
 }
 
 impl EthExecutor {
     /// Creates a new executor.
-    pub fn new(evms: NonEmpty<Box<dyn Evm>>, eth_storage: Arc<dyn EthStorage>) -> Self {
+    pub fn new(evms: NonEmpty<Box<dyn Evm>>, eth_storage: Arc<dyn EthStorage>, clock: Arc<dyn Clock>, config: &Config) -> Self {
         let evm_tx = spawn_background_evms(evms);
 
         Self {
             evm_tx,
-            miner: Mutex::new(BlockMiner::new(Arc::clone(&eth_storage))),
+            miner: BlockMiner::new(Arc::clone(&eth_storage), config.block_gas_limit.into(), config.fee_policy(), config.coinbase()),
+            mining_lock: Default::default(),
+            txpool: Mutex::new(TransactionPool::new(config.txpool_limits())),
+            block_mode: config.block_mode,
+            block_max_transactions: config.block_max_transactions,
+            block_gas_limit: config.block_gas_limit.into(),
+            pending_block: Default::default(),
+            chain_id: config.chain_id.into(),
+            fee_policy: config.fee_policy(),
+            charge_gas: config.charge_gas,
+            evm_hardfork: config.evm_hardfork,
+            transaction_gas_limit: config.transaction_gas_limit.into(),
+            initcode_size_limit: config.initcode_size_limit(),
+            call_gas_cap: config.call_gas_cap.into(),
+            conflict_max_retries: config.conflict_max_retries,
+            conflict_timeout: config.conflict_timeout,
             eth_storage,
             block_notifier: broadcast::channel(NOTIFIER_CAPACITY).0,
             log_notifier: broadcast::channel(NOTIFIER_CAPACITY).0,
+            promotion_notifier: broadcast::channel(NOTIFIER_CAPACITY).0,
+            impersonated_accounts: Default::default(),
+            clock: OffsetClock::new(clock),
+            next_block_timestamp_in_secs: Default::default(),
+            snapshots: Default::default(),
+        }
+    }
+
+    /// Submits a transaction to the transaction pool.
+    ///
+    /// A transaction sent with the nonce expected by its sender is executed right away, and the queued transactions of the same sender
+    /// that became executable are released in the background by the queue promoter. A transaction sent with a future nonce is queued
+    /// until the nonce gap is filled, in which case no execution is returned.
+    pub async fn submit(&self, transaction: TransactionInput) -> anyhow::Result<Option<Execution>> {
+        validate(
+            &transaction,
+            &self.chain_id,
+            self.evm_hardfork,
+            &self.transaction_gas_limit,
+            self.initcode_size_limit,
+        )?;
+
+        // queue transaction if there is a nonce gap
+        // txpool stays locked while reading the nonce, so concurrent promotions do not miss the queued transaction
+        {
+            let mut txpool_lock = self.txpool.lock().await;
+            let account = self.eth_storage.read_account(&transaction.signer, &StoragePointInTime::Present).await?;
+            let expected_nonce = self.executable_nonce(&txpool_lock, &account);
+            if transaction.nonce > expected_nonce {
+                tracing::info!(
+                    hash = %transaction.hash,
+                    nonce = %transaction.nonce,
+                    %expected_nonce,
+                    signer = %transaction.signer,
+                    "queueing transaction with nonce gap"
+                );
+                txpool_lock.add_queued(transaction, &account.nonce, Instant::now())?;
+                return Ok(None);
+            }
+        }
+
+        // execute transaction and release the ones waiting for it without waiting for them
+        let execution_rx = self.accept(&transaction).await?;
+        let _ = self.promotion_notifier.send(transaction.signer.clone());
+
+        Ok(Some(receive_execution(execution_rx).await?))
+    }
+
+    /// Submits a transaction sent by an impersonated account without requiring it to be signed.
+    pub async fn submit_impersonated(&self, transaction: TransactionInput) -> anyhow::Result<Option<Execution>> {
+        if not(self.impersonated_accounts.read().await.contains(&transaction.signer)) {
+            tracing::warn!(signer = %transaction.signer, "rejecting unsigned transaction from account that is not impersonated");
+            return Err(anyhow!("Account {} is not impersonated.", transaction.signer));
         }
+        self.submit(transaction).await
     }
 
     /// Executes Ethereum transactions and facilitates block creation.
@@ -72,8 +278,20 @@ impl EthExecutor {
     /// processing itself. This method encapsulates the execution, block mining, and state mutation,
     /// concluding with broadcasting necessary notifications for the newly created block and associated transaction logs.
     ///
+    /// When blocks are mined by interval, the transaction is not mined right away and waits until the next block is produced.
+    /// In both modes, the transaction is tracked as pending in the transaction pool until its block is mined.
+    ///
     /// TODO: too much cloning that can be optimized here.
     pub async fn transact(&self, transaction: TransactionInput) -> anyhow::Result<Execution> {
+        let execution_rx = self.accept(&transaction).await?;
+        receive_execution(execution_rx).await
+    }
+
+    /// Validates a transaction and sends it to be executed and mined, returning where its execution is received after it is mined.
+    ///
+    /// In automine mode, the transaction is mined before returning. In interval mode, it is added to the block the interval miner produces
+    /// next, and it stays pending in the transaction pool until that block is mined.
+    async fn accept(&self, transaction: &TransactionInput) -> anyhow::Result<oneshot::Receiver<anyhow::Result<Execution>>> {
         tracing::info!(
             hash = %transaction.hash,
             nonce = %transaction.nonce,
@@ -86,50 +304,570 @@ impl EthExecutor {
         );
 
         // validate
-        if transaction.signer.is_zero() {
-            tracing::warn!("rejecting transaction from zero address");
-            return Err(anyhow!("Transaction sent from zero address is not allowed."));
+        validate(
+            transaction,
+            &self.chain_id,
+            self.evm_hardfork,
+            &self.transaction_gas_limit,
+            self.initcode_size_limit,
+        )?;
+        validate_fee_cap(transaction, &self.next_base_fee().await?)?;
+
+        // validate against the sender and track the transaction as pending in the same txpool lock, so the next transaction of the
+        // sender sees it as pending only after it is in the pending block
+        let mut txpool_lock = self.txpool.lock().await;
+        let account = self.eth_storage.read_account(&transaction.signer, &StoragePointInTime::Present).await?;
+        validate_account(transaction, &account, &self.executable_nonce(&txpool_lock, &account), self.charge_gas)?;
+        txpool_lock.add_pending(transaction.clone());
+
+        // execute and mine while the transaction is pending
+        let (execution_tx, execution_rx) = oneshot::channel();
+        match self.block_mode {
+            BlockMode::Automine => {
+                drop(txpool_lock);
+                let result = self.execute_and_mine(transaction).await;
+                self.txpool.lock().await.remove_pending(transaction);
+                let _ = execution_tx.send(Ok(result?));
+            }
+            BlockMode::Interval(_) => self.pending_block.lock().await.push(PendingTransaction {
+                input: transaction.clone(),
+                execution_tx,
+            }),
         }
+        Ok(execution_rx)
+    }
+
+    /// Executes a transaction and mines it in a new block.
+    ///
+    /// The transaction is mined with the same header it was executed with. If another block is mined in the meantime, the header is built
+    /// again and the transaction is executed again, so transactions always see the context of the block they are mined in.
+    async fn execute_and_mine(&self, transaction: &TransactionInput) -> anyhow::Result<Execution> {
+        // execute transaction until no more conflicts or until the conflict limits are reached
+        let timestamp_in_secs = self.next_block_timestamp().await;
+        let started_at = Instant::now();
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
 
-        // execute transaction until no more conflicts
-        // todo: must have a stop condition like timeout or max number of retries
-        let (execution, block) = loop {
             // execute and check conflicts before mining block
-            let execution = self.execute_in_evm(transaction.clone().try_into()?).await?;
+            let pending_block = self.read_pending_block_at(timestamp_in_secs).await?;
+            let execution = self.execute_transaction_in_evm(transaction, &pending_block.header, Default::default()).await?;
             if let Some(conflicts) = self.eth_storage.check_conflicts(&execution).await? {
-                tracing::warn!(?conflicts, "storage conflict detected before mining block");
+                tracing::warn!(?conflicts, %attempt, "storage conflict detected before mining block");
+                self.check_conflict_retry(&conflicts, attempt, started_at)?;
                 continue;
             }
 
-            // mine and save block
-            let mut miner_lock = self.miner.lock().await;
-            let block = miner_lock.mine_with_one_transaction(transaction.clone(), execution.clone()).await?;
-            match self.eth_storage.save_block(block.clone()).await {
-                Ok(()) => {}
+            // mine and save block if no other block was mined since the transaction was executed
+            let mining_lock = self.mining_lock.lock().await;
+            if not(self.miner.is_pending(&pending_block.header).await?) {
+                tracing::warn!(number = %pending_block.number(), %attempt, "block mined while executing transaction");
+                self.check_retry(attempt, started_at)?;
+                continue;
+            }
+            let block = self
+                .miner
+                .mine_with_one_transaction(pending_block.header, transaction.clone(), execution.clone())
+                .await?;
+            let block = match self.miner.save(block).await {
+                Ok(block) => block,
                 Err(EthStorageError::Conflict(conflicts)) => {
-                    tracing::warn!(?conflicts, "storage conflict detected when saving block");
+                    tracing::warn!(?conflicts, %attempt, "storage conflict detected when saving block");
+                    self.check_conflict_retry(&conflicts, attempt, started_at)?;
                     continue;
                 }
                 Err(e) => return Err(e.into()),
             };
-            break (execution, block);
+            drop(mining_lock);
+
+            self.notify(block);
+            return Ok(execution);
+        }
+    }
+
+    /// Spawns a new thread that mines blocks periodically when the block mode is interval.
+    ///
+    /// Empty blocks are mined when there are no transactions, so block timestamps keep advancing.
+    pub fn spawn_interval_miner(self: Arc<Self>) {
+        let BlockMode::Interval(interval) = self.block_mode else {
+            return;
         };
+        tracing::info!(?interval, max_transactions = %self.block_max_transactions, "starting interval miner");
 
-        // notify new blocks
-        if let Err(e) = self.block_notifier.send(block.clone()) {
-            tracing::error!(reason = ?e, "failed to send block notification");
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.mine_pending_block().await {
+                    tracing::error!(reason = ?e, "failed to mine block by interval");
+                }
+            }
+        });
+    }
+
+    /// Executes and mines the transactions waiting for the next block, or mines an empty block if there are none.
+    async fn mine_pending_block(&self) -> anyhow::Result<()> {
+        // take transactions that fit in the block by number and by the gas they can use, like geth does
+        // the first transaction is always taken, so a transaction above the block gas limit does not stall the ones after it
+        let pending_transactions = {
+            let mut pending_block_lock = self.pending_block.lock().await;
+            let mut block_gas = Gas::ZERO;
+            let mut block_len = 0;
+            for pending in pending_block_lock.iter().take(self.block_max_transactions) {
+                let next_block_gas = block_gas.clone() + pending.input.gas.clone();
+                if block_len > 0 && next_block_gas > self.block_gas_limit {
+                    break;
+                }
+                block_gas = next_block_gas;
+                block_len += 1;
+            }
+            pending_block_lock.drain(..block_len).collect_vec()
         };
+        if pending_transactions.is_empty() {
+            self.mine_empty_block(None).await?;
+            return Ok(());
+        }
 
-        // notify transaction logs
-        for trx in block.transactions {
-            for log in trx.logs {
-                if let Err(e) = self.log_notifier.send(log) {
-                    tracing::error!(reason = ?e, "failed to send log notification");
+        let (transactions, execution_txs): (Vec<_>, Vec<_>) = pending_transactions.into_iter().map(|pending| (pending.input, pending.execution_tx)).unzip();
+        tracing::info!(transactions = %transactions.len(), "mining block with pending transactions");
+        let result = self.mine_transactions(&transactions).await;
+
+        // release transactions waiting for the block, which are not pending anymore whether they were mined or not
+        {
+            let mut txpool_lock = self.txpool.lock().await;
+            for transaction in &transactions {
+                txpool_lock.remove_pending(transaction);
+            }
+        }
+        match result {
+            Ok((block, results)) => {
+                tracing::info!(number = %block.number(), transactions = %block.transactions.len(), "mined block with pending transactions");
+                for (execution_tx, result) in execution_txs.into_iter().zip(results) {
+                    let _ = execution_tx.send(result);
+                }
+                Ok(())
+            }
+            Err(e) => {
+                // executor errors are sent to the transactions, while other errors drop them
+                if let Some(executor_error) = e.downcast_ref::<EthExecutorError>() {
+                    for execution_tx in execution_txs {
+                        let _ = execution_tx.send(Err(executor_error.clone().into()));
+                    }
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Executes and mines transactions in a new block, returning the block and the result of each transaction.
+    ///
+    /// Transactions that fail to execute are not mined. If no transaction is executed, an empty block is mined instead.
+    async fn mine_transactions(&self, transactions: &[TransactionInput]) -> anyhow::Result<(Block, Vec<anyhow::Result<Execution>>)> {
+        // execute and mine until the block is saved without conflicts or until the conflict limits are reached
+        // transactions are executed again when another block is mined meanwhile, so they are mined with the header they were executed with
+        let timestamp_in_secs = self.next_block_timestamp().await;
+        let started_at = Instant::now();
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let pending_block = self.read_pending_block_at(timestamp_in_secs).await?;
+            let results = self.execute_batch(transactions, &pending_block.header).await;
+
+            // transactions that failed to execute are not mined
+            let executed = transactions
+                .iter()
+                .zip(&results)
+                .filter_map(|(transaction, result)| result.as_ref().ok().map(|execution| (transaction.clone(), execution.clone())))
+                .collect_vec();
+            let Some(executed) = NonEmpty::from_vec(executed) else {
+                return Ok((self.mine_empty_block(Some(timestamp_in_secs)).await?, results));
+            };
+
+            let mining_lock = self.mining_lock.lock().await;
+            if not(self.miner.is_pending(&pending_block.header).await?) {
+                tracing::warn!(number = %pending_block.number(), %attempt, "block mined while executing pending transactions");
+                self.check_retry(attempt, started_at)?;
+                continue;
+            }
+            let block = self.miner.mine_with_many_transactions(pending_block.header, executed).await?;
+
+            // gas used never exceeds the gas taken, but the limit is checked again before the block is persisted
+            if block.header.gas > block.header.gas_limit {
+                tracing::error!(gas = %block.header.gas, gas_limit = %block.header.gas_limit, "mined block uses more gas than the block gas limit");
+                return Err(EthExecutorError::BlockGasLimitExceeded {
+                    have: block.header.gas.clone(),
+                    limit: block.header.gas_limit.clone(),
+                }
+                .into());
+            }
+
+            let block = match self.miner.save(block).await {
+                Ok(block) => block,
+                Err(EthStorageError::Conflict(conflicts)) => {
+                    tracing::warn!(?conflicts, %attempt, "storage conflict detected when saving block");
+                    self.check_conflict_retry(&conflicts, attempt, started_at)?;
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
+            drop(mining_lock);
+
+            self.notify(block.clone());
+            return Ok((block, results));
+        }
+    }
+
+    /// Executes transactions of the same block in parallel, producing the same results as executing them serially in order.
+    ///
+    /// All transactions are executed speculatively on top of the current state. Then, in order, each execution is validated against the
+    /// changes of the previous transactions, and only the ones that read values modified by them are executed again on top of those changes.
+    async fn execute_batch(&self, transactions: &[TransactionInput], block: &BlockHeader) -> Vec<anyhow::Result<Execution>> {
+        // execute all transactions in parallel
+        let speculative_executions = transactions
+            .iter()
+            .map(|transaction| self.execute_transaction_in_evm(transaction, block, Default::default()));
+        let mut results = join_all(speculative_executions).await;
+
+        // validate in order, executing again the transactions that conflict with previous ones
+        let mut state_overlay = StateOverlay::default();
+        for (transaction, result) in transactions.iter().zip(results.iter_mut()) {
+            let conflicts = match result {
+                Ok(execution) => state_overlay.check_conflicts(execution),
+                Err(_) => None,
+            };
+            if let Some(ref conflicts) = conflicts {
+                record_conflicts(conflicts);
+            }
+
+            // failures may also be caused by state modified by previous transactions, like nonces
+            let failed_on_stale_state = result.is_err() && not(state_overlay.is_empty());
+            if conflicts.is_some() || failed_on_stale_state {
+                tracing::info!(hash = %transaction.hash, ?conflicts, "executing transaction again on top of previous transactions of the block");
+                *result = self.execute_transaction_in_evm(transaction, block, Arc::new(state_overlay.clone())).await;
+            }
+
+            if let Ok(execution) = result {
+                state_overlay.apply(execution);
+            }
+        }
+
+        results
+    }
+
+    /// Submits a transaction to the EVM to be executed in the given block on top of the storage state and the given overlay.
+    ///
+    /// The transaction pays the effective gas price of the block it will be mined in, if gas is charged.
+    async fn execute_transaction_in_evm(
+        &self,
+        transaction: &TransactionInput,
+        block: &BlockHeader,
+        state_overlay: Arc<StateOverlay>,
+    ) -> anyhow::Result<Execution> {
+        let mut evm_input: EvmInput = transaction.clone().try_into()?;
+        evm_input.block = Some(block.clone());
+        evm_input.state_overlay = state_overlay;
+        self.execute_in_evm(evm_input).await
+    }
+
+    /// Records conflicts that prevented a transaction from being mined and checks if it can be executed again.
+    fn check_conflict_retry(&self, conflicts: &ExecutionConflicts, attempt: usize, started_at: Instant) -> Result<(), EthExecutorError> {
+        record_conflicts(conflicts);
+        self.check_retry(attempt, started_at)
+    }
+
+    /// Checks if a transaction can be executed again after conflicting with concurrent transactions or with a concurrently mined block.
+    ///
+    /// Fails when the maximum number of attempts or the timeout is reached, so clients can back off instead of waiting forever on hot state.
+    fn check_retry(&self, attempt: usize, started_at: Instant) -> Result<(), EthExecutorError> {
+        if attempt > self.conflict_max_retries {
+            tracing::error!(%attempt, "giving up executing transaction after too many conflicts");
+            return Err(EthExecutorError::ConflictMaxRetries(attempt));
+        }
+        if started_at.elapsed() > self.conflict_timeout {
+            tracing::error!(%attempt, timeout = ?self.conflict_timeout, "giving up executing transaction after conflicts timeout");
+            return Err(EthExecutorError::ConflictTimeout(self.conflict_timeout));
+        }
+        Ok(())
+    }
+
+    /// Spawns a task that executes queued transactions once the transactions before them are executed.
+    ///
+    /// Queued transactions of a sender are promoted after each transaction the sender submits and after each mined block, so promotions
+    /// that failed are tried again and RPC requests do not wait for the transactions they release.
+    pub fn spawn_queue_promoter(self: Arc<Self>) {
+        let mut promotions = self.promotion_notifier.subscribe();
+        let mut blocks = self.block_notifier.subscribe();
+
+        tokio::spawn(async move {
+            loop {
+                let signers = tokio::select! {
+                    promotion = promotions.recv() => match promotion {
+                        Ok(signer) => vec![signer],
+                        Err(RecvError::Lagged(_)) => self.txpool.lock().await.queued_senders(),
+                        Err(RecvError::Closed) => return,
+                    },
+                    block = blocks.recv() => match block {
+                        Ok(_) | Err(RecvError::Lagged(_)) => self.txpool.lock().await.queued_senders(),
+                        Err(RecvError::Closed) => return,
+                    },
+                };
+                for signer in signers {
+                    self.promote_queued(&signer).await;
+                }
+            }
+        });
+    }
+
+    /// Executes queued transactions of the signer while their nonces are the ones that make them executable.
+    ///
+    /// Promoted transactions are not waited for after they are accepted, so transactions sent to the interval miner do not block the
+    /// promoter until their block is mined.
+    async fn promote_queued(&self, signer: &Address) {
+        loop {
+            let transaction = {
+                let mut txpool_lock = self.txpool.lock().await;
+                let account = match self.eth_storage.read_account(signer, &StoragePointInTime::Present).await {
+                    Ok(account) => account,
+                    Err(e) => {
+                        tracing::error!(reason = ?e, %signer, "failed to read account when promoting queued transactions");
+                        return;
+                    }
                 };
+                let executable_nonce = self.executable_nonce(&txpool_lock, &account);
+                match txpool_lock.take_queued(signer, &executable_nonce) {
+                    Some(transaction) => transaction,
+                    None => return,
+                }
+            };
+
+            tracing::info!(hash = %transaction.hash, nonce = %transaction.nonce, %signer, "promoting queued transaction");
+            if let Err(e) = self.accept(&transaction).await {
+                tracing::warn!(reason = ?e, %signer, "failed to execute promoted transaction");
+                return;
             }
         }
+    }
 
-        Ok(execution)
+    /// Mines a block without transactions using the given timestamp or the next block timestamp.
+    pub async fn mine_empty_block(&self, timestamp_in_secs: Option<u64>) -> anyhow::Result<Block> {
+        let timestamp_in_secs = match timestamp_in_secs {
+            Some(timestamp_in_secs) => timestamp_in_secs,
+            None => self.next_block_timestamp().await,
+        };
+        tracing::info!(%timestamp_in_secs, "mining empty block");
+
+        let block = {
+            let _mining_lock = self.mining_lock.lock().await;
+            let block = self.miner.mine_with_no_transactions(timestamp_in_secs).await?;
+            self.miner.save(block).await?
+        };
+
+        self.notify(block.clone());
+        Ok(block)
+    }
+
+    /// Overrides some fields of an account and some of its slots without executing a transaction.
+    ///
+    /// The changes are recorded in a new empty block, so the state root of the latest block always commits to the current state and
+    /// reverting to a snapshot taken before the changes discards them.
+    pub async fn override_account(&self, address: &Address, account_override: AccountOverride) -> anyhow::Result<Block> {
+        tracing::info!(
+            %address,
+            nonce = ?account_override.nonce,
+            balance = ?account_override.balance,
+            bytecode_changed = %account_override.bytecode.is_some(),
+            slots = %account_override.slots.len(),
+            "overriding account state"
+        );
+
+        let block = {
+            let _mining_lock = self.mining_lock.lock().await;
+
+            // account is read while mining is locked, so changes of transactions mined concurrently are not overwritten
+            let account = self.eth_storage.read_account(address, &StoragePointInTime::Present).await?;
+            let mut changes = ExecutionAccountChanges::from_existing_account(account);
+            let AccountOverride {
+                nonce,
+                balance,
+                bytecode,
+                slots,
+            } = account_override;
+            if let Some(nonce) = nonce {
+                changes.nonce.set_modified(nonce);
+            }
+            if let Some(balance) = balance {
+                changes.balance.set_modified(balance);
+            }
+            if let Some(bytecode) = bytecode {
+                changes.bytecode.set_modified(bytecode);
+            }
+            for slot in slots {
+                changes.slots.insert(slot.index.clone(), ExecutionValueChange::from_modified(slot));
+            }
+
+            let block = self.miner.mine_with_no_transactions(self.current_timestamp()).await?;
+            self.miner.save_with_overrides(block, vec![changes]).await?
+        };
+
+        self.notify(block.clone());
+        Ok(block)
+    }
+
+    /// Takes a snapshot of the chain and of the time used to compute block timestamps, returning its id.
+    pub async fn snapshot(&self) -> anyhow::Result<usize> {
+        // mining stays locked, so no block is being mined while the snapshot is taken
+        let _mining_lock = self.mining_lock.lock().await;
+        let snapshot = Snapshot {
+            block_number: self.eth_storage.read_current_block_number().await?,
+            clock_offset_in_secs: self.clock.offset(),
+            next_block_timestamp_in_secs: *self.next_block_timestamp_in_secs.lock().await,
+        };
+        tracing::info!(block_number = %snapshot.block_number, clock_offset_in_secs = %snapshot.clock_offset_in_secs, "taking snapshot");
+
+        let mut snapshots = self.snapshots.lock().await;
+        snapshots.push(snapshot);
+        Ok(snapshots.len())
+    }
+
+    /// Reverts the chain and the time used to compute block timestamps to a snapshot. Returns `false` if the snapshot does not exist.
+    ///
+    /// Reverting to a snapshot invalidates it and all snapshots taken after it.
+    pub async fn revert(&self, id: usize) -> anyhow::Result<bool> {
+        let _mining_lock = self.mining_lock.lock().await;
+        let mut snapshots = self.snapshots.lock().await;
+        if id == 0 || id > snapshots.len() {
+            tracing::warn!(%id, "cannot revert to unknown snapshot");
+            return Ok(false);
+        }
+        snapshots.truncate(id);
+        let Some(snapshot) = snapshots.pop() else { return Ok(false) };
+        tracing::info!(%id, block_number = %snapshot.block_number, "reverting to snapshot");
+
+        self.eth_storage.reset(snapshot.block_number).await?;
+        self.clock.set_offset(snapshot.clock_offset_in_secs);
+        *self.next_block_timestamp_in_secs.lock().await = snapshot.next_block_timestamp_in_secs;
+        Ok(true)
+    }
+
+    /// Allows an account to send unsigned transactions.
+    pub async fn impersonate_account(&self, address: Address) {
+        tracing::info!(%address, "impersonating account");
+        self.impersonated_accounts.write().await.insert(address);
+    }
+
+    /// Stops allowing an account to send unsigned transactions. Returns `true` if the account was impersonated.
+    pub async fn stop_impersonating_account(&self, address: &Address) -> bool {
+        tracing::info!(%address, "stopping impersonating account");
+        self.impersonated_accounts.write().await.remove(address)
+    }
+
+    /// Moves forward the time used to compute block timestamps, returning the total offset in seconds.
+    pub fn increase_time(&self, secs: u64) -> i64 {
+        self.clock.increase(secs)
+    }
+
+    /// Forces the timestamp of the next mined block. Blocks mined after it continue counting from it.
+    pub async fn set_next_block_timestamp(&self, timestamp_in_secs: u64) {
+        self.clock.set(timestamp_in_secs);
+        *self.next_block_timestamp_in_secs.lock().await = Some(timestamp_in_secs);
+    }
+
+    /// Nonce a transaction of the account needs to be executed right away instead of being queued.
+    ///
+    /// When blocks are mined by interval, transactions following the pending ones of the same sender are executed after them in the same
+    /// block. When blocks are automined, each transaction is mined in its own block, so the previous ones must be mined first.
+    fn executable_nonce(&self, txpool: &TransactionPool, account: &Account) -> Nonce {
+        match self.block_mode {
+            BlockMode::Automine => account.nonce.clone(),
+            BlockMode::Interval(_) => txpool.executable_nonce(&account.address, account.nonce.clone()),
+        }
+    }
+
+    /// Reads the nonce the next transaction of the account should use, considering transactions that are not mined yet.
+    pub async fn read_pending_nonce(&self, address: &Address) -> anyhow::Result<Nonce> {
+        let txpool_lock = self.txpool.lock().await;
+        let account = self.eth_storage.read_account(address, &StoragePointInTime::Present).await?;
+        Ok(txpool_lock.pending_nonce(address, account.nonce))
+    }
+
+    /// Reads the header of the block that will be mined next.
+    ///
+    /// Transactions waiting to be mined are not included, they can be read from the transaction pool.
+    pub async fn read_pending_block(&self) -> anyhow::Result<Block> {
+        self.read_pending_block_at(self.current_timestamp()).await
+    }
+
+    /// Builds the block that will be mined next with the given timestamp.
+    async fn read_pending_block_at(&self, timestamp_in_secs: u64) -> anyhow::Result<Block> {
+        self.miner.pending_block(timestamp_in_secs).await
+    }
+
+    /// Reads the header of the block used as context by executions on top of the state at the given point-in-time.
+    ///
+    /// The current state is executed in the pending block, and past states are executed in the selected block as the chain saw it.
+    async fn read_context_block(&self, point_in_time: &StoragePointInTime) -> anyhow::Result<BlockHeader> {
+        match point_in_time {
+            StoragePointInTime::Present => Ok(self.read_pending_block().await?.header),
+            StoragePointInTime::Past(number) => match self.eth_storage.read_block(&BlockSelection::Number(*number)).await? {
+                Some(block) => Ok(block.header),
+                None => Err(anyhow!("Block {} was expected to be mined, but it was not.", number)),
+            },
+        }
+    }
+
+    /// Maximum gas a single transaction can use.
+    pub fn transaction_gas_limit(&self) -> Gas {
+        self.transaction_gas_limit.clone()
+    }
+
+    /// Calculates the base fee of the block that will be mined next.
+    pub async fn next_base_fee(&self) -> anyhow::Result<Wei> {
+        let latest = self.eth_storage.read_block(&BlockSelection::Latest).await?;
+        Ok(self.fee_policy.base_fee(latest.as_ref().map(|block| &block.header)))
+    }
+
+    /// Suggests the priority fee of new dynamic fee transactions, based on the priority fees paid in the latest block.
+    pub async fn suggest_priority_fee(&self) -> anyhow::Result<Wei> {
+        let Some(latest) = self.eth_storage.read_block(&BlockSelection::Latest).await? else {
+            return Ok(Wei::ZERO);
+        };
+        let history = FeeHistory::new(&[latest], Wei::ZERO, &[SUGGESTED_PRIORITY_FEE_PERCENTILE]);
+        Ok(history.rewards.into_iter().flatten().next().unwrap_or_default())
+    }
+
+    /// Reads the fees paid in up to `block_count` blocks ending at the `newest` block.
+    ///
+    /// The number of blocks is limited to 1024, like other Ethereum nodes do.
+    pub async fn read_fee_history(&self, block_count: u64, newest: BlockNumber, reward_percentiles: &[f64]) -> anyhow::Result<FeeHistory> {
+        let newest = u64::from(newest);
+        let block_count = block_count.min(FEE_HISTORY_MAX_BLOCKS).min(newest + 1);
+
+        let mut blocks = Vec::with_capacity(block_count as usize);
+        for number in (newest + 1 - block_count)..=newest {
+            match self.eth_storage.read_block(&BlockSelection::Number(number.into())).await? {
+                Some(block) => blocks.push(block),
+                None => return Err(anyhow!("Block {} not found.", number)),
+            }
+        }
+
+        // base fee after the newest block is calculated as if the next block was mined now
+        let next_base_fee = self.fee_policy.base_fee(blocks.last().map(|block| &block.header));
+        Ok(FeeHistory::new(&blocks, next_base_fee, reward_percentiles))
+    }
+
+    /// Reads a transaction that is in the transaction pool.
+    pub async fn read_pool_transaction(&self, hash: &Hash) -> Option<TransactionInput> {
+        let mut txpool_lock = self.txpool.lock().await;
+        txpool_lock.remove_expired(Instant::now());
+        txpool_lock.find(hash).cloned()
+    }
+
+    /// Reads a copy of the transaction pool.
+    pub async fn read_pool(&self) -> TransactionPool {
+        let mut txpool_lock = self.txpool.lock().await;
+        txpool_lock.remove_expired(Instant::now());
+        txpool_lock.clone()
     }
 
     /// Execute a function and return the function output. State changes are ignored.
@@ -142,27 +880,423 @@ impl EthExecutor {
             "executing read-only transaction"
         );
 
-        let execution = self.execute_in_evm((input, point_in_time).into()).await?;
+        validate_call(&input, self.evm_hardfork)?;
+        self.validate_call_nonce(&input, &point_in_time).await?;
+        let block = self.read_context_block(&point_in_time).await?;
+        let gas_limit = self.call_gas_limit(&input);
+        let mut evm_input: EvmInput = (input, point_in_time).into();
+        evm_input.gas_limit = gas_limit;
+        evm_input.block = Some(block);
+        let execution = self.execute_in_evm(evm_input).await?;
         Ok(execution)
     }
 
+    /// Estimates the gas a call needs to succeed, searching for the lowest gas limit it succeeds with like geth does.
+    ///
+    /// The gas used by a successful execution is not enough for calls that depend on the gas available, like calls subject to the 63/64
+    /// rule, calls with gas refunds or contracts that check the remaining gas.
+    ///
+    /// When gas is charged, the search is limited to the gas the sender can pay for at the gas price or maximum fee of the call.
+    pub async fn estimate_gas(&self, input: CallInput, point_in_time: StoragePointInTime) -> anyhow::Result<Gas> {
+        tracing::info!(from = %input.from, to = ?input.to, ?point_in_time, "estimating gas");
+
+        // the call needs more than the intrinsic gas and at most the gas it specifies or the call gas cap
+        let mut lo = u64::from(input.intrinsic_gas(self.evm_hardfork)).saturating_sub(1);
+        let mut hi = u64::from(self.call_gas_limit(&input));
+
+        // when gas is charged, the call cannot use more gas than the sender can pay for at the fee cap it specifies
+        let fee_cap = input
+            .gas_price
+            .as_ref()
+            .or(input.max_fee_per_gas.as_ref())
+            .map(|fee_cap| U256::from(fee_cap.clone()));
+        if let Some(fee_cap) = fee_cap.filter(|fee_cap| self.charge_gas && not(fee_cap.is_zero())) {
+            let balance = U256::from(self.eth_storage.read_account(&input.from, &point_in_time).await?.balance);
+            let value = U256::from(input.value.clone());
+            if value >= balance {
+                tracing::warn!(from = %input.from, %value, %balance, "rejecting gas estimation with value not covered by balance");
+                return Err(EthExecutorError::InsufficientFundsForTransfer.into());
+            }
+            let allowance = (balance - value) / fee_cap;
+            if allowance < U256::from(hi) {
+                tracing::info!(from = %input.from, %allowance, cap = %hi, "gas estimation capped by limited funds");
+                hi = allowance.as_u64();
+            }
+        }
+
+        // if the call fails with the highest gas limit, it fails with any gas limit
+        let execution = self.call_with_gas_limit(&input, &point_in_time, hi).await?;
+        if not(execution.is_success()) {
+            return Err(estimate_gas_failure(execution, hi).into());
+        }
+
+        // the gas used is a lower bound, and most calls succeed with the gas used plus the gas retained by the 63/64 rule
+        let gas_used = u64::from(execution.gas);
+        lo = lo.max(gas_used.saturating_sub(1));
+        let optimistic = (gas_used + CALL_STIPEND) * 64 / 63;
+        if optimistic < hi {
+            if self.call_with_gas_limit(&input, &point_in_time, optimistic).await?.is_success() {
+                hi = optimistic;
+            } else {
+                lo = optimistic;
+            }
+        }
+
+        // binary search favoring lower limits, as the gas needed is usually close to the gas used
+        while lo + 1 < hi {
+            if ((hi - lo) as f64 / hi as f64) < ESTIMATE_GAS_ERROR_RATIO {
+                break;
+            }
+            let mid = (lo + (hi - lo) / 2).min(lo * 2);
+            if self.call_with_gas_limit(&input, &point_in_time, mid).await?.is_success() {
+                hi = mid;
+            } else {
+                lo = mid;
+            }
+        }
+
+        Ok(hi.into())
+    }
+
+    /// Executes a call with an explicit gas limit.
+    async fn call_with_gas_limit(&self, input: &CallInput, point_in_time: &StoragePointInTime, gas_limit: u64) -> anyhow::Result<Execution> {
+        let mut input = input.clone();
+        input.gas = Some(gas_limit.into());
+        self.call(input, point_in_time.clone()).await
+    }
+
+    /// Re-executes a mined transaction collecting its trace. State changes are ignored.
+    ///
+    /// The transaction is executed on top of the state of the previous block and of the changes of the transactions mined before it in
+    /// the same block, which is the state it was originally executed against.
+    pub async fn trace_transaction(&self, mined: &TransactionMined, tracer: Tracer) -> anyhow::Result<(Execution, ExecutionTrace)> {
+        tracing::info!(hash = %mined.input.hash, block_number = %mined.block_number, ?tracer, "tracing transaction");
+
+        let Some(block) = self.eth_storage.read_block(&BlockSelection::Number(mined.block_number)).await? else {
+            return Err(anyhow!("Block {} was expected to be mined, but it was not.", mined.block_number));
+        };
+
+        let mut state_overlay = StateOverlay::default();
+        for previous in block.transactions.iter().take_while(|previous| previous.input.hash != mined.input.hash) {
+            state_overlay.apply(&previous.execution);
+        }
+        self.trace_mined_transaction(mined, &block.header, Arc::new(state_overlay), tracer).await
+    }
+
+    /// Re-executes all transactions of a mined block collecting their traces, each one on top of the changes of the previous ones. State
+    /// changes are ignored.
+    pub async fn trace_block(&self, block: &Block, tracer: Tracer) -> anyhow::Result<Vec<(Execution, ExecutionTrace)>> {
+        tracing::info!(number = %block.number(), transactions = %block.transactions.len(), ?tracer, "tracing block");
+
+        let mut state_overlay = StateOverlay::default();
+        let mut traces = Vec::with_capacity(block.transactions.len());
+        for mined in &block.transactions {
+            let trace = self
+                .trace_mined_transaction(mined, &block.header, Arc::new(state_overlay.clone()), tracer.clone())
+                .await?;
+            traces.push(trace);
+            state_overlay.apply(&mined.execution);
+        }
+        Ok(traces)
+    }
+
+    /// Re-executes a mined transaction in its block on top of the state of the previous block and the given overlay.
+    async fn trace_mined_transaction(
+        &self,
+        mined: &TransactionMined,
+        block: &BlockHeader,
+        state_overlay: Arc<StateOverlay>,
+        tracer: Tracer,
+    ) -> anyhow::Result<(Execution, ExecutionTrace)> {
+        let Some(previous_block_number) = mined.block_number.predecessor() else {
+            return Err(anyhow!("Transactions in the genesis block cannot be traced."));
+        };
+
+        let mut evm_input: EvmInput = mined.input.clone().try_into()?;
+        evm_input.point_in_time = StoragePointInTime::Past(previous_block_number);
+        evm_input.block = Some(block.clone());
+        evm_input.state_overlay = state_overlay;
+        self.trace_in_evm(evm_input, tracer).await
+    }
+
+    /// Execute a function collecting its trace. State changes are ignored.
+    pub async fn trace_call(&self, input: CallInput, point_in_time: StoragePointInTime, tracer: Tracer) -> anyhow::Result<(Execution, ExecutionTrace)> {
+        tracing::info!(from = %input.from, to = ?input.to, ?point_in_time, ?tracer, "tracing read-only transaction");
+
+        validate_call(&input, self.evm_hardfork)?;
+        self.validate_call_nonce(&input, &point_in_time).await?;
+        let block = self.read_context_block(&point_in_time).await?;
+        let gas_limit = self.call_gas_limit(&input);
+        let mut evm_input: EvmInput = (input, point_in_time).into();
+        evm_input.gas_limit = gas_limit;
+        evm_input.block = Some(block);
+        self.trace_in_evm(evm_input, tracer).await
+    }
+
+    /// Validates the nonce of a call, if specified, against the nonce of its sender at the point-in-time the call is executed on.
+    ///
+    /// It is checked before executing, so a mismatch is reported with the same error of transactions instead of failing in the EVM.
+    async fn validate_call_nonce(&self, input: &CallInput, point_in_time: &StoragePointInTime) -> anyhow::Result<()> {
+        let Some(ref nonce) = input.nonce else {
+            return Ok(());
+        };
+        let account = self.eth_storage.read_account(&input.from, point_in_time).await?;
+        validate_nonce(nonce, &account, &account.nonce)?;
+        Ok(())
+    }
+
+    /// Gas a call can use: the gas it specifies limited by the call gas cap, or the cap itself when not specified.
+    fn call_gas_limit(&self, input: &CallInput) -> Gas {
+        match input.gas {
+            Some(ref gas) => gas.clone().min(self.call_gas_cap.clone()),
+            None => self.call_gas_cap.clone(),
+        }
+    }
+
     /// Submits a transaction to the EVM and awaits for its execution.
     async fn execute_in_evm(&self, evm_input: EvmInput) -> anyhow::Result<Execution> {
         let (execution_tx, execution_rx) = oneshot::channel::<anyhow::Result<Execution>>();
-        self.evm_tx.send((evm_input, execution_tx))?;
+        self.evm_tx.send(EvmTask::Execute(evm_input, execution_tx))?;
         execution_rx.await?
     }
 
-    /// Subscribe to new blocks events.
-    pub fn subscribe_to_new_heads(&self) -> broadcast::Receiver<Block> {
-        self.block_notifier.subscribe()
+    /// Submits a transaction to the EVM and awaits for its execution and trace.
+    async fn trace_in_evm(&self, evm_input: EvmInput, tracer: Tracer) -> anyhow::Result<(Execution, ExecutionTrace)> {
+        let (trace_tx, trace_rx) = oneshot::channel::<anyhow::Result<(Execution, ExecutionTrace)>>();
+        self.evm_tx.send(EvmTask::Trace(evm_input, tracer, trace_tx))?;
+        trace_rx.await?
     }
 
-    /// Subscribe to new logs events.
-    pub fn subscribe_to_logs(&self) -> broadcast::Receiver<LogMined> {
-        self.log_notifier.subscribe()
+    /// Current time used to compute block timestamps.
+    fn current_timestamp(&self) -> u64 {
+        self.clock.now_in_secs()
     }
-}
+
+    /// Timestamp of the next mined block, consuming the forced timestamp if there is one.
+    async fn next_block_timestamp(&self) -> u64 {
+        match self.next_block_timestamp_in_secs.lock().await.take() {
+            Some(timestamp_in_secs) => timestamp_in_secs,
+            None => self.current_timestamp(),
+        }
+    }
+
+    /// Notifies subscribers about a new block and the logs of its transactions.
+    fn notify(&self, block: Block) {
+        // notify new blocks
+        if let Err(e) = self.block_notifier.send(block.clone()) {
+            tracing::error!(reason = ?e, "failed to send block notification");
+        };
+
+        // notify transaction logs
+        for trx in block.transactions {
+            for log in trx.logs {
+                if let Err(e) = self.log_notifier.send(log) {
+                    tracing::error!(reason = ?e, "failed to send log notification");
+                };
+            }
+        }
+    }
+
+    /// Subscribe to new blocks events.
+    pub fn subscribe_to_new_heads(&self) -> broadcast::Receiver<Block> {
+        self.block_notifier.subscribe()
+    }
+
+    /// Subscribe to new logs events.
+    pub fn subscribe_to_logs(&self) -> broadcast::Receiver<LogMined> {
+        self.log_notifier.subscribe()
+    }
+}
+
+/// Records metrics for each conflict by kind.
+fn record_conflicts(conflicts: &ExecutionConflicts) {
+    for conflict in conflicts.0.iter() {
+        metrics::inc_executor_conflicts(conflict);
+    }
+}
+
+/// Validates a transaction against the node configuration before it is queued or executed.
+fn validate(
+    transaction: &TransactionInput,
+    chain_id: &ChainId,
+    evm_hardfork: EvmHardfork,
+    transaction_gas_limit: &Gas,
+    initcode_size_limit: Option<usize>,
+) -> anyhow::Result<()> {
+    if transaction.signer.is_zero() {
+        tracing::warn!("rejecting transaction from zero address");
+        return Err(anyhow!("Transaction sent from zero address is not allowed."));
+    }
+
+    if &transaction.chain_id != chain_id {
+        tracing::warn!(chain_id = %transaction.chain_id, "rejecting transaction with invalid chain id");
+        return Err(EthExecutorError::InvalidChainId {
+            expected: chain_id.clone(),
+            actual: transaction.chain_id.clone(),
+        }
+        .into());
+    }
+
+    if let (Some(max_fee), Some(max_priority_fee)) = (&transaction.max_fee_per_gas, &transaction.max_priority_fee_per_gas) {
+        if max_priority_fee > max_fee {
+            tracing::warn!(%max_fee, %max_priority_fee, "rejecting transaction with priority fee above maximum fee");
+            return Err(EthExecutorError::TipAboveFeeCap {
+                address: transaction.signer.clone(),
+                max_priority_fee: max_priority_fee.clone(),
+                max_fee: max_fee.clone(),
+            }
+            .into());
+        }
+    }
+
+    if transaction.input.len() > MAX_TRANSACTION_INPUT_SIZE {
+        tracing::warn!(size = %transaction.input.len(), "rejecting transaction with input above size limit");
+        return Err(EthExecutorError::OversizedData.into());
+    }
+
+    let intrinsic_gas = transaction.intrinsic_gas(evm_hardfork);
+    if transaction.gas < intrinsic_gas {
+        tracing::warn!(gas = %transaction.gas, %intrinsic_gas, "rejecting transaction with gas below intrinsic gas");
+        return Err(EthExecutorError::IntrinsicGasTooLow {
+            have: transaction.gas.clone(),
+            want: intrinsic_gas,
+        }
+        .into());
+    }
+
+    if &transaction.gas > transaction_gas_limit {
+        tracing::warn!(gas = %transaction.gas, "rejecting transaction with gas above transaction gas limit");
+        return Err(EthExecutorError::GasLimitExceeded {
+            have: transaction.gas.clone(),
+            limit: transaction_gas_limit.clone(),
+        }
+        .into());
+    }
+
+    if let Some(limit) = initcode_size_limit {
+        if transaction.is_contract_deployment() && transaction.input.len() > limit {
+            tracing::warn!(size = %transaction.input.len(), %limit, "rejecting contract deployment with creation code above size limit");
+            return Err(EthExecutorError::InitcodeSizeExceeded {
+                size: transaction.input.len(),
+                limit,
+            }
+            .into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Validates the gas and fee fields of a call before it is executed.
+fn validate_call(input: &CallInput, evm_hardfork: EvmHardfork) -> Result<(), EthExecutorError> {
+    if input.gas_price.is_some() && input.is_dynamic_fee() {
+        tracing::warn!("rejecting call with both gas price and dynamic fees");
+        return Err(EthExecutorError::GasPriceWithDynamicFees);
+    }
+
+    if let (Some(max_fee), Some(max_priority_fee)) = (&input.max_fee_per_gas, &input.max_priority_fee_per_gas) {
+        if max_priority_fee > max_fee {
+            tracing::warn!(%max_fee, %max_priority_fee, "rejecting call with priority fee above maximum fee");
+            return Err(EthExecutorError::TipAboveFeeCap {
+                address: input.from.clone(),
+                max_priority_fee: max_priority_fee.clone(),
+                max_fee: max_fee.clone(),
+            });
+        }
+    }
+
+    if let Some(ref gas) = input.gas {
+        let intrinsic_gas = input.intrinsic_gas(evm_hardfork);
+        if gas < &intrinsic_gas {
+            tracing::warn!(%gas, %intrinsic_gas, "rejecting call with gas below intrinsic gas");
+            return Err(EthExecutorError::IntrinsicGasTooLow {
+                have: gas.clone(),
+                want: intrinsic_gas,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Explains why a call failed with the highest gas limit available to it when estimating gas.
+fn estimate_gas_failure(execution: Execution, gas_limit: u64) -> EthExecutorError {
+    match execution.result {
+        ExecutionResult::Reverted => EthExecutorError::ExecutionReverted(execution.output),
+        ExecutionResult::Halted { reason } if not(reason.starts_with("OutOfGas")) => EthExecutorError::ExecutionHalted(reason),
+        _ => EthExecutorError::GasAllowanceExceeded(gas_limit.into()),
+    }
+}
+
+/// Waits for the execution of an accepted transaction.
+async fn receive_execution(execution_rx: oneshot::Receiver<anyhow::Result<Execution>>) -> anyhow::Result<Execution> {
+    match execution_rx.await {
+        Ok(result) => result,
+        Err(_) => Err(anyhow!("Transaction was dropped before being mined.")),
+    }
+}
+
+/// Validates that the maximum fee offered by a transaction covers the base fee of the block it will be mined in.
+fn validate_fee_cap(transaction: &TransactionInput, base_fee: &Wei) -> Result<(), EthExecutorError> {
+    // legacy transactions offer their gas price, while dynamic fee transactions offer their maximum fee
+    let max_fee = transaction.max_fee_per_gas.as_ref().unwrap_or(&transaction.gas_price);
+    if max_fee < base_fee {
+        tracing::warn!(%max_fee, %base_fee, "rejecting transaction with maximum fee below base fee");
+        return Err(EthExecutorError::FeeCapTooLow {
+            address: transaction.signer.clone(),
+            max_fee: max_fee.clone(),
+            base_fee: base_fee.clone(),
+        });
+    }
+    Ok(())
+}
+
+/// Validates a transaction against the current state of its sender before it is executed.
+///
+/// The nonce can be any nonce from the mined nonce of the sender up to the nonce that makes its transactions executable.
+fn validate_account(transaction: &TransactionInput, account: &Account, executable_nonce: &Nonce, charge_gas: bool) -> Result<(), EthExecutorError> {
+    validate_nonce(&transaction.nonce, account, executable_nonce)?;
+
+    // sender must afford the transferred value and, when gas is charged, the maximum gas cost, even if not all gas is used
+    let gas_cost = if_else!(
+        charge_gas,
+        U256::from(transaction.gas.clone()).saturating_mul(transaction.gas_price.clone().into()),
+        U256::zero()
+    );
+    let cost = gas_cost.saturating_add(transaction.value.clone().into());
+    let cost = Wei::from(cost);
+    if account.balance < cost {
+        tracing::warn!(balance = %account.balance, %cost, "rejecting transaction with insufficient funds");
+        return Err(EthExecutorError::InsufficientFunds {
+            address: account.address.clone(),
+            have: account.balance.clone(),
+            want: cost,
+        });
+    }
+
+    Ok(())
+}
+
+/// Validates the nonce of a transaction or call against the nonces its sender can use, from its mined nonce up to the given nonce.
+fn validate_nonce(nonce: &Nonce, account: &Account, highest_nonce: &Nonce) -> Result<(), EthExecutorError> {
+    if nonce < &account.nonce {
+        tracing::warn!(%nonce, expected_nonce = %account.nonce, "rejecting transaction with nonce too low");
+        return Err(EthExecutorError::NonceTooLow {
+            address: account.address.clone(),
+            expected: account.nonce.clone(),
+            actual: nonce.clone(),
+        });
+    }
+    if nonce > highest_nonce {
+        tracing::warn!(%nonce, expected_nonce = %highest_nonce, "rejecting transaction with nonce too high");
+        return Err(EthExecutorError::NonceTooHigh {
+            address: account.address.clone(),
+            expected: highest_nonce.clone(),
+            actual: nonce.clone(),
+        });
+    }
+    Ok(())
+}
 
 // for each evm, spawn a new thread that runs in an infinite loop executing transactions.
 fn spawn_background_evms(evms: NonEmpty<Box<dyn Evm>>) -> crossbeam_channel::Sender<EvmTask> {
@@ -180,10 +1314,17 @@ fn spawn_background_evms(evms: NonEmpty<Box<dyn Evm>>) -> crossbeam_channel::Sen
             let _tokio_guard = tokio.enter();
 
             // keep executing transactions until the channel is closed
-            while let Ok((input, tx)) = evm_rx.recv() {
-                if let Err(e) = tx.send(evm.execute(input)) {
-                    tracing::error!(reason = ?e, "failed to send evm execution result");
-                };
+            while let Ok(task) = evm_rx.recv() {
+                match task {
+                    EvmTask::Execute(input, tx) =>
+                        if let Err(e) = tx.send(catch_evm_panic(|| evm.execute(input))) {
+                            tracing::error!(reason = ?e, "failed to send evm execution result");
+                        },
+                    EvmTask::Trace(input, tracer, tx) =>
+                        if let Err(e) = tx.send(catch_evm_panic(|| evm.trace(input, &tracer))) {
+                            tracing::error!(reason = ?e, "failed to send evm trace result");
+                        },
+                }
             }
             tracing::warn!("stopping evm thread because task channel was closed");
         })
@@ -191,3 +1332,903 @@ fn spawn_background_evms(evms: NonEmpty<Box<dyn Evm>>) -> crossbeam_channel::Sen
     }
     evm_tx
 }
+
+/// Runs an EVM operation reporting a panic as an error, so the EVM thread is not lost and keeps executing the next tasks.
+fn catch_evm_panic<T>(operation: impl FnOnce() -> anyhow::Result<T>) -> anyhow::Result<T> {
+    match panic::catch_unwind(AssertUnwindSafe(operation)) {
+        Ok(result) => result,
+        Err(panic) => {
+            let reason = match panic.downcast_ref::<&str>() {
+                Some(reason) => reason.to_string(),
+                None => panic.downcast_ref::<String>().cloned().unwrap_or_default(),
+            };
+            tracing::error!(%reason, "evm panicked while executing task");
+            Err(anyhow!("Unexpected error with EVM execution. Check logs for more information."))
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Tests
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+
+    use clap::Parser;
+    use ethers_core::utils::keccak256;
+    use nonempty::NonEmpty;
+
+    use super::*;
+    use crate::eth::evm::revm::Revm;
+    use crate::eth::miner::ManualClock;
+    use crate::eth::primitives::Genesis;
+    use crate::eth::primitives::SlotIndex;
+    use crate::eth::primitives::SlotValue;
+    use crate::eth::storage::test_accounts;
+    use crate::eth::storage::InMemoryStorage;
+
+    /// Time the clock of test executors starts at, after the default genesis timestamp.
+    const NOW_IN_SECS: u64 = 1_800_000_000;
+
+    /// Executor backed by in-memory storage and a manual clock.
+    struct TestExecutor {
+        executor: Arc<EthExecutor>,
+        storage: Arc<dyn EthStorage>,
+        clock: Arc<ManualClock>,
+
+        /// Number of transactions and calls executed by the EVM, excluding traces.
+        executions: Arc<AtomicUsize>,
+
+        /// Runs in the EVM thread before the next execution, simulating what happens while the EVM is busy.
+        before_next_execution: Arc<std::sync::Mutex<Option<ExecutionHook>>>,
+    }
+
+    type ExecutionHook = Box<dyn FnOnce() + Send>;
+
+    /// EVM that counts its executions and runs a hook before the next one.
+    struct TestEvm {
+        inner: Revm,
+        executions: Arc<AtomicUsize>,
+        before_next_execution: Arc<std::sync::Mutex<Option<ExecutionHook>>>,
+    }
+
+    impl Evm for TestEvm {
+        fn execute(&mut self, input: EvmInput) -> anyhow::Result<Execution> {
+            let hook = self.before_next_execution.lock().unwrap().take();
+            if let Some(hook) = hook {
+                hook();
+            }
+            self.executions.fetch_add(1, Ordering::SeqCst);
+            self.inner.execute(input)
+        }
+
+        fn trace(&mut self, input: EvmInput, tracer: &Tracer) -> anyhow::Result<(Execution, ExecutionTrace)> {
+            self.inner.trace(input, tracer)
+        }
+    }
+
+    /// Creates an executor configured with the given command line arguments and genesis accounts.
+    fn test_executor(args: &[&str], accounts: Vec<Account>) -> TestExecutor {
+        let config = Config::parse_from(std::iter::once("stratus").chain(args.iter().copied()));
+
+        let mut genesis = Genesis::default();
+        genesis.add_accounts(accounts);
+        let storage: Arc<dyn EthStorage> = Arc::new(InMemoryStorage::new(&genesis));
+
+        let executions = Arc::new(AtomicUsize::new(0));
+        let before_next_execution = Arc::new(std::sync::Mutex::new(None));
+        let evm: Box<dyn Evm> = Box::new(TestEvm {
+            inner: Revm::new(Arc::clone(&storage), &config).unwrap(),
+            executions: Arc::clone(&executions),
+            before_next_execution: Arc::clone(&before_next_execution),
+        });
+        let clock = Arc::new(ManualClock::new(NOW_IN_SECS));
+        let executor = Arc::new(EthExecutor::new(
+            NonEmpty::new(evm),
+            Arc::clone(&storage),
+            Arc::clone(&clock) as Arc<dyn Clock>,
+            &config,
+        ));
+        Arc::clone(&executor).spawn_queue_promoter();
+
+        TestExecutor {
+            executor,
+            storage,
+            clock,
+            executions,
+            before_next_execution,
+        }
+    }
+
+    fn address(byte: u8) -> Address {
+        Address::from([byte; 20])
+    }
+
+    async fn read_latest_block(storage: &Arc<dyn EthStorage>) -> Block {
+        storage.read_block(&BlockSelection::Latest).await.unwrap().unwrap()
+    }
+
+    /// Unsigned transaction transferring value between accounts.
+    fn transfer(executor: &EthExecutor, from: &Address, nonce: u64, to: &Address, value: u64) -> TransactionInput {
+        let call = CallInput {
+            from: from.clone(),
+            to: Some(to.clone()),
+            value: value.into(),
+            ..CallInput::default()
+        };
+        TransactionInput::new_impersonated(executor.chain_id.clone(), nonce.into(), call)
+    }
+
+    /// Executes and mines transactions in a single block, like the interval miner does.
+    async fn mine_in_single_block(executor: &EthExecutor, transactions: Vec<TransactionInput>) -> Block {
+        {
+            let mut pending_block_lock = executor.pending_block.lock().await;
+            for input in transactions {
+                let execution_tx = oneshot::channel().0;
+                pending_block_lock.push(PendingTransaction { input, execution_tx });
+            }
+        }
+        executor.mine_pending_block().await.unwrap();
+        read_latest_block(&executor.eth_storage).await
+    }
+
+    /// Waits until the mined nonce of the account reaches the given nonce, failing after a few seconds.
+    async fn wait_for_mined_nonce(storage: &Arc<dyn EthStorage>, address: &Address, nonce: u64) {
+        for _ in 0..500 {
+            if storage.read_account(address, &StoragePointInTime::Present).await.unwrap().nonce == nonce.into() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("nonce {} of {} was not mined in time", nonce, address);
+    }
+
+    /// Account with the given bytecode and no balance.
+    fn contract_account(address: &Address, bytecode: Vec<u8>) -> Account {
+        Account {
+            address: address.clone(),
+            bytecode: Some(Bytes::from(bytecode)),
+            ..Account::default()
+        }
+    }
+
+    /// Contract that returns the `NUMBER` and `TIMESTAMP` of the block it is executed in.
+    fn block_context_contract(address: Address) -> Account {
+        // NUMBER PUSH1 0 MSTORE TIMESTAMP PUSH1 32 MSTORE PUSH1 64 PUSH1 0 RETURN
+        contract_account(&address, vec![0x43, 0x60, 0x00, 0x52, 0x42, 0x60, 0x20, 0x52, 0x60, 0x40, 0x60, 0x00, 0xf3])
+    }
+
+    /// Calls a contract with the given gas limit, or with the call gas cap if none is given.
+    async fn call_contract(executor: &EthExecutor, contract: &Address, gas: Option<u64>) -> Execution {
+        let input = CallInput {
+            to: Some(contract.clone()),
+            gas: gas.map(Gas::from),
+            ..CallInput::default()
+        };
+        executor.call(input, StoragePointInTime::Present).await.unwrap()
+    }
+
+    async fn estimate_contract(executor: &EthExecutor, contract: &Address) -> anyhow::Result<u64> {
+        let input = CallInput {
+            to: Some(contract.clone()),
+            ..CallInput::default()
+        };
+        executor.estimate_gas(input, StoragePointInTime::Present).await.map(u64::from)
+    }
+
+    /// Calls a contract that returns the `BLOCKHASH` of the block number sent as input.
+    async fn call_blockhash(executor: &EthExecutor, contract: &Address, number: u64, point_in_time: StoragePointInTime) -> Hash {
+        let mut data = [0u8; 32];
+        data[24..].copy_from_slice(&number.to_be_bytes());
+        let input = CallInput {
+            to: Some(contract.clone()),
+            data: Bytes::from(data.to_vec()),
+            ..CallInput::default()
+        };
+        let execution = executor.call(input, point_in_time).await.unwrap();
+        Hash::new(execution.output.as_ref().try_into().unwrap())
+    }
+
+    /// Calls a contract that returns the `NUMBER`, `TIMESTAMP` and `BASEFEE` of the block it is executed in.
+    async fn call_block_context(executor: &EthExecutor, contract: &Address, point_in_time: StoragePointInTime) -> (U256, U256, U256) {
+        let input = CallInput {
+            to: Some(contract.clone()),
+            ..CallInput::default()
+        };
+        let output = executor.call(input, point_in_time).await.unwrap().output;
+        let word = |index: usize| U256::from_big_endian(&output.as_ref()[index * 32..(index + 1) * 32]);
+        (word(0), word(1), word(2))
+    }
+
+    /// Creates an executor that charges gas at a fixed base fee of 7 wei and credits it to the given fee recipient.
+    fn test_executor_charging_gas(fee_recipient: &Address, accounts: Vec<Account>) -> TestExecutor {
+        let fee_recipient = format!("--fee-recipient={}", fee_recipient);
+        test_executor(&["--charge-gas", &fee_recipient, "--fee-policy=fixed", "--gas-price=7"], accounts)
+    }
+
+    async fn read_balance(storage: &Arc<dyn EthStorage>, address: &Address) -> Wei {
+        storage.read_account(address, &StoragePointInTime::Present).await.unwrap().balance
+    }
+
+    // -------------------------------------------------------------------------
+    // Development
+    // -------------------------------------------------------------------------
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn override_account_is_committed_to_latest_state_root() {
+        let TestExecutor { executor, storage, .. } = test_executor(&[], vec![]);
+        let address = address(0x11);
+        let bytecode = Bytes::from(vec![0x60, 0x00]);
+        let slot = Slot::new(SlotIndex::from(1u64), SlotValue::from(2u64));
+
+        let account_override = AccountOverride {
+            nonce: Some(3u64.into()),
+            balance: Some(4u64.into()),
+            bytecode: Some(Some(bytecode.clone())),
+            slots: vec![slot.clone()],
+        };
+        let block = executor.override_account(&address, account_override).await.unwrap();
+
+        // override is recorded in a new block, which is saved only once
+        assert_eq!(*block.number(), BlockNumber::from(1u64));
+        let latest = read_latest_block(&storage).await;
+        assert_eq!(latest.hash(), block.hash());
+        assert_eq!(
+            storage.read_block(&BlockSelection::Hash(block.hash().clone())).await.unwrap(),
+            Some(block.clone())
+        );
+        assert_eq!(storage.read_block(&BlockSelection::Number(1u64.into())).await.unwrap(), Some(block.clone()));
+
+        // overridden state is readable
+        let account = storage.read_account(&address, &StoragePointInTime::Present).await.unwrap();
+        assert_eq!(account.nonce, 3u64.into());
+        assert_eq!(account.balance, 4u64.into());
+        assert_eq!(storage.read_bytecode(&account.code_hash()).await.unwrap(), Some(bytecode));
+        let read_slot = storage.read_slot(&address, &slot.index, &StoragePointInTime::Present).await.unwrap();
+        assert_eq!(read_slot.value, slot.value);
+
+        // proofs of the latest block are verifiable against its state root
+        let point_in_time = storage.translate_to_point_in_time(&BlockSelection::Latest).await.unwrap();
+        let proof = storage.read_proof(&address, &[slot.index.clone()], &point_in_time).await.unwrap();
+        assert_eq!(Hash::new(keccak256(&proof.proof[0])), latest.header.state_root);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn override_account_keeps_fields_that_are_not_overridden() {
+        let TestExecutor { executor, storage, .. } = test_executor(&[], test_accounts());
+        let sender = test_accounts()[0].address.clone();
+
+        // nonce changed by a mined transaction is kept when only the balance is overridden
+        executor.transact(transfer(&executor, &sender, 0, &address(0x26), 1)).await.unwrap();
+        let account_override = AccountOverride {
+            balance: Some(5u64.into()),
+            ..AccountOverride::default()
+        };
+        executor.override_account(&sender, account_override).await.unwrap();
+
+        let account = storage.read_account(&sender, &StoragePointInTime::Present).await.unwrap();
+        assert_eq!(account.nonce, 1u64.into());
+        assert_eq!(account.balance, 5u64.into());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn override_account_removes_bytecode() {
+        let TestExecutor { executor, storage, .. } = test_executor(&[], vec![]);
+        let address = address(0x27);
+
+        let account_override = AccountOverride {
+            bytecode: Some(Some(Bytes::from(vec![0x60, 0x00]))),
+            ..AccountOverride::default()
+        };
+        executor.override_account(&address, account_override).await.unwrap();
+        let account_override = AccountOverride {
+            bytecode: Some(None),
+            ..AccountOverride::default()
+        };
+        executor.override_account(&address, account_override).await.unwrap();
+
+        let account = storage.read_account(&address, &StoragePointInTime::Present).await.unwrap();
+        assert_eq!(account.bytecode, None);
+        let account = storage.read_account(&address, &StoragePointInTime::Past(1u64.into())).await.unwrap();
+        assert_eq!(account.bytecode, Some(Bytes::from(vec![0x60, 0x00])));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn snapshot_does_not_mine_blocks() {
+        let TestExecutor { executor, storage, .. } = test_executor(&[], vec![]);
+
+        assert_eq!(executor.snapshot().await.unwrap(), 1);
+        assert_eq!(executor.snapshot().await.unwrap(), 2);
+        assert_eq!(storage.read_current_block_number().await.unwrap(), BlockNumber::ZERO);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn revert_discards_blocks_overrides_and_time_after_snapshot() {
+        let TestExecutor { executor, storage, clock, .. } = test_executor(&[], vec![]);
+        let address = address(0x22);
+        let id = executor.snapshot().await.unwrap();
+
+        // change state and time after snapshot
+        executor.increase_time(100);
+        let account_override = AccountOverride {
+            balance: Some(1u64.into()),
+            ..AccountOverride::default()
+        };
+        executor.override_account(&address, account_override).await.unwrap();
+        let block = executor.mine_empty_block(None).await.unwrap();
+        assert_eq!(*block.header.timestamp_in_secs, NOW_IN_SECS + 100);
+
+        // revert restores chain, state and time
+        assert!(executor.revert(id).await.unwrap());
+        assert_eq!(storage.read_current_block_number().await.unwrap(), BlockNumber::ZERO);
+        let account = storage.read_account(&address, &StoragePointInTime::Present).await.unwrap();
+        assert_eq!(account.balance, Wei::ZERO);
+        clock.advance(5);
+        let block = executor.mine_empty_block(None).await.unwrap();
+        assert_eq!(*block.number(), BlockNumber::from(1u64));
+        assert_eq!(*block.header.timestamp_in_secs, NOW_IN_SECS + 5);
+
+        // reverted snapshot cannot be reverted again
+        assert!(not(executor.revert(id).await.unwrap()));
+        assert!(not(executor.revert(0).await.unwrap()));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn revert_invalidates_later_snapshots() {
+        let TestExecutor { executor, .. } = test_executor(&[], vec![]);
+        let first = executor.snapshot().await.unwrap();
+        let second = executor.snapshot().await.unwrap();
+
+        assert!(executor.revert(first).await.unwrap());
+        assert!(not(executor.revert(second).await.unwrap()));
+    }
+
+    // -------------------------------------------------------------------------
+    // Transaction pool
+    // -------------------------------------------------------------------------
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn submit_rejects_nonce_above_64_bits() {
+        let TestExecutor { executor, .. } = test_executor(&[], test_accounts());
+        let sender = test_accounts()[0].address.clone();
+
+        let mut transaction = transfer(&executor, &sender, 0, &address(0x22), 1);
+        transaction.nonce = (U256::from(u64::MAX) + 1).into();
+        let error = executor.submit(transaction).await.unwrap_err();
+        assert!(matches!(error.downcast_ref::<EthExecutorError>(), Some(EthExecutorError::NonceTooHigh { .. })));
+        assert_eq!(executor.read_pool().await.to_txpool_status().queued, 0u64.into());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn transaction_with_input_larger_than_storage_limit_is_rejected() {
+        let TestExecutor { executor, .. } = test_executor(&[], test_accounts());
+        let sender = test_accounts()[0].address.clone();
+
+        let mut transaction = transfer(&executor, &sender, 0, &address(0x23), 1);
+        transaction.input = Bytes::from(vec![0; MAX_TRANSACTION_INPUT_SIZE + 1]);
+        let error = executor.transact(transaction).await.unwrap_err();
+        assert!(matches!(error.downcast_ref::<EthExecutorError>(), Some(EthExecutorError::OversizedData)));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn queued_transactions_are_promoted_in_background_after_the_gap_is_filled() {
+        let TestExecutor { executor, storage, .. } = test_executor(&[], test_accounts());
+        let sender = test_accounts()[0].address.clone();
+        let receiver = address(0x23);
+
+        // future nonces are queued until the gap is filled
+        assert!(executor.submit(transfer(&executor, &sender, 2, &receiver, 1)).await.unwrap().is_none());
+        assert!(executor.submit(transfer(&executor, &sender, 1, &receiver, 1)).await.unwrap().is_none());
+        assert_eq!(executor.read_pool().await.to_txpool_status().queued, 2u64.into());
+
+        // filling the gap returns with its own execution, and the queued transactions are mined afterwards
+        let execution = executor.submit(transfer(&executor, &sender, 0, &receiver, 1)).await.unwrap().unwrap();
+        assert!(execution.is_success());
+        wait_for_mined_nonce(&storage, &sender, 3).await;
+        assert_eq!(read_balance(&storage, &receiver).await, 3u64.into());
+        assert_eq!(executor.read_pool().await.to_txpool_status().queued, 0u64.into());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn consecutive_nonces_of_a_sender_are_mined_in_the_same_interval_block() {
+        let TestExecutor { executor, storage, .. } = test_executor(&["--block-mode=3600s"], test_accounts());
+        let sender = test_accounts()[0].address.clone();
+        let receiver = address(0x24);
+
+        // the second transaction follows the pending one instead of being queued until it is mined
+        let mut submissions = vec![];
+        for nonce in 0..2 {
+            let transaction = transfer(&executor, &sender, nonce, &receiver, 1);
+            let executor = Arc::clone(&executor);
+            submissions.push(tokio::spawn(async move { executor.submit(transaction).await }));
+            while executor.pending_block.lock().await.len() <= nonce as usize {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }
+        assert_eq!(executor.read_pending_nonce(&sender).await.unwrap(), 2u64.into());
+        assert_eq!(executor.read_pool().await.to_txpool_status().queued, 0u64.into());
+
+        executor.mine_pending_block().await.unwrap();
+        for submission in submissions {
+            assert!(submission.await.unwrap().unwrap().unwrap().is_success());
+        }
+        let block = read_latest_block(&storage).await;
+        assert_eq!(block.transactions.len(), 2);
+        assert_eq!(read_balance(&storage, &receiver).await, 2u64.into());
+        assert_eq!(executor.read_pool().await.to_txpool_status().pending, 0u64.into());
+    }
+
+    // -------------------------------------------------------------------------
+    // Mining
+    // -------------------------------------------------------------------------
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn pending_block_is_limited_by_block_gas_limit() {
+        let TestExecutor { executor, .. } = test_executor(&["--block-gas-limit=50000", "--transaction-gas-limit=50000"], test_accounts());
+        let transactions = test_accounts()
+            .iter()
+            .take(3)
+            .map(|account| {
+                let mut transaction = transfer(&executor, &account.address, 0, &address(0x99), 1);
+                transaction.gas = 21_000u64.into();
+                transaction
+            })
+            .collect_vec();
+
+        // only the transactions whose gas fit in the block are mined, the others wait for the next block
+        let block = mine_in_single_block(&executor, transactions).await;
+        assert_eq!(block.transactions.len(), 2);
+        assert!(block.header.gas <= block.header.gas_limit);
+        assert_eq!(executor.pending_block.lock().await.len(), 1);
+    }
+
+    /// Mines an empty block in the future while the EVM executes the next transaction.
+    fn mine_block_during_next_execution(test: &TestExecutor, timestamp_in_secs: u64) {
+        let executor = Arc::clone(&test.executor);
+        *test.before_next_execution.lock().unwrap() = Some(Box::new(move || {
+            Handle::current().block_on(executor.mine_empty_block(Some(timestamp_in_secs))).unwrap();
+        }));
+    }
+
+    /// Asserts that an execution saw the number and timestamp of the block it was mined in.
+    fn assert_executed_in_block(execution: &Execution, block: &Block) {
+        let word = |index: usize| U256::from_big_endian(&execution.output.as_ref()[index * 32..(index + 1) * 32]);
+        assert_eq!(word(0), U256::from(u64::from(*block.number())));
+        assert_eq!(word(1), U256::from(*block.header.timestamp_in_secs));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn automined_transaction_is_executed_again_when_a_block_is_mined_during_its_execution() {
+        let (sender, contract) = (test_accounts()[0].address.clone(), address(0x19));
+        let mut accounts = test_accounts();
+        accounts.push(block_context_contract(contract.clone()));
+        let test = test_executor(&[], accounts);
+        test.clock.advance(10);
+
+        // first execution happens in block 1 at the current time, but block 1 is mined meanwhile with a later timestamp
+        mine_block_during_next_execution(&test, NOW_IN_SECS + 100);
+        let execution = test.executor.transact(transfer(&test.executor, &sender, 0, &contract, 0)).await.unwrap();
+        assert_eq!(test.executions.load(Ordering::SeqCst), 2);
+
+        // transaction is mined in the next block, with the context it was executed with and without block number gaps
+        let block = read_latest_block(&test.storage).await;
+        assert_eq!(*block.number(), BlockNumber::from(2u64));
+        assert_eq!(*block.header.timestamp_in_secs, NOW_IN_SECS + 100);
+        assert_eq!(block.transactions[0].execution, execution);
+        assert_executed_in_block(&execution, &block);
+        assert_eq!(test.storage.read_current_block_number().await.unwrap(), BlockNumber::from(2u64));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn pending_block_is_executed_again_when_a_block_is_mined_during_its_execution() {
+        let (sender, contract) = (test_accounts()[0].address.clone(), address(0x19));
+        let mut accounts = test_accounts();
+        accounts.push(block_context_contract(contract.clone()));
+        let test = test_executor(&[], accounts);
+        test.clock.advance(10);
+
+        mine_block_during_next_execution(&test, NOW_IN_SECS + 100);
+        let block = mine_in_single_block(&test.executor, vec![transfer(&test.executor, &sender, 0, &contract, 0)]).await;
+        assert_eq!(test.executions.load(Ordering::SeqCst), 2);
+
+        assert_eq!(*block.number(), BlockNumber::from(2u64));
+        assert_eq!(*block.header.timestamp_in_secs, NOW_IN_SECS + 100);
+        assert_executed_in_block(&block.transactions[0].execution, &block);
+        assert_eq!(test.storage.read_current_block_number().await.unwrap(), BlockNumber::from(2u64));
+    }
+
+    // -------------------------------------------------------------------------
+    // Fees
+    // -------------------------------------------------------------------------
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn charged_gas_is_transferred_from_sender_to_fee_recipient() {
+        let (sender, receiver, fee_recipient) = (address(0x12), address(0x13), address(0xfe));
+        let sender_account = Account {
+            address: sender.clone(),
+            balance: 10_000_000u64.into(),
+            ..Account::default()
+        };
+        let TestExecutor { executor, storage, .. } = test_executor_charging_gas(&fee_recipient, vec![sender_account]);
+
+        let mut transaction = transfer(&executor, &sender, 0, &receiver, 1_000);
+        transaction.gas = 50_000u64.into();
+        transaction.gas_price = 10u64.into();
+        let execution = executor.transact(transaction).await.unwrap();
+        assert!(execution.is_success());
+
+        // only the gas used is paid, at the full gas price, including the base fee part
+        let fee = 21_000 * 10;
+        assert_eq!(execution.gas, 21_000u64.into());
+        assert_eq!(read_balance(&storage, &sender).await, (10_000_000 - 1_000 - fee).into());
+        assert_eq!(read_balance(&storage, &receiver).await, 1_000u64.into());
+        assert_eq!(read_balance(&storage, &fee_recipient).await, fee.into());
+        assert_eq!(read_latest_block(&storage).await.header.miner, fee_recipient);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn failed_transaction_charges_gas_and_increments_nonce() {
+        let (sender, contract, fee_recipient) = (address(0x14), address(0x15), address(0xfe));
+        let accounts = vec![
+            Account {
+                address: sender.clone(),
+                balance: 10_000_000u64.into(),
+                ..Account::default()
+            },
+            // PUSH1 0 PUSH1 0 REVERT
+            contract_account(&contract, vec![0x60, 0x00, 0x60, 0x00, 0xfd]),
+        ];
+        let TestExecutor { executor, storage, .. } = test_executor_charging_gas(&fee_recipient, accounts);
+
+        let mut transaction = transfer(&executor, &sender, 0, &contract, 1_000);
+        transaction.gas = 50_000u64.into();
+        transaction.gas_price = 10u64.into();
+        let execution = executor.transact(transaction).await.unwrap();
+        assert!(not(execution.is_success()));
+
+        // value is not transferred, but the gas used is still paid and the nonce is consumed
+        let fee = u64::from(execution.gas.clone()) * 10;
+        let sender_account = storage.read_account(&sender, &StoragePointInTime::Present).await.unwrap();
+        assert_eq!(sender_account.nonce, 1u64.into());
+        assert_eq!(sender_account.balance, (10_000_000 - fee).into());
+        assert_eq!(read_balance(&storage, &contract).await, Wei::ZERO);
+        assert_eq!(read_balance(&storage, &fee_recipient).await, fee.into());
+        assert_eq!(executor.read_pending_nonce(&sender).await.unwrap(), 1u64.into());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn sender_balance_must_cover_gas_cost_only_when_gas_is_charged() {
+        let (sender, receiver, fee_recipient) = (address(0x16), address(0x17), address(0xfe));
+        let sender_account = Account {
+            address: sender.clone(),
+            balance: 1_000u64.into(),
+            ..Account::default()
+        };
+        let transaction = |executor: &EthExecutor, value: u64| {
+            let mut transaction = transfer(executor, &sender, 0, &receiver, value);
+            transaction.gas = 50_000u64.into();
+            transaction.gas_price = 10u64.into();
+            transaction
+        };
+
+        // without charging gas, the balance must only cover the transferred value
+        let TestExecutor { executor, .. } = test_executor(&[], vec![sender_account.clone()]);
+        let error = executor.transact(transaction(&executor, 1_001)).await.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<EthExecutorError>(),
+            Some(EthExecutorError::InsufficientFunds { .. })
+        ));
+        assert!(executor.transact(transaction(&executor, 1_000)).await.unwrap().is_success());
+
+        // charging gas, the balance must also cover the maximum gas cost
+        let TestExecutor { executor, .. } = test_executor_charging_gas(&fee_recipient, vec![sender_account]);
+        let error = executor.transact(transaction(&executor, 1_000)).await.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<EthExecutorError>(),
+            Some(EthExecutorError::InsufficientFunds { .. })
+        ));
+    }
+
+    // -------------------------------------------------------------------------
+    // EVM
+    // -------------------------------------------------------------------------
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn blockhash_returns_hashes_of_the_256_most_recent_blocks() {
+        let contract = address(0x16);
+        // PUSH1 0 CALLDATALOAD BLOCKHASH PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+        let account = contract_account(&contract, vec![0x60, 0x00, 0x35, 0x40, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3]);
+        let TestExecutor { executor, .. } = test_executor(&[], vec![account]);
+
+        // hashes are indexed by block number
+        let mut hashes = vec![Hash::zero()];
+        for _ in 0..260 {
+            hashes.push(executor.mine_empty_block(None).await.unwrap().hash().clone());
+        }
+
+        // calls in the pending block 261 see blocks 5 to 260
+        assert_eq!(call_blockhash(&executor, &contract, 260, StoragePointInTime::Present).await, hashes[260]);
+        assert_eq!(call_blockhash(&executor, &contract, 5, StoragePointInTime::Present).await, hashes[5]);
+        assert_eq!(call_blockhash(&executor, &contract, 4, StoragePointInTime::Present).await, Hash::zero());
+        assert_eq!(call_blockhash(&executor, &contract, 261, StoragePointInTime::Present).await, Hash::zero());
+
+        // calls in a past block see the blocks before it
+        let past = StoragePointInTime::Past(100u64.into());
+        assert_eq!(call_blockhash(&executor, &contract, 99, past.clone()).await, hashes[99]);
+        assert_eq!(call_blockhash(&executor, &contract, 100, past).await, Hash::zero());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn calls_are_executed_in_the_context_of_the_selected_block() {
+        let contract = address(0x18);
+        // NUMBER PUSH1 0 MSTORE TIMESTAMP PUSH1 32 MSTORE BASEFEE PUSH1 64 MSTORE PUSH1 96 PUSH1 0 RETURN
+        let bytecode = vec![
+            0x43, 0x60, 0x00, 0x52, 0x42, 0x60, 0x20, 0x52, 0x48, 0x60, 0x40, 0x52, 0x60, 0x60, 0x60, 0x00, 0xf3,
+        ];
+        let account = contract_account(&contract, bytecode);
+        let TestExecutor { executor, clock, .. } = test_executor(&["--fee-policy=dynamic", "--gas-price=1000"], vec![account]);
+
+        // empty blocks decrease the base fee, so each block has its own number, timestamp and base fee
+        let mut blocks = vec![];
+        for _ in 0..3 {
+            clock.advance(10);
+            blocks.push(executor.mine_empty_block(None).await.unwrap());
+        }
+        assert_ne!(blocks[0].header.base_fee, blocks[2].header.base_fee);
+
+        // past calls see the selected block as it was mined
+        for block in &blocks {
+            let (number, timestamp, base_fee) = call_block_context(&executor, &contract, StoragePointInTime::Past(*block.number())).await;
+            assert_eq!(number, U256::from(u64::from(*block.number())));
+            assert_eq!(timestamp, U256::from(*block.header.timestamp_in_secs));
+            assert_eq!(base_fee, U256::from(block.header.base_fee.clone()));
+        }
+
+        // present calls see the pending block
+        let pending = executor.read_pending_block().await.unwrap();
+        let (number, timestamp, base_fee) = call_block_context(&executor, &contract, StoragePointInTime::Present).await;
+        assert_eq!(number, U256::from(4u64));
+        assert_eq!(timestamp, U256::from(*pending.header.timestamp_in_secs));
+        assert_eq!(base_fee, U256::from(executor.next_base_fee().await.unwrap()));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn evm_keeps_executing_after_panic() {
+        let TestExecutor {
+            executor,
+            before_next_execution,
+            ..
+        } = test_executor(&[], vec![]);
+        let call = || CallInput {
+            to: Some(address(0x17)),
+            ..CallInput::default()
+        };
+
+        // the only evm thread reports the panic as an error and executes the next call
+        *before_next_execution.lock().unwrap() = Some(Box::new(|| panic!("evm panic requested by test")));
+        assert!(executor.call(call(), StoragePointInTime::Present).await.is_err());
+        assert!(executor.call(call(), StoragePointInTime::Present).await.unwrap().is_success());
+    }
+
+    // -------------------------------------------------------------------------
+    // Calls
+    // -------------------------------------------------------------------------
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn call_with_unexpected_nonce_fails_with_nonce_error() {
+        let TestExecutor { executor, .. } = test_executor(&[], test_accounts());
+        let call = |nonce: u64| CallInput {
+            from: test_accounts()[0].address.clone(),
+            to: Some(address(0x1a)),
+            nonce: Some(nonce.into()),
+            ..CallInput::default()
+        };
+
+        assert!(executor.call(call(0), StoragePointInTime::Present).await.unwrap().is_success());
+
+        let error = executor.call(call(1), StoragePointInTime::Present).await.unwrap_err();
+        assert!(matches!(error.downcast_ref::<EthExecutorError>(), Some(EthExecutorError::NonceTooHigh { .. })));
+        assert!(error.to_string().starts_with("nonce too high"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn calls_do_not_wait_for_blocks_being_mined() {
+        let TestExecutor { executor, .. } = test_executor(&[], vec![]);
+        let call = CallInput {
+            to: Some(address(0x25)),
+            ..CallInput::default()
+        };
+
+        // the pending block is built while a block is being mined
+        let _mining_lock = executor.mining_lock.lock().await;
+        let execution = tokio::time::timeout(Duration::from_secs(5), executor.call(call, StoragePointInTime::Present))
+            .await
+            .expect("call should not wait for the mining lock");
+        assert!(execution.unwrap().is_success());
+    }
+
+    // -------------------------------------------------------------------------
+    // Gas estimation
+    // -------------------------------------------------------------------------
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn estimate_gas_searches_lowest_gas_limit_that_succeeds() {
+        let contract = address(0x1b);
+        // PUSH3 100000 GAS LT PUSH1 10 JUMPI STOP JUMPDEST PUSH1 0 PUSH1 0 REVERT
+        let bytecode = vec![0x62, 0x01, 0x86, 0xa0, 0x5a, 0x10, 0x60, 0x0a, 0x57, 0x00, 0x5b, 0x60, 0x00, 0x60, 0x00, 0xfd];
+        let TestExecutor { executor, .. } = test_executor(&[], vec![contract_account(&contract, bytecode)]);
+
+        // the contract uses little gas, but reverts unless 100000 gas remain after the intrinsic gas, PUSH3 and GAS
+        let needed = 21_000 + 3 + 2 + 100_000;
+        let gas_used = u64::from(call_contract(&executor, &contract, None).await.gas);
+        assert!(gas_used < 22_000);
+        assert!(call_contract(&executor, &contract, Some(needed)).await.is_success());
+        assert!(not(call_contract(&executor, &contract, Some(needed - 1)).await.is_success()));
+
+        // estimation stops within the error ratio above the lowest gas limit
+        let estimate = estimate_contract(&executor, &contract).await.unwrap();
+        assert!(estimate >= needed);
+        assert!(((estimate - needed) as f64) < estimate as f64 * ESTIMATE_GAS_ERROR_RATIO);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn estimate_gas_covers_gas_retained_by_the_63_64_rule() {
+        let (caller, callee) = (address(0x1c), address(0x1d));
+        // PUSH1 0 (x5) PUSH20 callee GAS CALL ISZERO PUSH1 38 JUMPI STOP JUMPDEST PUSH1 0 PUSH1 0 REVERT
+        let mut caller_bytecode = [0x60, 0x00].repeat(5);
+        caller_bytecode.push(0x73);
+        caller_bytecode.extend_from_slice(callee.as_ref());
+        caller_bytecode.extend_from_slice(&[0x5a, 0xf1, 0x15, 0x60, 0x26, 0x57, 0x00, 0x5b, 0x60, 0x00, 0x60, 0x00, 0xfd]);
+        // PUSH1 1 PUSH1 0 SSTORE STOP
+        let callee_bytecode = vec![0x60, 0x01, 0x60, 0x00, 0x55, 0x00];
+        let accounts = vec![contract_account(&caller, caller_bytecode), contract_account(&callee, callee_bytecode)];
+        let TestExecutor { executor, .. } = test_executor(&[], accounts);
+
+        // with only the gas used, the caller keeps 1/64 of the gas and the callee runs out of gas
+        let gas_used = u64::from(call_contract(&executor, &caller, None).await.gas);
+        assert!(not(call_contract(&executor, &caller, Some(gas_used)).await.is_success()));
+
+        let estimate = estimate_contract(&executor, &caller).await.unwrap();
+        assert!(estimate > gas_used);
+        assert!(call_contract(&executor, &caller, Some(estimate)).await.is_success());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn estimate_gas_covers_gas_refunded_after_execution() {
+        let contract = address(0x1e);
+        // PUSH1 1 PUSH1 0 SSTORE PUSH1 0 PUSH1 0 SSTORE STOP
+        let bytecode = vec![0x60, 0x01, 0x60, 0x00, 0x55, 0x60, 0x00, 0x60, 0x00, 0x55, 0x00];
+        let TestExecutor { executor, .. } = test_executor(&[], vec![contract_account(&contract, bytecode)]);
+
+        // restoring the slot refunds gas, so the gas used is below the gas consumed during execution
+        let consumed = 21_000 + 4 * 3 + 22_100 + 100;
+        let gas_used = u64::from(call_contract(&executor, &contract, None).await.gas);
+        assert_eq!(gas_used, consumed - consumed / 5);
+        assert!(not(call_contract(&executor, &contract, Some(gas_used)).await.is_success()));
+
+        let estimate = estimate_contract(&executor, &contract).await.unwrap();
+        assert!(estimate >= consumed);
+        assert!(call_contract(&executor, &contract, Some(estimate)).await.is_success());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn estimate_gas_returns_revert_data_of_failed_call() {
+        let contract = address(0x1f);
+        // PUSH1 0xab PUSH1 0 MSTORE8 PUSH1 1 PUSH1 0 REVERT
+        let bytecode = vec![0x60, 0xab, 0x60, 0x00, 0x53, 0x60, 0x01, 0x60, 0x00, 0xfd];
+        let TestExecutor { executor, .. } = test_executor(&[], vec![contract_account(&contract, bytecode)]);
+
+        let error = estimate_contract(&executor, &contract).await.unwrap_err();
+        let Some(EthExecutorError::ExecutionReverted(output)) = error.downcast_ref::<EthExecutorError>() else {
+            panic!("unexpected error: {error:?}");
+        };
+        assert_eq!(output.as_ref(), &[0xab]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn estimate_gas_is_capped_by_the_gas_the_sender_can_pay_for() {
+        let (sender, receiver, fee_recipient) = (address(0x20), address(0x21), address(0xfe));
+        let sender_account = Account {
+            address: sender.clone(),
+            balance: (30_000 * 7 + 1_000u64).into(),
+            ..Account::default()
+        };
+        let TestExecutor { executor, .. } = test_executor_charging_gas(&fee_recipient, vec![sender_account]);
+        let call = |value: u64| CallInput {
+            from: sender.clone(),
+            to: Some(receiver.clone()),
+            value: value.into(),
+            gas_price: Some(7u64.into()),
+            ..CallInput::default()
+        };
+
+        // without the cap, the call gas cap would cost more than the sender balance
+        let estimate = u64::from(executor.estimate_gas(call(1_000), StoragePointInTime::Present).await.unwrap());
+        assert!((21_000..30_000).contains(&estimate));
+
+        // the transferred value must leave some balance to pay for gas
+        let error = executor.estimate_gas(call(30_000 * 7 + 1_000), StoragePointInTime::Present).await.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<EthExecutorError>(),
+            Some(EthExecutorError::InsufficientFundsForTransfer)
+        ));
+    }
+
+    // -------------------------------------------------------------------------
+    // Tracing
+    // -------------------------------------------------------------------------
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn trace_observes_previous_transactions_of_the_block() {
+        let TestExecutor { executor, .. } = test_executor(&[], test_accounts());
+        let sender = test_accounts()[0].address.clone();
+        let (middle, receiver) = (address(0x33), address(0x44));
+
+        // second transaction can only be executed after the first one funds its sender
+        let block = mine_in_single_block(
+            &executor,
+            vec![transfer(&executor, &sender, 0, &middle, 1_000), transfer(&executor, &middle, 0, &receiver, 500)],
+        )
+        .await;
+        assert_eq!(block.transactions.len(), 2);
+
+        // transactions are traced with the same results they were mined with
+        let tracer = Tracer::CallTracer {
+            only_top_call: false,
+            with_log: false,
+        };
+        let (execution, _) = executor.trace_transaction(&block.transactions[1], tracer.clone()).await.unwrap();
+        assert!(execution.is_success());
+        assert_eq!(execution.gas, block.transactions[1].execution.gas);
+
+        let traces = executor.trace_block(&block, tracer).await.unwrap();
+        assert_eq!(traces.len(), 2);
+        for ((execution, _), mined) in traces.iter().zip(&block.transactions) {
+            assert!(execution.is_success());
+            assert_eq!(execution.gas, mined.execution.gas);
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    // Batch execution
+    // -------------------------------------------------------------------------
+
+    /// Sorts the account changes of an execution, because their order depends on the order the EVM touched the accounts.
+    fn sorted(mut execution: Execution) -> Execution {
+        execution.changes.sort_by(|a, b| a.address.as_ref().cmp(b.address.as_ref()));
+        execution
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn execute_batch_executes_again_only_conflicting_transactions() {
+        let TestExecutor { executor, executions, .. } = test_executor(&[], test_accounts());
+        let accounts = test_accounts();
+        let middle = address(0x55);
+
+        // third transaction depends on the first one, while the others are independent
+        let transactions = vec![
+            transfer(&executor, &accounts[0].address, 0, &middle, 1_000),
+            transfer(&executor, &accounts[1].address, 0, &address(0x66), 10),
+            transfer(&executor, &middle, 0, &address(0x77), 500),
+            transfer(&executor, &accounts[2].address, 0, &address(0x88), 20),
+        ];
+        let block = executor.read_pending_block_at(NOW_IN_SECS).await.unwrap().header;
+
+        // all transactions are executed once, and only the dependent one is executed again
+        let results = executor.execute_batch(&transactions, &block).await;
+        assert_eq!(executions.load(Ordering::SeqCst), transactions.len() + 1);
+
+        // results are the same of executing the transactions serially
+        let mut state_overlay = StateOverlay::default();
+        for (transaction, result) in transactions.iter().zip(results) {
+            let serial = executor
+                .execute_transaction_in_evm(transaction, &block, Arc::new(state_overlay.clone()))
+                .await
+                .unwrap();
+            state_overlay.apply(&serial);
+            assert_eq!(sorted(result.unwrap()), sorted(serial));
+        }
+    }
+}
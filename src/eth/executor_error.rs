@@ -0,0 +1,90 @@
+use std::time::Duration;
+
+use crate::eth::primitives::Address;
+use crate::eth::primitives::Bytes;
+use crate::eth::primitives::ChainId;
+use crate::eth::primitives::Gas;
+use crate::eth::primitives::Nonce;
+use crate::eth::primitives::Wei;
+
+/// Errors that prevent a transaction from being executed.
+///
+/// Validation messages follow the ones returned by geth, because clients and libraries parse them.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum EthExecutorError {
+    /// Transaction was signed for a different chain.
+    #[error("invalid chain id: have {actual}, want {expected}")]
+    InvalidChainId { expected: ChainId, actual: ChainId },
+
+    /// Transaction nonce was already used by the sender.
+    #[error("nonce too low: address {address}, tx: {actual} state: {expected}")]
+    NonceTooLow { address: Address, expected: Nonce, actual: Nonce },
+
+    /// Transaction nonce is ahead of the sender nonce.
+    #[error("nonce too high: address {address}, tx: {actual} state: {expected}")]
+    NonceTooHigh { address: Address, expected: Nonce, actual: Nonce },
+
+    /// Sender balance does not cover the transferred value and the maximum gas cost.
+    #[error("insufficient funds for gas * price + value: address {address} have {have} want {want}")]
+    InsufficientFunds { address: Address, have: Wei, want: Wei },
+
+    /// Sender balance does not cover the value transferred by a call when estimating gas.
+    #[error("insufficient funds for transfer")]
+    InsufficientFundsForTransfer,
+
+    /// Dynamic fee transaction offers a priority fee higher than its maximum fee.
+    #[error("max priority fee per gas higher than max fee per gas: address {address}, maxPriorityFeePerGas: {max_priority_fee}, maxFeePerGas: {max_fee}")]
+    TipAboveFeeCap { address: Address, max_priority_fee: Wei, max_fee: Wei },
+
+    /// Call specifies both a gas price and the fee caps of dynamic fee transactions.
+    #[error("both gasPrice and (maxFeePerGas or maxPriorityFeePerGas) specified")]
+    GasPriceWithDynamicFees,
+
+    /// Transaction maximum fee does not cover the base fee of the block it would be mined in.
+    #[error("max fee per gas less than block base fee: address {address}, maxFeePerGas: {max_fee}, baseFee: {base_fee}")]
+    FeeCapTooLow { address: Address, max_fee: Wei, base_fee: Wei },
+
+    /// Transaction gas does not cover the cost of the transaction before any code is executed.
+    #[error("intrinsic gas too low: have {have}, want {want}")]
+    IntrinsicGasTooLow { have: Gas, want: Gas },
+
+    /// Transaction gas is above the limit a single transaction can use.
+    #[error("exceeds block gas limit: have {have}, limit {limit}")]
+    GasLimitExceeded { have: Gas, limit: Gas },
+
+    /// Transaction pool has no room for a queued transaction.
+    #[error("txpool is full")]
+    TxPoolFull,
+
+    /// Transactions of a block use more gas than the block gas limit.
+    #[error("block gas limit reached: have {have}, limit {limit}")]
+    BlockGasLimitExceeded { have: Gas, limit: Gas },
+
+    /// Transaction input is above the largest size that can be stored.
+    #[error("oversized data")]
+    OversizedData,
+
+    /// Contract deployment creation code is above the size limit (EIP-3860).
+    #[error("max initcode size exceeded: code size {size} limit {limit}")]
+    InitcodeSizeExceeded { size: usize, limit: usize },
+
+    /// Call reverted with the highest gas limit available to it when estimating gas.
+    #[error("execution reverted")]
+    ExecutionReverted(Bytes),
+
+    /// Call halted for a reason other than running out of gas when estimating gas.
+    #[error("{0}")]
+    ExecutionHalted(String),
+
+    /// Call ran out of gas with the highest gas limit available to it when estimating gas.
+    #[error("gas required exceeds allowance ({0})")]
+    GasAllowanceExceeded(Gas),
+
+    /// Transaction kept conflicting with concurrent transactions until the maximum number of attempts was reached.
+    #[error("Transaction conflicted with concurrent transactions in {0} attempts.")]
+    ConflictMaxRetries(usize),
+
+    /// Transaction kept conflicting with concurrent transactions until the deadline was reached.
+    #[error("Transaction conflicted with concurrent transactions for more than {0:?}.")]
+    ConflictTimeout(Duration),
+}
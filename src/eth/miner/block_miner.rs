@@ -7,59 +7,122 @@
 
 use std::sync::Arc;
 
+use anyhow::anyhow;
 use ethereum_types::BloomInput;
 use keccak_hasher::KeccakHasher;
 use nonempty::NonEmpty;
 
+use crate::eth::primitives::Address;
 use crate::eth::primitives::Block;
+use crate::eth::primitives::BlockHeader;
 use crate::eth::primitives::BlockNumber;
+use crate::eth::primitives::BlockSelection;
 use crate::eth::primitives::Execution;
+use crate::eth::primitives::ExecutionAccountChanges;
+use crate::eth::primitives::FeePolicy;
+use crate::eth::primitives::Gas;
+use crate::eth::primitives::Genesis;
 use crate::eth::primitives::Hash;
 use crate::eth::primitives::Index;
 use crate::eth::primitives::LogMined;
+use crate::eth::primitives::LogsBloom;
 use crate::eth::primitives::TransactionInput;
 use crate::eth::primitives::TransactionMined;
 use crate::eth::storage::EthStorage;
+use crate::eth::storage::EthStorageError;
 use crate::ext::not;
 
 pub struct BlockMiner {
     storage: Arc<dyn EthStorage>,
+
+    /// Maximum gas used by all transactions of a mined block.
+    gas_limit: Gas,
+
+    /// Calculates the base fee of mined blocks.
+    fee_policy: FeePolicy,
+
+    /// Account recorded as the miner of mined blocks.
+    coinbase: Address,
 }
 
 impl BlockMiner {
     /// Initializes a new BlockMiner with storage access.
     /// The storage component is crucial for retrieving the current state and persisting new blocks.
-    pub fn new(storage: Arc<dyn EthStorage>) -> Self {
-        Self { storage }
+    pub fn new(storage: Arc<dyn EthStorage>, gas_limit: Gas, fee_policy: FeePolicy, coinbase: Address) -> Self {
+        Self {
+            storage,
+            gas_limit,
+            fee_policy,
+            coinbase,
+        }
     }
 
     /// Constructs the genesis block, the first block in the blockchain.
-    /// This block serves as the foundation of the blockchain, with a fixed state and no previous block.
-    pub fn genesis() -> Block {
-        Block::new_with_capacity(BlockNumber::ZERO, 1702568764, 0)
+    /// This block serves as the foundation of the blockchain, with the state described by the genesis and no previous block.
+    pub fn genesis(genesis: &Genesis) -> Block {
+        let mut block = Block::new_with_capacity(BlockNumber::ZERO, *genesis.timestamp, 0);
+        block.header.base_fee = genesis.base_fee.clone();
+        block.header.miner = genesis.coinbase.clone();
+        block.header.size = block.calculate_size();
+        block
+    }
+
+    /// Builds the block that will be mined next with the given timestamp, without reserving its number.
+    /// Used as the context of transactions and calls executed on top of the current state.
+    ///
+    /// It only reads the latest block from the storage, so it does not need to wait for blocks being mined.
+    ///
+    /// Transactions executed in the header of this block are mined with the same header, as long as no other block is mined before.
+    pub async fn pending_block(&self, timestamp_in_secs: u64) -> anyhow::Result<Block> {
+        let parent = self.parent().await?;
+        let number = self.storage.read_current_block_number().await?.successor();
+
+        let mut block = Block::new_with_capacity(number, timestamp_in_secs, 0);
+        self.link_to_parent(&mut block, parent);
+        Ok(block)
+    }
+
+    /// Checks if a header built by `pending_block` still describes the next block, which is not the case after another block is mined.
+    pub async fn is_pending(&self, header: &BlockHeader) -> anyhow::Result<bool> {
+        let pending = self.pending_block(*header.timestamp_in_secs).await?;
+        Ok(&pending.header == header)
+    }
+
+    /// Mine one block with no transactions.
+    /// Used to advance the chain when there is nothing to execute, like when requested by development methods.
+    pub async fn mine_with_no_transactions(&self, timestamp_in_secs: u64) -> anyhow::Result<Block> {
+        let mut block = self.pending_block(timestamp_in_secs).await?;
+        block.header.size = block.calculate_size();
+        block.update_hash();
+        Ok(block)
     }
 
     /// Mine one block with a single transaction.
     /// Internally, it wraps the single transaction into a format suitable for `mine_with_many_transactions`,
     /// enabling consistent processing for both single and multiple transaction scenarios.
-    pub async fn mine_with_one_transaction(&mut self, input: TransactionInput, execution: Execution) -> anyhow::Result<Block> {
+    pub async fn mine_with_one_transaction(&self, header: BlockHeader, input: TransactionInput, execution: Execution) -> anyhow::Result<Block> {
         let transactions = NonEmpty::new((input, execution));
-        self.mine_with_many_transactions(transactions).await
+        self.mine_with_many_transactions(header, transactions).await
     }
 
     /// Mines a new block from one or more transactions.
     /// This is the core function for block creation, processing each transaction, generating the necessary logs,
     /// and finalizing the block. It is used both directly for multiple transactions and indirectly by `mine_with_one_transaction`.
     ///
+    /// The header is the pending block header the transactions were executed with, as blocks are stamped before their transactions are
+    /// executed. It must still be pending, otherwise the block does not extend the chain.
+    ///
     /// TODO: Future enhancements may include breaking down this method for improved readability and maintenance.
-    pub async fn mine_with_many_transactions(&mut self, transactions: NonEmpty<(TransactionInput, Execution)>) -> anyhow::Result<Block> {
+    pub async fn mine_with_many_transactions(&self, header: BlockHeader, transactions: NonEmpty<(TransactionInput, Execution)>) -> anyhow::Result<Block> {
+        if not(self.is_pending(&header).await?) {
+            return Err(anyhow!("Block {} is not the next block of the chain anymore.", header.number));
+        }
+
         // init block
-        let number = self.storage.increment_block_number().await?;
-        let block_timpestamp = transactions
-            .minimum_by(|(_, e1), (_, e2)| e1.block_timestamp_in_secs.cmp(&e2.block_timestamp_in_secs))
-            .1
-            .block_timestamp_in_secs;
-        let mut block = Block::new_with_capacity(number, block_timpestamp, transactions.len());
+        let mut block = Block {
+            header,
+            transactions: Vec::with_capacity(transactions.len()),
+        };
 
         // mine transactions and logs
         let mut log_index = Index::ZERO;
@@ -67,11 +130,12 @@ impl BlockMiner {
             let transaction_index = Index::new(tx_idx as u16);
             // mine logs
             let mut mined_logs: Vec<LogMined> = Vec::with_capacity(execution.logs.len());
+            let mut logs_bloom = LogsBloom::default();
             for mined_log in execution.logs.clone() {
                 // calculate bloom
-                block.header.bloom.accrue(BloomInput::Raw(mined_log.address.as_ref()));
+                logs_bloom.accrue(BloomInput::Raw(mined_log.address.as_ref()));
                 for topic in &mined_log.topics {
-                    block.header.bloom.accrue(BloomInput::Raw(topic.as_ref()));
+                    logs_bloom.accrue(BloomInput::Raw(topic.as_ref()));
                 }
 
                 // mine log
@@ -89,11 +153,18 @@ impl BlockMiner {
                 log_index = log_index + Index::ONE;
             }
 
+            // calculate gas and bloom of the block up to this transaction
+            block.header.gas = block.header.gas + execution.gas.clone();
+            block.header.bloom.accrue_bloom(&logs_bloom);
+
             // mine transaction
             let mined_transaction = TransactionMined {
+                effective_gas_price: input.effective_gas_price(&block.header.base_fee),
                 input,
                 execution,
                 transaction_index,
+                cumulative_gas_used: block.header.gas.clone(),
+                logs_bloom,
                 block_number: block.header.number,
                 block_hash: block.header.hash.clone(),
                 logs: mined_logs,
@@ -103,22 +174,60 @@ impl BlockMiner {
             block.transactions.push(mined_transaction);
         }
 
-        // calculate transactions hash
+        // calculate transactions and receipts hashes
         if not(block.transactions.is_empty()) {
             let transactions_hashes: Vec<&Hash> = block.transactions.iter().map(|x| &x.input.hash).collect();
             block.header.transactions_root = triehash::ordered_trie_root::<KeccakHasher, _>(transactions_hashes).into();
+
+            let receipts = block.transactions.iter().map(|x| x.to_rlp_receipt());
+            block.header.receipts_root = triehash::ordered_trie_root::<KeccakHasher, _>(receipts).into();
         }
 
-        // calculate final block hash
+        // calculate size after all header fields are filled, as hashes have fixed length and do not change it
+        block.header.size = block.calculate_size();
 
-        // replicate calculated block hash from header to transactions and logs
-        for transaction in block.transactions.iter_mut() {
-            transaction.block_hash = block.header.hash.clone();
-            for log in transaction.logs.iter_mut() {
-                log.block_hash = block.header.hash.clone();
-            }
-        }
+        // calculate final block hash and replicate it to transactions and logs
+        block.update_hash();
+
+        Ok(block)
+    }
+
+    /// Saves a mined block and makes it the current block of the chain.
+    ///
+    /// The block number is only incremented after the block is saved, so blocks that conflict with the storage do not leave gaps.
+    pub async fn save(&self, block: Block) -> Result<Block, EthStorageError> {
+        let block = self.storage.save_block(block).await?;
+        self.storage.increment_block_number().await?;
+        Ok(block)
+    }
 
+    /// Saves a mined block together with changes that override the state of some accounts, making it the current block of the chain.
+    ///
+    /// The overrides are committed by the state root and by the hash of the saved block.
+    pub async fn save_with_overrides(&self, block: Block, overrides: Vec<ExecutionAccountChanges>) -> Result<Block, EthStorageError> {
+        let block = self.storage.save_block_with_overrides(block, overrides).await?;
+        self.storage.increment_block_number().await?;
         Ok(block)
     }
+
+    /// Reads the header of the latest block from the storage, which is the parent of the block being mined.
+    async fn parent(&self) -> anyhow::Result<Option<BlockHeader>> {
+        let parent = self.storage.read_block(&BlockSelection::Latest).await?;
+        Ok(parent.map(|block| block.header))
+    }
+
+    /// Fills the header fields of a new block that depend on its parent and on the miner configuration.
+    ///
+    /// Block timestamps never go backwards, so a block is never older than its parent even if the clock is.
+    fn link_to_parent(&self, block: &mut Block, parent: Option<BlockHeader>) {
+        if let Some(ref parent) = parent {
+            if *parent.timestamp_in_secs > *block.header.timestamp_in_secs {
+                block.header.timestamp_in_secs = parent.timestamp_in_secs.clone();
+            }
+        }
+        block.header.base_fee = self.fee_policy.base_fee(parent.as_ref());
+        block.header.parent_hash = parent.map(|parent| parent.hash).unwrap_or_default();
+        block.header.gas_limit = self.gas_limit.clone();
+        block.header.miner = self.coinbase.clone();
+    }
 }
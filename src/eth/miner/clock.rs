@@ -0,0 +1,139 @@
+//! Clock
+//!
+//! Source of the time used to compute block timestamps. The system clock is
+//! used in production, while a manual clock lets tests and replays control
+//! the timestamp of each block. The offset clock runs ahead of another clock
+//! and is moved by development methods like `evm_increaseTime`.
+
+use std::sync::atomic::AtomicI64;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use chrono::Utc;
+
+/// Source of the current time.
+pub trait Clock: Send + Sync + 'static {
+    /// Current time in seconds since the Unix epoch.
+    fn now_in_secs(&self) -> u64;
+}
+
+/// Clock that follows the system time.
+#[derive(Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_in_secs(&self) -> u64 {
+        Utc::now().timestamp().max(0) as u64
+    }
+}
+
+/// Clock that only moves when told to.
+#[derive(Debug, Default)]
+pub struct ManualClock {
+    now_in_secs: AtomicU64,
+}
+
+impl ManualClock {
+    /// Creates a clock stopped at the given time.
+    pub fn new(now_in_secs: u64) -> Self {
+        Self {
+            now_in_secs: AtomicU64::new(now_in_secs),
+        }
+    }
+
+    /// Moves the clock to the given time.
+    pub fn set(&self, now_in_secs: u64) {
+        self.now_in_secs.store(now_in_secs, Ordering::SeqCst);
+    }
+
+    /// Moves the clock forward.
+    pub fn advance(&self, secs: u64) {
+        self.now_in_secs.fetch_add(secs, Ordering::SeqCst);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now_in_secs(&self) -> u64 {
+        self.now_in_secs.load(Ordering::SeqCst)
+    }
+}
+
+/// Clock that runs ahead of another clock by an adjustable offset.
+pub struct OffsetClock {
+    inner: Arc<dyn Clock>,
+    offset_in_secs: AtomicI64,
+}
+
+impl OffsetClock {
+    /// Creates a clock with no offset from the inner clock.
+    pub fn new(inner: Arc<dyn Clock>) -> Self {
+        Self {
+            inner,
+            offset_in_secs: AtomicI64::new(0),
+        }
+    }
+
+    /// Moves the clock forward, returning the total offset in seconds.
+    pub fn increase(&self, secs: u64) -> i64 {
+        let secs = secs as i64;
+        self.offset_in_secs.fetch_add(secs, Ordering::SeqCst) + secs
+    }
+
+    /// Moves the clock to the given time. It keeps running from it at the pace of the inner clock.
+    pub fn set(&self, now_in_secs: u64) {
+        let offset_in_secs = now_in_secs as i64 - self.inner.now_in_secs() as i64;
+        self.offset_in_secs.store(offset_in_secs, Ordering::SeqCst);
+    }
+
+    /// Offset in seconds the clock runs ahead of the inner clock.
+    pub fn offset(&self) -> i64 {
+        self.offset_in_secs.load(Ordering::SeqCst)
+    }
+
+    /// Replaces the offset the clock runs ahead of the inner clock, like when restoring a snapshot.
+    pub fn set_offset(&self, offset_in_secs: i64) {
+        self.offset_in_secs.store(offset_in_secs, Ordering::SeqCst);
+    }
+}
+
+impl Clock for OffsetClock {
+    fn now_in_secs(&self) -> u64 {
+        let now_in_secs = self.inner.now_in_secs() as i64 + self.offset_in_secs.load(Ordering::SeqCst);
+        now_in_secs.max(0) as u64
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Tests
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::eth::miner::Clock;
+    use crate::eth::miner::ManualClock;
+    use crate::eth::miner::OffsetClock;
+
+    #[test]
+    fn offset_clock_follows_inner_clock() {
+        let manual = Arc::new(ManualClock::new(1_000));
+        let clock = OffsetClock::new(Arc::clone(&manual) as Arc<dyn Clock>);
+        assert_eq!(clock.now_in_secs(), 1_000);
+
+        // increased time accumulates
+        assert_eq!(clock.increase(10), 10);
+        assert_eq!(clock.increase(5), 15);
+        assert_eq!(clock.now_in_secs(), 1_015);
+
+        // set time keeps running with the inner clock
+        clock.set(2_000);
+        manual.advance(3);
+        assert_eq!(clock.now_in_secs(), 2_003);
+
+        // restored offset keeps running with the inner clock
+        clock.set_offset(15);
+        assert_eq!(clock.offset(), 15);
+        assert_eq!(clock.now_in_secs(), 1_018);
+    }
+}
@@ -6,8 +6,15 @@
 //!
 //! Components:
 //! - `block_miner`: The core of the block mining process, this submodule contains the logic and algorithms necessary for constructing new blocks from processed transactions. It is responsible for organizing transaction data, validating transactions, and finalizing block creation.
+//! - `clock`: Source of the time used to compute block timestamps, which can be the system time or a time controlled by tests and replays.
 //!
 //! The Block Miner plays a crucial role in the overall functionality of the Stratus blockchain, aligning with the project's vision of a flexible, efficient, and responsive blockchain system.
 
 mod block_miner;
+mod clock;
+
 pub use block_miner::BlockMiner;
+pub use clock::Clock;
+pub use clock::ManualClock;
+pub use clock::OffsetClock;
+pub use clock::SystemClock;
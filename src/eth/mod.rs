@@ -11,6 +11,7 @@
 //! - primitives: Basic data structures such as Blocks and Transactions.
 //! - rpc: Handles communication with Ethereum nodes via RPC.
 //! - storage: Stores and retrieves blockchain data, ensuring data integrity and access.
+//! - txpool: Holds transactions that are not mined yet, queueing the ones sent with nonce gaps.
 //!
 //!
 //! A key feature of this module is the `executor` submodule, specifically its `transact` function.
@@ -22,9 +23,13 @@
 pub mod codegen;
 pub mod evm;
 mod executor;
+mod executor_error;
 pub mod miner;
 pub mod primitives;
 pub mod rpc;
 pub mod storage;
+pub mod txpool;
 
+pub use executor::AccountOverride;
 pub use executor::EthExecutor;
+pub use executor_error::EthExecutorError;
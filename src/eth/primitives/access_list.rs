@@ -0,0 +1,149 @@
+//! Access List Module
+//!
+//! Lists the accounts and storage slots a transaction plans to access, as
+//! introduced by EIP-2930 and also used by EIP-1559 transactions. Listed
+//! accounts and slots are loaded by the EVM before the transaction starts, so
+//! accessing them during the execution is cheaper in exchange for a fee paid
+//! upfront for each item.
+
+use std::ops::Deref;
+
+use ethereum_types::H256;
+use ethers_core::types::transaction::eip2930::AccessList as EthersAccessList;
+use ethers_core::types::transaction::eip2930::AccessListItem as EthersAccessListItem;
+use itertools::Itertools;
+use revm::primitives::Address as RevmAddress;
+use revm::primitives::U256 as RevmU256;
+use rlp::Decodable;
+use rlp::Encodable;
+use rlp::RlpStream;
+use sqlx::database::HasValueRef;
+use sqlx::error::BoxDynError;
+
+use crate::eth::primitives::Address;
+use crate::eth::primitives::SlotIndex;
+
+/// Accounts and slots accessed by a transaction.
+#[derive(Debug, Clone, Default, PartialEq, Eq, fake::Dummy, serde::Serialize, serde::Deserialize)]
+pub struct AccessList(Vec<AccessListItem>);
+
+/// Account and its slots accessed by a transaction.
+#[derive(Debug, Clone, Default, PartialEq, Eq, fake::Dummy, serde::Serialize, serde::Deserialize)]
+pub struct AccessListItem {
+    pub address: Address,
+    pub slots: Vec<SlotIndex>,
+}
+
+impl AccessList {
+    /// Counts the slots of all accounts.
+    pub fn slots_len(&self) -> usize {
+        self.0.iter().map(|item| item.slots.len()).sum()
+    }
+}
+
+impl Deref for AccessList {
+    type Target = Vec<AccessListItem>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Serialization / Deserialization
+// -----------------------------------------------------------------------------
+impl Encodable for AccessList {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        EthersAccessList::from(self.clone()).rlp_append(s);
+    }
+}
+
+impl Decodable for AccessList {
+    fn decode(rlp: &rlp::Rlp) -> Result<Self, rlp::DecoderError> {
+        EthersAccessList::decode(rlp).map(Into::into)
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Conversions: Other -> Self
+// -----------------------------------------------------------------------------
+impl From<Vec<AccessListItem>> for AccessList {
+    fn from(value: Vec<AccessListItem>) -> Self {
+        Self(value)
+    }
+}
+
+impl From<EthersAccessList> for AccessList {
+    fn from(value: EthersAccessList) -> Self {
+        Self(value.0.into_iter().map_into().collect())
+    }
+}
+
+impl From<EthersAccessListItem> for AccessListItem {
+    fn from(value: EthersAccessListItem) -> Self {
+        Self {
+            address: value.address.into(),
+            slots: value.storage_keys.into_iter().map(|key| key.to_fixed_bytes().into()).collect(),
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Conversions: sqlx -> Self
+// -----------------------------------------------------------------------------
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for AccessList {
+    fn decode(value: <sqlx::Postgres as HasValueRef<'r>>::ValueRef) -> Result<Self, BoxDynError> {
+        let value = <Vec<u8> as sqlx::Decode<sqlx::Postgres>>::decode(value)?;
+        Ok(rlp::decode(&value)?)
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for AccessList {
+    fn type_info() -> <sqlx::Postgres as sqlx::Database>::TypeInfo {
+        sqlx::postgres::PgTypeInfo::with_name("BYTEA")
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Conversions: Self -> Other
+// -----------------------------------------------------------------------------
+impl From<AccessList> for EthersAccessList {
+    fn from(value: AccessList) -> Self {
+        Self(value.0.into_iter().map_into().collect())
+    }
+}
+
+impl From<AccessListItem> for EthersAccessListItem {
+    fn from(value: AccessListItem) -> Self {
+        Self {
+            address: value.address.into(),
+            storage_keys: value.slots.into_iter().map(|index| H256(index.into())).collect(),
+        }
+    }
+}
+
+impl From<AccessListItem> for (RevmAddress, Vec<RevmU256>) {
+    fn from(value: AccessListItem) -> Self {
+        let slots = value.slots.into_iter().map(|index| RevmU256::from_be_bytes(<[u8; 32]>::from(index))).collect();
+        (value.address.into(), slots)
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Tests
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use crate::eth::primitives::*;
+
+    #[test]
+    fn rlp_roundtrip() {
+        let access_list = AccessList::from(vec![AccessListItem {
+            address: Address::new([1; 20]),
+            slots: vec![SlotIndex::from(1u64), SlotIndex::from(2u64)],
+        }]);
+        let encoded = rlp::encode(&access_list);
+        assert_eq!(rlp::decode::<AccessList>(&encoded).unwrap(), access_list);
+        assert_eq!(access_list.slots_len(), 2);
+    }
+}
@@ -8,12 +8,14 @@
 //! tracking account states and differentiating between standard accounts and
 //! contract accounts.
 
+use ethers_core::utils::keccak256;
 use revm::primitives::AccountInfo as RevmAccountInfo;
 use revm::primitives::Address as RevmAddress;
 use revm::primitives::KECCAK_EMPTY;
 
 use crate::eth::primitives::Address;
 use crate::eth::primitives::Bytes;
+use crate::eth::primitives::Hash;
 use crate::eth::primitives::Nonce;
 use crate::eth::primitives::Wei;
 use crate::ext::OptionExt;
@@ -42,6 +44,14 @@ impl Account {
             None => false,
         }
     }
+
+    /// Calculates the keccak of the bytecode, or the keccak of empty data if the account is not a contract.
+    pub fn code_hash(&self) -> Hash {
+        match self.bytecode {
+            Some(ref bytecode) => Hash::new(keccak256(bytecode)),
+            None => KECCAK_EMPTY.0.into(),
+        }
+    }
 }
 
 // -----------------------------------------------------------------------------
@@ -63,10 +73,11 @@ impl From<(RevmAddress, RevmAccountInfo)> for Account {
 // -----------------------------------------------------------------------------
 impl From<Account> for RevmAccountInfo {
     fn from(value: Account) -> Self {
+        let code_hash = value.code_hash();
         Self {
             nonce: value.nonce.into(),
             balance: value.balance.into(),
-            code_hash: KECCAK_EMPTY,
+            code_hash: code_hash.into(),
             code: value.bytecode.map_into(),
         }
     }
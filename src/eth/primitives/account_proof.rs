@@ -0,0 +1,70 @@
+//! Account Proof Module
+//!
+//! Merkle proofs of an account and some of its storage slots against the
+//! state root of a block, as returned by `eth_getProof` (EIP-1186). The
+//! proofs allow external systems to verify balances and contract storage
+//! without trusting the node that served them.
+
+use ethereum_types::H256;
+use ethereum_types::U64;
+use ethers_core::types::EIP1186ProofResponse;
+use ethers_core::types::StorageProof as EthersStorageProof;
+use itertools::Itertools;
+
+use crate::eth::primitives::Account;
+use crate::eth::primitives::Bytes;
+use crate::eth::primitives::Hash;
+use crate::eth::primitives::Slot;
+
+/// Proof of an account and some of its slots.
+#[derive(Debug, Clone)]
+pub struct AccountProof {
+    /// Account at the point in time of the proof.
+    pub account: Account,
+
+    /// Root of the account storage trie.
+    pub storage_root: Hash,
+
+    /// Trie nodes from the state root to the account.
+    pub proof: Vec<Bytes>,
+
+    /// Proofs of the requested slots.
+    pub slots: Vec<SlotProof>,
+}
+
+/// Proof of a slot of an account.
+#[derive(Debug, Clone)]
+pub struct SlotProof {
+    /// Slot at the point in time of the proof.
+    pub slot: Slot,
+
+    /// Trie nodes from the storage root to the slot.
+    pub proof: Vec<Bytes>,
+}
+
+// -----------------------------------------------------------------------------
+// Conversions: Self -> Other
+// -----------------------------------------------------------------------------
+impl From<AccountProof> for EIP1186ProofResponse {
+    fn from(value: AccountProof) -> Self {
+        Self {
+            address: value.account.address.clone().into(),
+            balance: value.account.balance.clone().into(),
+            code_hash: value.account.code_hash().into(),
+            nonce: U64::from(u64::from(value.account.nonce)),
+            storage_hash: value.storage_root.into(),
+            account_proof: value.proof.into_iter().map_into().collect(),
+            storage_proof: value.slots.into_iter().map_into().collect(),
+        }
+    }
+}
+
+impl From<SlotProof> for EthersStorageProof {
+    fn from(value: SlotProof) -> Self {
+        Self {
+            key: H256(value.slot.index.into()),
+            proof: value.proof.into_iter().map_into().collect(),
+            value: value.slot.value.as_u256(),
+        }
+    }
+}
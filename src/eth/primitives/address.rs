@@ -11,7 +11,9 @@
 
 use std::fmt::Display;
 use std::ops::Deref;
+use std::str::FromStr;
 
+use anyhow::anyhow;
 use ethabi::Token;
 use ethereum_types::H160;
 use ethers_core::types::NameOrAddress;
@@ -55,7 +57,8 @@ impl Address {
 
     /// Checks if current address should have their updates ignored.
     ///
-    /// * Coinbase is ignored because we do not charge gas, otherwise it will have to be updated for every transaction.
+    /// * Coinbase is ignored because it receives no fees, otherwise it will have to be updated for every transaction. When gas is
+    ///   charged, fees are credited to the configured fee recipient instead.
     /// * Not sure if zero address should be ignored or not.
     pub fn is_ignored(&self) -> bool {
         self.is_coinbase() || self.is_zero()
@@ -101,6 +104,20 @@ impl From<NameOrAddress> for Address {
     }
 }
 
+impl FromStr for Address {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match H160::from_str(s) {
+            Ok(parsed) => Ok(Self(parsed)),
+            Err(e) => {
+                tracing::warn!(reason = ?e, value = %s, "failed to parse address");
+                Err(anyhow!("Failed to parse field '{}' with value '{}'", "address", s))
+            }
+        }
+    }
+}
+
 // -----------------------------------------------------------------------------
 // Conversions: sqlx -> Self
 // -----------------------------------------------------------------------------
@@ -12,11 +12,13 @@ use ethereum_types::H256;
 use ethers_core::types::Block as EthersBlock;
 use ethers_core::types::Transaction as EthersTransaction;
 use itertools::Itertools;
+use rlp::RlpStream;
 use serde_json::Value as JsonValue;
 
 use crate::eth::primitives::BlockHeader;
 use crate::eth::primitives::BlockNumber;
 use crate::eth::primitives::Hash;
+use crate::eth::primitives::Size;
 use crate::eth::primitives::TransactionMined;
 
 #[derive(Debug, Clone, PartialEq, Eq, fake::Dummy, serde::Serialize, serde::Deserialize)]
@@ -55,6 +57,32 @@ impl Block {
     pub fn hash(&self) -> &Hash {
         &self.header.hash
     }
+
+    /// Calculates the size in bytes of the RLP-encoded block with its header, transactions and no uncles.
+    pub fn calculate_size(&self) -> Size {
+        let mut stream = RlpStream::new_list(3);
+        stream.append(&self.header);
+        stream.begin_list(self.transactions.len());
+        for transaction in &self.transactions {
+            let ethers_transaction = EthersTransaction::from(transaction.input.clone());
+            stream.append_raw(&ethers_transaction.rlp(), 1);
+        }
+        stream.begin_list(0);
+        stream.out().len().into()
+    }
+
+    /// Calculates the block hash from the header and replicates it to transactions and logs.
+    ///
+    /// Must be called after any header field changes.
+    pub fn update_hash(&mut self) {
+        self.header.hash = self.header.compute_hash();
+        for transaction in self.transactions.iter_mut() {
+            transaction.block_hash = self.header.hash.clone();
+            for log in transaction.logs.iter_mut() {
+                log.block_hash = self.header.hash.clone();
+            }
+        }
+    }
 }
 
 // -----------------------------------------------------------------------------
@@ -7,51 +7,84 @@
 //! elements are essential for blockchain verification and consensus mechanisms,
 //! as well as for navigating and interpreting the blockchain.
 
+use ethereum_types::H160;
+use ethereum_types::H256;
 use ethereum_types::H64;
 use ethereum_types::U256;
+use ethereum_types::U64;
 use ethers_core::types::Block as EthersBlock;
+use ethers_core::utils::keccak256;
 use fake::Dummy;
 use fake::Fake;
 use fake::Faker;
 use hex_literal::hex;
 use jsonrpsee::SubscriptionMessage;
+use rlp::Encodable;
+use rlp::RlpStream;
 
 use crate::eth::primitives::logs_bloom::LogsBloom;
 use crate::eth::primitives::Address;
 use crate::eth::primitives::BlockNumber;
 use crate::eth::primitives::Gas;
 use crate::eth::primitives::Hash;
+use crate::eth::primitives::Size;
 use crate::eth::primitives::UnixTime;
+use crate::eth::primitives::Wei;
 
 /// Special hash used in block mining to indicate no uncle blocks.
 const HASH_EMPTY_UNCLES: Hash = Hash::new(hex!("1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347"));
 
-/// Special hash used in block mining to indicate no transaction root and no receipts root.
-const HASH_EMPTY_TRANSACTIONS_ROOT: Hash = Hash::new(hex!("56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421"));
+/// Maximum gas used by all transactions of a block when not configured.
+const DEFAULT_BLOCK_GAS_LIMIT: Gas = Gas::TRANSACTION_LIMIT;
+
+/// Special hash used in block mining to indicate an empty trie: no transactions, no receipts or no state.
+const HASH_EMPTY_TRIE: Hash = Hash::new(hex!("56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421"));
 
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct BlockHeader {
     pub number: BlockNumber,
     pub hash: Hash,
     pub transactions_root: Hash,
+    pub state_root: Hash,
+    pub receipts_root: Hash,
     pub gas: Gas,
+    pub gas_limit: Gas,
+    pub base_fee: Wei,
     pub bloom: LogsBloom,
+    pub miner: Address,
     pub timestamp_in_secs: UnixTime,
+    pub size: Size,
     pub parent_hash: Hash,
 }
 
 impl BlockHeader {
-    /// Creates a new block header with the given number.
-    pub fn new(number: BlockNumber, _timestamp_in_secs: u64) -> Self {
-        Self {
+    /// Creates a new block header with the given number and timestamp, no parent, no base fee, the default coinbase as miner and the
+    /// default gas limit.
+    ///
+    /// The hash must be computed again with [`BlockHeader::compute_hash`] after the parent hash or the block contents change.
+    pub fn new(number: BlockNumber, timestamp_in_secs: u64) -> Self {
+        let mut header = Self {
             number,
-            hash: number.hash(),
-            transactions_root: HASH_EMPTY_TRANSACTIONS_ROOT,
+            hash: Hash::zero(),
+            transactions_root: HASH_EMPTY_TRIE,
+            state_root: HASH_EMPTY_TRIE,
+            receipts_root: HASH_EMPTY_TRIE,
             gas: Gas::ZERO,
+            gas_limit: DEFAULT_BLOCK_GAS_LIMIT,
+            base_fee: Wei::ZERO,
             bloom: LogsBloom::default(),
-            timestamp_in_secs: UnixTime::ZERO,
-            parent_hash: number.predecessor().map(|n| n.hash()).unwrap_or(Hash::zero()),
-        }
+            miner: Address::COINBASE,
+            timestamp_in_secs: timestamp_in_secs.into(),
+            size: Size::ZERO,
+            parent_hash: Hash::zero(),
+        };
+        header.hash = header.compute_hash();
+        header
+    }
+
+    /// Computes the block hash as the keccak of the RLP-encoded header, as Ethereum does.
+    pub fn compute_hash(&self) -> Hash {
+        Hash::new(keccak256(rlp::encode(self)))
     }
 }
 
@@ -61,14 +94,46 @@ impl Dummy<Faker> for BlockHeader {
             number: faker.fake_with_rng(rng),
             hash: faker.fake_with_rng(rng),
             transactions_root: faker.fake_with_rng(rng),
+            state_root: faker.fake_with_rng(rng),
+            receipts_root: faker.fake_with_rng(rng),
             gas: faker.fake_with_rng(rng),
+            gas_limit: faker.fake_with_rng(rng),
+            base_fee: faker.fake_with_rng(rng),
             bloom: Default::default(),
+            miner: faker.fake_with_rng(rng),
             timestamp_in_secs: faker.fake_with_rng(rng),
+            size: faker.fake_with_rng(rng),
             parent_hash: faker.fake_with_rng(rng),
         }
     }
 }
 
+// -----------------------------------------------------------------------------
+// Serialization / Deserialization
+// -----------------------------------------------------------------------------
+impl Encodable for BlockHeader {
+    /// Encodes the header fields in the order defined by Ethereum, using the same values returned by the JSON-RPC conversion.
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(16);
+        s.append(&H256::from(self.parent_hash.clone()));
+        s.append(&H256::from(HASH_EMPTY_UNCLES));
+        s.append(&H160::from(self.miner.clone()));
+        s.append(&H256::from(self.state_root.clone()));
+        s.append(&H256::from(self.transactions_root.clone()));
+        s.append(&H256::from(self.receipts_root.clone()));
+        s.append(&*self.bloom);
+        s.append(&U256::zero()); // difficulty
+        s.append(&U64::from(self.number));
+        s.append(&U256::from(self.gas_limit.clone()));
+        s.append(&U256::from(self.gas.clone()));
+        s.append(&*self.timestamp_in_secs);
+        s.append_empty_data(); // extra data
+        s.append(&H256::zero()); // mix hash
+        s.append(&H64::zero()); // nonce
+        s.append(&U256::from(self.base_fee.clone()));
+    }
+}
+
 // -----------------------------------------------------------------------------
 // Conversions: Self -> Other
 // -----------------------------------------------------------------------------
@@ -90,7 +155,7 @@ where
 
             // mining: identifiers
             timestamp: (*header.timestamp_in_secs).into(),
-            author: Some(Address::COINBASE.into()),
+            author: Some(header.miner.into()),
 
             // minining: difficulty
             difficulty: U256::zero(),
@@ -98,25 +163,27 @@ where
             nonce: Some(H64::zero()),
 
             // mining: gas
-            gas_limit: Gas::from(100_000_000).into(),
+            gas_limit: header.gas_limit.into(),
             gas_used: header.gas.into(),
-            base_fee_per_gas: Some(U256::zero()),
+            base_fee_per_gas: Some(header.base_fee.into()),
             blob_gas_used: None,
             excess_blob_gas: None,
 
             // transactions
             transactions_root: header.transactions_root.into(),
-            receipts_root: HASH_EMPTY_TRANSACTIONS_ROOT.into(),
+            receipts_root: header.receipts_root.into(),
+
+            // state
+            state_root: header.state_root.into(),
 
             // data
             logs_bloom: Some(*header.bloom),
             extra_data: Default::default(),
+            size: Some(header.size.into()),
 
             // TODO
-            ..Default::default() // state_root: todo!(),
-                                 // seal_fields: todo!(),
+            ..Default::default() // seal_fields: todo!(),
                                  // transactions: todo!(),
-                                 // size: todo!(),
                                  // mix_hash: todo!(),
                                  // withdrawals_root: todo!(),
                                  // withdrawals: todo!(),
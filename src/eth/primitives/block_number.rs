@@ -13,13 +13,11 @@ use std::str::FromStr;
 
 use anyhow::anyhow;
 use ethereum_types::U64;
-use ethers_core::utils::keccak256;
 use fake::Dummy;
 use fake::Faker;
 use sqlx::database::HasValueRef;
 use sqlx::error::BoxDynError;
 
-use super::Hash;
 use crate::gen_newtype_from;
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, derive_more::Add, derive_more::Sub, serde::Serialize, serde::Deserialize)]
@@ -29,11 +27,6 @@ pub struct BlockNumber(U64);
 impl BlockNumber {
     pub const ZERO: BlockNumber = BlockNumber(U64::zero());
 
-    // Returns the keccak256 hash of the block number
-    pub fn hash(&self) -> Hash {
-        Hash::new(keccak256(<[u8; 8]>::from(*self)))
-    }
-
     // Returns the previous block number
     pub fn predecessor(&self) -> Option<Self> {
         if self.0.is_zero() {
@@ -42,6 +35,11 @@ impl BlockNumber {
             Some(Self(self.0 - 1))
         }
     }
+
+    // Returns the next block number
+    pub fn successor(&self) -> Self {
+        Self(self.0 + 1)
+    }
 }
 
 impl Display for BlockNumber {
@@ -2,9 +2,10 @@
 //!
 //! This module offers mechanisms for selecting specific blocks from the
 //! Ethereum blockchain. It allows for the retrieval of blocks based on
-//! different criteria, such as the most recent block (`Latest`), the earliest
-//! block in the chain (`Earliest`), a block by its hash (`Hash`), or a block
-//! by its number (`Number`). This flexibility is crucial in various blockchain
+//! different criteria, such as the most recent block (`Latest`), the block
+//! that will be mined next (`Pending`), the earliest block in the chain
+//! (`Earliest`), a block by its hash (`Hash`), or a block by its number
+//! (`Number`). This flexibility is crucial in various blockchain
 //! operations, including querying block information and validating chain
 //! integrity.
 
@@ -16,6 +17,11 @@ pub enum BlockSelection {
     /// Retrieve the most recent block.
     Latest,
 
+    /// Retrieve the header of the block that will be mined next.
+    ///
+    /// Transactions that are not mined yet are not included, they are read from the transaction pool.
+    Pending,
+
     /// Retrieve the most early block.
     Earliest,
 
@@ -41,7 +47,7 @@ impl<'de> serde::Deserialize<'de> for BlockSelection {
         match value.as_str() {
             // parse special keywords
             "latest" => Ok(Self::Latest),
-            "pending" => Ok(Self::Latest), // map to latest because we do not have the concept of pending blocks internally
+            "pending" => Ok(Self::Pending),
             "earliest" => Ok(Self::Earliest),
 
             // parse hash (64: H256 without 0x prefix; 66: H256 with 0x prefix)
@@ -70,6 +76,12 @@ mod tests {
         assert_eq!(serde_json::from_value::<BlockSelection>(json).unwrap(), BlockSelection::Latest);
     }
 
+    #[test]
+    fn serde_block_number_with_pending() {
+        let json = json!("pending");
+        assert_eq!(serde_json::from_value::<BlockSelection>(json).unwrap(), BlockSelection::Pending);
+    }
+
     #[test]
     fn serde_block_number_with_number() {
         let json = json!("0x2");
@@ -3,21 +3,177 @@
 //! This module defines the structure for Ethereum transaction call inputs,
 //! encompassing essential elements like the sender's address (`from`), the
 //! recipient's address (`to`), the amount of Ether to transfer (`value`), and
-//! the transaction data payload (`data`). It is crucial in constructing and
+//! the transaction data payload (`data`). Calls can also specify the gas, fee
+//! and access list fields of a transaction. It is crucial in constructing and
 //! interpreting transaction calls, especially for smart contract interactions.
 
+use anyhow::anyhow;
+use ethers_core::types::transaction::eip2930::AccessList as EthersAccessList;
+use serde_with::serde_as;
+use serde_with::FromInto;
+
+use crate::config::EvmHardfork;
+use crate::eth::primitives::transaction_input::intrinsic_gas;
+use crate::eth::primitives::AccessList;
 use crate::eth::primitives::Address;
 use crate::eth::primitives::Bytes;
+use crate::eth::primitives::Gas;
+use crate::eth::primitives::Nonce;
 use crate::eth::primitives::Wei;
 
-#[derive(Debug, Clone, serde::Deserialize)]
+/// JSON-RPC input used in methods like `eth_call` and `eth_estimateGas`.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(try_from = "CallInputJson")]
 pub struct CallInput {
+    /// Sender of the call. Defaults to the zero address.
     pub from: Address,
+
     pub to: Option<Address>,
 
-    #[serde(default)]
     pub value: Wei,
 
-    #[serde(default)]
+    /// Call data, sent in the `data` field or in the `input` field, its newer name.
     pub data: Bytes,
+
+    /// Maximum gas the call can use, limited by the node call gas cap.
+    pub gas: Option<Gas>,
+
+    pub gas_price: Option<Wei>,
+
+    pub max_fee_per_gas: Option<Wei>,
+
+    pub max_priority_fee_per_gas: Option<Wei>,
+
+    pub access_list: AccessList,
+
+    /// Nonce the sender is expected to have.
+    pub nonce: Option<Nonce>,
+}
+
+/// JSON-RPC format of [`CallInput`], where the call data can be sent in both the `data` and `input` fields.
+#[serde_as]
+#[derive(serde::Deserialize)]
+struct CallInputJson {
+    #[serde(default)]
+    from: Address,
+
+    to: Option<Address>,
+
+    #[serde(default)]
+    value: Wei,
+
+    data: Option<Bytes>,
+
+    input: Option<Bytes>,
+
+    gas: Option<Gas>,
+
+    #[serde(rename = "gasPrice")]
+    gas_price: Option<Wei>,
+
+    #[serde(rename = "maxFeePerGas")]
+    max_fee_per_gas: Option<Wei>,
+
+    #[serde(rename = "maxPriorityFeePerGas")]
+    max_priority_fee_per_gas: Option<Wei>,
+
+    #[serde(rename = "accessList", default)]
+    #[serde_as(deserialize_as = "FromInto<EthersAccessList>")]
+    access_list: AccessList,
+
+    nonce: Option<Nonce>,
+}
+
+impl CallInput {
+    /// Gas consumed before any code is executed in the given hardfork, as if the call was sent as a transaction.
+    pub fn intrinsic_gas(&self, hardfork: EvmHardfork) -> Gas {
+        intrinsic_gas(self.to.as_ref(), &self.data, &self.access_list, hardfork)
+    }
+
+    /// Checks if the call specifies the fee caps of dynamic fee transactions (EIP-1559) instead of a gas price.
+    pub fn is_dynamic_fee(&self) -> bool {
+        self.max_fee_per_gas.is_some() || self.max_priority_fee_per_gas.is_some()
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Conversions: Other -> Self
+// -----------------------------------------------------------------------------
+impl TryFrom<CallInputJson> for CallInput {
+    type Error = anyhow::Error;
+
+    fn try_from(value: CallInputJson) -> anyhow::Result<Self> {
+        // like geth, both fields are accepted as long as they do not disagree
+        let data = match (value.data, value.input) {
+            (Some(data), Some(input)) if data != input =>
+                return Err(anyhow!(
+                    "both \"data\" and \"input\" are set and not equal. Please use \"input\" to pass transaction call data"
+                )),
+            (_, Some(input)) => input,
+            (Some(data), None) => data,
+            (None, None) => Bytes::default(),
+        };
+
+        Ok(Self {
+            from: value.from,
+            to: value.to,
+            value: value.value,
+            data,
+            gas: value.gas,
+            gas_price: value.gas_price,
+            max_fee_per_gas: value.max_fee_per_gas,
+            max_priority_fee_per_gas: value.max_priority_fee_per_gas,
+            access_list: value.access_list,
+            nonce: value.nonce,
+        })
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Tests
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::eth::primitives::*;
+
+    #[test]
+    fn deserializes_geth_call_fields() {
+        let call: CallInput = serde_json::from_value(json!({
+            "to": "0x0000000000000000000000000000000000000001",
+            "input": "0x1234",
+            "gas": "0x5208",
+            "maxFeePerGas": "0x2",
+            "maxPriorityFeePerGas": "0x1",
+            "accessList": [{
+                "address": "0x0000000000000000000000000000000000000002",
+                "storageKeys": ["0x0000000000000000000000000000000000000000000000000000000000000003"]
+            }],
+            "nonce": "0x7"
+        }))
+        .unwrap();
+
+        assert_eq!(call.from, Address::ZERO);
+        assert_eq!(call.data.as_ref(), &[0x12, 0x34]);
+        assert_eq!(call.gas, Some(21_000u64.into()));
+        assert!(call.is_dynamic_fee());
+        assert_eq!(call.access_list.slots_len(), 1);
+        assert_eq!(call.nonce, Some(7u64.into()));
+    }
+
+    #[test]
+    fn deserializes_call_data_from_data_or_input() {
+        let call: CallInput = serde_json::from_value(json!({ "data": "0x12" })).unwrap();
+        assert_eq!(call.data.as_ref(), &[0x12]);
+
+        let call: CallInput = serde_json::from_value(json!({ "data": "0x12", "input": "0x12" })).unwrap();
+        assert_eq!(call.data.as_ref(), &[0x12]);
+
+        let call: CallInput = serde_json::from_value(json!({})).unwrap();
+        assert!(call.data.is_empty());
+
+        let error = serde_json::from_value::<CallInput>(json!({ "data": "0x12", "input": "0x34" })).unwrap_err();
+        assert!(error.to_string().contains("both \"data\" and \"input\" are set and not equal"));
+    }
 }
@@ -6,15 +6,26 @@
 //! It is vital for interpreting the results of transaction execution and
 //! applying changes to the Ethereum state.
 
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::fmt::Debug;
 
+use ethereum_types::H256;
+use ethers_core::types::AccountDiff as EthersAccountDiff;
+use ethers_core::types::ChangedType as EthersChangedType;
+use ethers_core::types::Diff as EthersDiff;
+use ethers_core::types::StateDiff as EthersStateDiff;
+
 use crate::eth::primitives::Address;
 use crate::eth::primitives::Bytes;
 use crate::eth::primitives::ExecutionAccountChanges;
 use crate::eth::primitives::ExecutionResult;
+use crate::eth::primitives::ExecutionValueChange;
 use crate::eth::primitives::Gas;
 use crate::eth::primitives::Log;
+use crate::eth::primitives::Slot;
+use crate::ext::not;
+use crate::if_else;
 
 pub type ExecutionChanges = HashMap<Address, ExecutionAccountChanges>;
 
@@ -56,4 +67,60 @@ impl Execution {
     pub fn is_success(&self) -> bool {
         matches!(self.result, ExecutionResult::Success { .. })
     }
+
+    /// Serializes its changes to the OpenEthereum state diff format, as returned by `trace_replayTransaction`.
+    pub fn to_parity_state_diff(&self) -> EthersStateDiff {
+        let mut accounts = BTreeMap::new();
+        for changes in &self.changes {
+            let modified_slots = changes.slots.values().filter(|slot| slot.is_modified()).collect::<Vec<_>>();
+            let account_modified = changes.nonce.is_modified() || changes.balance.is_modified() || changes.bytecode.is_modified();
+            if not(account_modified) && modified_slots.is_empty() {
+                continue;
+            }
+
+            let storage = modified_slots
+                .into_iter()
+                .filter_map(|slot| {
+                    let modified = slot.take_modified_ref()?;
+                    // slots of existing accounts that were never written before are zero
+                    let original = if_else!(
+                        changes.is_account_creation(),
+                        None,
+                        Some(slot.take_original_ref().map(slot_value_to_h256).unwrap_or_default())
+                    );
+                    let diff = parity_diff(original, Some(slot_value_to_h256(modified)));
+                    Some((H256::from(<[u8; 32]>::from(modified.index.clone())), diff))
+                })
+                .collect();
+
+            let account = EthersAccountDiff {
+                balance: parity_value_diff(&changes.balance, |balance| balance.clone().into()),
+                nonce: parity_value_diff(&changes.nonce, |nonce| nonce.clone().into()),
+                code: parity_value_diff(&changes.bytecode, |bytecode| bytecode.clone().unwrap_or_default().into()),
+                storage,
+            };
+            accounts.insert(changes.address.clone().into(), account);
+        }
+        EthersStateDiff(accounts)
+    }
+}
+
+fn parity_value_diff<T, U>(change: &ExecutionValueChange<T>, convert: impl Fn(&T) -> U) -> EthersDiff<U>
+where
+    T: PartialEq,
+    U: PartialEq,
+{
+    parity_diff(change.take_original_ref().map(&convert), change.take_modified_ref().map(&convert))
+}
+
+fn parity_diff<T: PartialEq>(original: Option<T>, modified: Option<T>) -> EthersDiff<T> {
+    match (original, modified) {
+        (None, Some(modified)) => EthersDiff::Born(modified),
+        (Some(original), Some(modified)) if original != modified => EthersDiff::Changed(EthersChangedType { from: original, to: modified }),
+        _ => EthersDiff::Same,
+    }
+}
+
+fn slot_value_to_h256(slot: &Slot) -> H256 {
+    H256::from(<[u8; 32]>::from(slot.value.clone()))
 }
@@ -5,6 +5,7 @@ use crate::eth::primitives::Nonce;
 use crate::eth::primitives::SlotIndex;
 use crate::eth::primitives::SlotValue;
 use crate::eth::primitives::Wei;
+use crate::infra::metrics::LabelValue;
 
 #[derive(Debug)]
 pub struct ExecutionConflicts(pub NonEmpty<ExecutionConflict>);
@@ -59,3 +60,16 @@ pub enum ExecutionConflict {
         actual: SlotValue,
     },
 }
+
+// -----------------------------------------------------------------------------
+// Conversions: Self -> Other
+// -----------------------------------------------------------------------------
+impl From<&ExecutionConflict> for LabelValue {
+    fn from(value: &ExecutionConflict) -> Self {
+        match value {
+            ExecutionConflict::Nonce { .. } => Self::Some("nonce".to_string()),
+            ExecutionConflict::Balance { .. } => Self::Some("balance".to_string()),
+            ExecutionConflict::Slot { .. } => Self::Some("slot".to_string()),
+        }
+    }
+}
@@ -0,0 +1,393 @@
+//! Execution Trace Module
+//!
+//! Records how a transaction was executed inside the EVM: every call frame
+//! entered (calls, contract creations and self-destructs) and, when requested,
+//! every opcode step. Traces are never persisted; they are produced by
+//! re-executing transactions and consumed by debugging JSON-RPC methods that
+//! render them in formats compatible with other Ethereum clients, either as
+//! geth tracer frames or as OpenEthereum flat traces.
+
+use std::collections::BTreeMap;
+
+use ethereum_types::H256;
+use ethereum_types::U256;
+use ethers_core::types::Action as EthersAction;
+use ethers_core::types::ActionType as EthersActionType;
+use ethers_core::types::Call as EthersCall;
+use ethers_core::types::CallFrame as EthersCallFrame;
+use ethers_core::types::CallLogFrame as EthersCallLogFrame;
+use ethers_core::types::CallResult as EthersCallResult;
+use ethers_core::types::CallType as EthersCallType;
+use ethers_core::types::Create as EthersCreate;
+use ethers_core::types::CreateResult as EthersCreateResult;
+use ethers_core::types::DefaultFrame as EthersDefaultFrame;
+use ethers_core::types::NameOrAddress;
+use ethers_core::types::Res as EthersRes;
+use ethers_core::types::StructLog as EthersStructLog;
+use ethers_core::types::Suicide as EthersSuicide;
+use ethers_core::types::Trace as EthersTrace;
+use ethers_core::types::TransactionTrace as EthersTransactionTrace;
+
+use crate::eth::primitives::Address;
+use crate::eth::primitives::Bytes;
+use crate::eth::primitives::Execution;
+use crate::eth::primitives::Gas;
+use crate::eth::primitives::Log;
+use crate::eth::primitives::TransactionMined;
+use crate::eth::primitives::Wei;
+use crate::ext::not;
+use crate::if_else;
+
+/// Trace collected while executing a transaction in the EVM.
+#[derive(Debug, Clone, Default)]
+pub struct ExecutionTrace {
+    /// Call frames in the order they were entered. The first frame is the transaction itself.
+    pub frames: Vec<ExecutionTraceFrame>,
+
+    /// Opcodes executed in order. Only collected when the tracer needs them.
+    pub steps: Vec<ExecutionTraceStep>,
+}
+
+/// Call frame entered during the execution.
+#[derive(Debug, Clone)]
+pub struct ExecutionTraceFrame {
+    /// Kind of the frame.
+    pub kind: ExecutionTraceFrameKind,
+
+    /// Address that entered the frame.
+    pub from: Address,
+
+    /// Address that is the target of the frame. Contract creations only know it after the frame exits.
+    pub to: Option<Address>,
+
+    /// Value transferred in the frame.
+    pub value: Wei,
+
+    /// Gas available to the frame.
+    pub gas: Gas,
+
+    /// Gas consumed by the frame.
+    pub gas_used: Gas,
+
+    /// Calldata or initcode.
+    pub input: Bytes,
+
+    /// Returned data or deployed bytecode.
+    pub output: Bytes,
+
+    /// Error that caused the frame to fail.
+    pub error: Option<String>,
+
+    /// Depth of the frame. The transaction frame has depth zero.
+    pub depth: usize,
+
+    /// Index of the frame that entered this frame.
+    pub parent: Option<usize>,
+
+    /// Logs emitted directly by the frame.
+    pub logs: Vec<Log>,
+}
+
+/// Kind of a call frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::Display)]
+pub enum ExecutionTraceFrameKind {
+    #[strum(serialize = "CALL")]
+    Call,
+
+    #[strum(serialize = "STATICCALL")]
+    StaticCall,
+
+    #[strum(serialize = "DELEGATECALL")]
+    DelegateCall,
+
+    #[strum(serialize = "CALLCODE")]
+    CallCode,
+
+    #[strum(serialize = "CREATE")]
+    Create,
+
+    #[strum(serialize = "CREATE2")]
+    Create2,
+
+    #[strum(serialize = "SELFDESTRUCT")]
+    SelfDestruct,
+}
+
+impl ExecutionTraceFrameKind {
+    /// Checks if the frame deploys a contract.
+    pub fn is_create(&self) -> bool {
+        matches!(self, Self::Create | Self::Create2)
+    }
+}
+
+/// Opcode executed during the execution.
+#[derive(Debug, Clone)]
+pub struct ExecutionTraceStep {
+    /// Program counter.
+    pub pc: u64,
+
+    /// Opcode name.
+    pub op: String,
+
+    /// Gas remaining before executing the opcode.
+    pub gas: u64,
+
+    /// Gas consumed by the opcode.
+    pub gas_cost: u64,
+
+    /// Depth of the frame executing the opcode. The transaction frame has depth one.
+    pub depth: u64,
+
+    /// Stack before executing the opcode.
+    pub stack: Option<Vec<U256>>,
+
+    /// Storage slots accessed by the current contract so far. Only present in `SLOAD` and `SSTORE`.
+    pub storage: Option<BTreeMap<H256, H256>>,
+}
+
+impl ExecutionTrace {
+    /// Indexes of the frames entered directly by the given frame.
+    pub fn children_of(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        self.frames
+            .iter()
+            .enumerate()
+            .filter(move |(_, frame)| frame.parent == Some(index))
+            .map(|(child_index, _)| child_index)
+    }
+
+    /// Serializes itself to a geth `callTracer` frame.
+    pub fn to_geth_call_frame(&self, only_top_call: bool, with_log: bool) -> EthersCallFrame {
+        if self.frames.is_empty() {
+            return EthersCallFrame::default();
+        }
+        self.frame_to_geth_call_frame(0, only_top_call, with_log)
+    }
+
+    fn frame_to_geth_call_frame(&self, index: usize, only_top_call: bool, with_log: bool) -> EthersCallFrame {
+        let frame = &self.frames[index];
+
+        let calls = if_else!(
+            only_top_call,
+            vec![],
+            self.children_of(index)
+                .map(|child| self.frame_to_geth_call_frame(child, only_top_call, with_log))
+                .collect()
+        );
+        let logs = if_else!(
+            with_log,
+            frame
+                .logs
+                .iter()
+                .map(|log| EthersCallLogFrame {
+                    address: Some(log.address.clone().into()),
+                    topics: Some(log.topics.iter().cloned().map(Into::into).collect()),
+                    data: Some(log.data.clone().into()),
+                })
+                .collect(),
+            vec![]
+        );
+        let value = match frame.kind {
+            ExecutionTraceFrameKind::StaticCall | ExecutionTraceFrameKind::DelegateCall => None,
+            _ => Some(frame.value.clone().into()),
+        };
+
+        EthersCallFrame {
+            typ: frame.kind.to_string(),
+            from: frame.from.clone().into(),
+            to: frame.to.clone().map(|to| NameOrAddress::Address(to.into())),
+            value,
+            gas: frame.gas.clone().into(),
+            gas_used: frame.gas_used.clone().into(),
+            input: frame.input.clone().into(),
+            output: if_else!(frame.output.is_empty(), None, Some(frame.output.clone().into())),
+            error: frame.error.clone(),
+            calls: if_else!(calls.is_empty(), None, Some(calls)),
+            logs: if_else!(logs.is_empty(), None, Some(logs)),
+        }
+    }
+
+    /// Serializes itself to the geth default struct logger frame.
+    pub fn to_geth_default_frame(&self, execution: &Execution) -> EthersDefaultFrame {
+        EthersDefaultFrame {
+            failed: not(execution.is_success()),
+            gas: execution.gas.clone().into(),
+            return_value: execution.output.clone().into(),
+            struct_logs: self
+                .steps
+                .iter()
+                .map(|step| EthersStructLog {
+                    depth: step.depth,
+                    error: None,
+                    gas: step.gas,
+                    gas_cost: step.gas_cost,
+                    memory: None,
+                    op: step.op.clone(),
+                    pc: step.pc,
+                    refund_counter: None,
+                    stack: step.stack.clone(),
+                    storage: step.storage.clone(),
+                    mem_size: None,
+                    return_data: None,
+                })
+                .collect(),
+        }
+    }
+
+    /// Serializes itself to OpenEthereum flat traces, as returned by `trace_replayTransaction`.
+    ///
+    /// Traces are returned in the order frames were entered, so they can be zipped with `frames`.
+    pub fn to_parity_transaction_traces(&self) -> Vec<EthersTransactionTrace> {
+        let mut traces = Vec::with_capacity(self.frames.len());
+        let mut trace_addresses: Vec<Vec<usize>> = Vec::with_capacity(self.frames.len());
+        let mut children_entered = vec![0; self.frames.len()];
+
+        for (index, frame) in self.frames.iter().enumerate() {
+            // frames are always entered after their parents, so the parent address is already known
+            let trace_address = match frame.parent {
+                Some(parent) => {
+                    let mut trace_address = trace_addresses[parent].clone();
+                    trace_address.push(children_entered[parent]);
+                    children_entered[parent] += 1;
+                    trace_address
+                }
+                None => vec![],
+            };
+            trace_addresses.push(trace_address.clone());
+
+            let (action_type, action, result) = frame.to_parity_action_and_result();
+            traces.push(EthersTransactionTrace {
+                trace_address,
+                subtraces: self.children_of(index).count(),
+                action,
+                action_type,
+                result,
+                error: frame.error.as_deref().map(parse_parity_error),
+            });
+        }
+        traces
+    }
+
+    /// Serializes itself to OpenEthereum flat traces of a mined transaction, as returned by `trace_transaction` and `trace_block`.
+    pub fn to_parity_traces(&self, mined: &TransactionMined) -> Vec<EthersTrace> {
+        self.to_parity_transaction_traces()
+            .into_iter()
+            .map(|trace| EthersTrace {
+                action: trace.action,
+                result: trace.result,
+                trace_address: trace.trace_address,
+                subtraces: trace.subtraces,
+                transaction_position: Some(mined.transaction_index.into()),
+                transaction_hash: Some(mined.input.hash.clone().into()),
+                block_number: mined.block_number.into(),
+                block_hash: mined.block_hash.clone().into(),
+                action_type: trace.action_type,
+                error: trace.error,
+            })
+            .collect()
+    }
+}
+
+impl ExecutionTraceFrame {
+    fn to_parity_action_and_result(&self) -> (EthersActionType, EthersAction, Option<EthersRes>) {
+        let failed = self.error.is_some();
+        let to = self.to.clone().unwrap_or_default();
+
+        match self.kind {
+            ExecutionTraceFrameKind::Create | ExecutionTraceFrameKind::Create2 => {
+                let action = EthersAction::Create(EthersCreate {
+                    from: self.from.clone().into(),
+                    value: self.value.clone().into(),
+                    gas: self.gas.clone().into(),
+                    init: self.input.clone().into(),
+                });
+                let result = EthersRes::Create(EthersCreateResult {
+                    gas_used: self.gas_used.clone().into(),
+                    code: self.output.clone().into(),
+                    address: to.into(),
+                });
+                (EthersActionType::Create, action, if_else!(failed, None, Some(result)))
+            }
+            ExecutionTraceFrameKind::SelfDestruct => {
+                let action = EthersAction::Suicide(EthersSuicide {
+                    address: self.from.clone().into(),
+                    refund_address: to.into(),
+                    balance: self.value.clone().into(),
+                });
+                (EthersActionType::Suicide, action, None)
+            }
+            kind => {
+                let call_type = match kind {
+                    ExecutionTraceFrameKind::StaticCall => EthersCallType::StaticCall,
+                    ExecutionTraceFrameKind::DelegateCall => EthersCallType::DelegateCall,
+                    ExecutionTraceFrameKind::CallCode => EthersCallType::CallCode,
+                    _ => EthersCallType::Call,
+                };
+                let action = EthersAction::Call(EthersCall {
+                    from: self.from.clone().into(),
+                    to: to.into(),
+                    value: self.value.clone().into(),
+                    gas: self.gas.clone().into(),
+                    input: self.input.clone().into(),
+                    call_type,
+                });
+                let result = EthersRes::Call(EthersCallResult {
+                    gas_used: self.gas_used.clone().into(),
+                    output: self.output.clone().into(),
+                });
+                (EthersActionType::Call, action, if_else!(failed, None, Some(result)))
+            }
+        }
+    }
+}
+
+/// Translates frame errors to the messages used by OpenEthereum.
+fn parse_parity_error(error: &str) -> String {
+    match error {
+        "execution reverted" => "Reverted".to_owned(),
+        error => error.to_owned(),
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Tests
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use crate::eth::primitives::*;
+
+    fn frame(kind: ExecutionTraceFrameKind, depth: usize, parent: Option<usize>) -> ExecutionTraceFrame {
+        ExecutionTraceFrame {
+            kind,
+            from: Address::default(),
+            to: Some(Address::default()),
+            value: Wei::ZERO,
+            gas: Gas::ZERO,
+            gas_used: Gas::ZERO,
+            input: Bytes::default(),
+            output: Bytes::default(),
+            error: None,
+            depth,
+            parent,
+            logs: vec![],
+        }
+    }
+
+    #[test]
+    fn parity_traces_have_trace_addresses_and_subtraces() {
+        let trace = ExecutionTrace {
+            frames: vec![
+                frame(ExecutionTraceFrameKind::Call, 0, None),
+                frame(ExecutionTraceFrameKind::StaticCall, 1, Some(0)),
+                frame(ExecutionTraceFrameKind::Create, 1, Some(0)),
+                frame(ExecutionTraceFrameKind::SelfDestruct, 2, Some(2)),
+            ],
+            steps: vec![],
+        };
+
+        let traces = trace.to_parity_transaction_traces();
+        let addresses = traces.iter().map(|trace| trace.trace_address.clone()).collect::<Vec<_>>();
+        let subtraces = traces.iter().map(|trace| trace.subtraces).collect::<Vec<_>>();
+        assert_eq!(addresses, vec![vec![], vec![0], vec![1], vec![1, 0]]);
+        assert_eq!(subtraces, vec![2, 0, 1, 0]);
+    }
+}
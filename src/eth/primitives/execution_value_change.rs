@@ -73,6 +73,15 @@ where
         }
     }
 
+    /// Takes the modified value as reference if it is set.
+    pub fn take_modified_ref(&self) -> Option<&T> {
+        if let ValueState::Set(ref value) = self.modified {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
     /// Takes the modified value if it is set.
     pub fn take_modified(self) -> Option<T> {
         if let ValueState::Set(value) = self.modified {
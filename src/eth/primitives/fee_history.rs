@@ -0,0 +1,152 @@
+//! Fee History Module
+//!
+//! Summarizes the fees paid in a range of consecutive blocks, as returned by
+//! `eth_feeHistory`. Wallets use the base fees, how full the blocks were and
+//! the priority fees paid at some percentiles of the gas used to suggest the
+//! fee caps of new transactions.
+
+use ethereum_types::U256;
+use ethers_core::types::FeeHistory as EthersFeeHistory;
+use itertools::Itertools;
+
+use crate::eth::primitives::Block;
+use crate::eth::primitives::BlockNumber;
+use crate::eth::primitives::Gas;
+use crate::eth::primitives::Wei;
+
+/// Fees paid in a range of consecutive blocks.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FeeHistory {
+    /// Number of the oldest block in the range.
+    pub oldest_block: BlockNumber,
+
+    /// Base fee of each block, followed by the base fee of the block after the newest block.
+    pub base_fees: Vec<Wei>,
+
+    /// Ratio between the gas used and the gas limit of each block.
+    pub gas_used_ratios: Vec<f64>,
+
+    /// Priority fees paid in each block at the requested percentiles of the gas used.
+    pub rewards: Vec<Vec<Wei>>,
+}
+
+impl FeeHistory {
+    /// Summarizes the fees paid in consecutive blocks ordered by number.
+    pub fn new(blocks: &[Block], next_base_fee: Wei, reward_percentiles: &[f64]) -> Self {
+        let Some(oldest) = blocks.first() else {
+            return Self::default();
+        };
+
+        let mut base_fees = blocks.iter().map(|block| block.header.base_fee.clone()).collect_vec();
+        base_fees.push(next_base_fee);
+
+        let gas_used_ratios = blocks
+            .iter()
+            .map(|block| match u64::from(block.header.gas_limit.clone()) {
+                0 => 0.0,
+                gas_limit => u64::from(block.header.gas.clone()) as f64 / gas_limit as f64,
+            })
+            .collect();
+
+        let rewards = if reward_percentiles.is_empty() {
+            Vec::new()
+        } else {
+            blocks.iter().map(|block| block_rewards(block, reward_percentiles)).collect()
+        };
+
+        Self {
+            oldest_block: *oldest.number(),
+            base_fees,
+            gas_used_ratios,
+            rewards,
+        }
+    }
+}
+
+/// Calculates the priority fees paid at the percentiles of the gas used by the block, considering transactions ordered by priority fee.
+fn block_rewards(block: &Block, reward_percentiles: &[f64]) -> Vec<Wei> {
+    let tips: Vec<(Wei, Gas)> = block
+        .transactions
+        .iter()
+        .map(|tx| {
+            let tip = U256::from(tx.effective_gas_price.clone()).saturating_sub(block.header.base_fee.clone().into());
+            (tip.into(), tx.execution.gas.clone())
+        })
+        .sorted_by(|(tip1, _), (tip2, _)| tip1.cmp(tip2))
+        .collect();
+
+    let Some((last_tip, _)) = tips.last() else {
+        return vec![Wei::ZERO; reward_percentiles.len()];
+    };
+
+    let gas_used = u64::from(block.header.gas.clone()) as f64;
+    reward_percentiles
+        .iter()
+        .map(|percentile| {
+            let threshold = gas_used * percentile / 100.0;
+            let mut cumulative_gas = 0.0;
+            for (tip, gas) in &tips {
+                cumulative_gas += u64::from(gas.clone()) as f64;
+                if cumulative_gas >= threshold {
+                    return tip.clone();
+                }
+            }
+            last_tip.clone()
+        })
+        .collect()
+}
+
+// -----------------------------------------------------------------------------
+// Conversions: Self -> Other
+// -----------------------------------------------------------------------------
+impl From<FeeHistory> for EthersFeeHistory {
+    fn from(value: FeeHistory) -> Self {
+        Self {
+            base_fee_per_gas: value.base_fees.into_iter().map_into().collect(),
+            gas_used_ratio: value.gas_used_ratios,
+            oldest_block: u64::from(value.oldest_block).into(),
+            reward: value.rewards.into_iter().map(|rewards| rewards.into_iter().map_into().collect()).collect(),
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Tests
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use fake::Fake;
+    use fake::Faker;
+
+    use crate::eth::primitives::*;
+
+    fn transaction(effective_gas_price: u64, gas: u64) -> TransactionMined {
+        let mut transaction: TransactionMined = Faker.fake();
+        transaction.effective_gas_price = effective_gas_price.into();
+        transaction.execution.gas = gas.into();
+        transaction
+    }
+
+    #[test]
+    fn rewards_are_weighted_by_gas_used() {
+        let mut block = Block::new_with_capacity(1u64.into(), 0, 2);
+        block.header.base_fee = 10u64.into();
+        block.header.gas = 100_000u64.into();
+        block.header.gas_limit = 200_000u64.into();
+        block.transactions.push(transaction(30, 25_000));
+        block.transactions.push(transaction(12, 75_000));
+
+        let history = FeeHistory::new(&[block], 11u64.into(), &[10.0, 50.0, 90.0]);
+        assert_eq!(history.oldest_block, 1u64.into());
+        assert_eq!(history.base_fees, vec![10u64.into(), 11u64.into()]);
+        assert_eq!(history.gas_used_ratios, vec![0.5]);
+        assert_eq!(history.rewards, vec![vec![2u64.into(), 2u64.into(), 20u64.into()]]);
+    }
+
+    #[test]
+    fn rewards_of_empty_block_are_zero() {
+        let block = Block::new_with_capacity(1u64.into(), 0, 0);
+        let history = FeeHistory::new(&[block], Wei::ZERO, &[50.0]);
+        assert_eq!(history.rewards, vec![vec![Wei::ZERO]]);
+    }
+}
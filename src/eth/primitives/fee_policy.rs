@@ -0,0 +1,114 @@
+//! Fee Policy Module
+//!
+//! Defines how the base fee of each block is calculated. The base fee,
+//! introduced by EIP-1559, is the minimum price per gas a transaction must pay
+//! to be included in a block. Private networks usually do not charge for gas,
+//! so the base fee can be zero, fixed, or adjusted after each block according
+//! to how much gas the previous block used, as in Ethereum mainnet.
+
+use std::cmp::Ordering;
+
+use ethereum_types::U256;
+
+use crate::eth::primitives::BlockHeader;
+use crate::eth::primitives::BlockNumber;
+use crate::eth::primitives::Wei;
+
+/// Bound to the amount the base fee can change between blocks (EIP-1559).
+const BASE_FEE_MAX_CHANGE_DENOMINATOR: u64 = 8;
+
+/// Ratio between the block gas limit and the gas a block is expected to use (EIP-1559).
+const ELASTICITY_MULTIPLIER: u64 = 2;
+
+/// Policy used to calculate the base fee of mined blocks.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum FeePolicy {
+    /// Blocks have no base fee, so transactions can be sent with zero gas price.
+    #[default]
+    Zero,
+
+    /// All blocks have the same base fee.
+    Fixed(Wei),
+
+    /// Base fee adjusted after each block according to the gas used by the parent block (EIP-1559).
+    ///
+    /// The first block after genesis starts at the initial base fee.
+    Dynamic(Wei),
+}
+
+impl FeePolicy {
+    /// Calculates the base fee of the block mined after the parent block.
+    pub fn base_fee(&self, parent: Option<&BlockHeader>) -> Wei {
+        match (self, parent) {
+            (Self::Zero, _) => Wei::ZERO,
+            (Self::Fixed(base_fee), _) => base_fee.clone(),
+            (Self::Dynamic(initial_base_fee), None) => initial_base_fee.clone(),
+            (Self::Dynamic(initial_base_fee), Some(parent)) if parent.number == BlockNumber::ZERO => initial_base_fee.clone(),
+            (Self::Dynamic(_), Some(parent)) => dynamic_base_fee(parent),
+        }
+    }
+}
+
+/// Increases the parent base fee if the parent used more gas than the target, or decreases it if the parent used less gas than the
+/// target, by at most 1/8 of the parent base fee.
+fn dynamic_base_fee(parent: &BlockHeader) -> Wei {
+    let parent_base_fee = U256::from(parent.base_fee.clone());
+    let gas_used = U256::from(parent.gas.clone());
+    let gas_target = U256::from(parent.gas_limit.clone()) / ELASTICITY_MULTIPLIER;
+    if gas_target.is_zero() {
+        return parent.base_fee.clone();
+    }
+
+    let base_fee = match gas_used.cmp(&gas_target) {
+        Ordering::Equal => parent_base_fee,
+        Ordering::Greater => {
+            let delta = parent_base_fee.saturating_mul(gas_used - gas_target) / gas_target / BASE_FEE_MAX_CHANGE_DENOMINATOR;
+            parent_base_fee.saturating_add(delta.max(U256::one()))
+        }
+        Ordering::Less => {
+            let delta = parent_base_fee.saturating_mul(gas_target - gas_used) / gas_target / BASE_FEE_MAX_CHANGE_DENOMINATOR;
+            parent_base_fee.saturating_sub(delta)
+        }
+    };
+    base_fee.into()
+}
+
+// -----------------------------------------------------------------------------
+// Tests
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use crate::eth::primitives::*;
+
+    fn parent(number: u64, base_fee: u64, gas: u64) -> BlockHeader {
+        let mut header = BlockHeader::new(number.into(), 0);
+        header.base_fee = base_fee.into();
+        header.gas = gas.into();
+        header.gas_limit = 1_000_000u64.into();
+        header
+    }
+
+    #[test]
+    fn dynamic_base_fee_follows_parent_gas_usage() {
+        let policy = FeePolicy::Dynamic(1_000u64.into());
+
+        // first block starts at the initial base fee
+        assert_eq!(policy.base_fee(Some(&parent(0, 0, 0))), 1_000u64.into());
+
+        // parent at target keeps the base fee
+        assert_eq!(policy.base_fee(Some(&parent(1, 1_000, 500_000))), 1_000u64.into());
+
+        // full parent increases the base fee by 1/8
+        assert_eq!(policy.base_fee(Some(&parent(1, 1_000, 1_000_000))), 1_125u64.into());
+
+        // empty parent decreases the base fee by 1/8
+        assert_eq!(policy.base_fee(Some(&parent(1, 1_000, 0))), 875u64.into());
+    }
+
+    #[test]
+    fn static_base_fees_ignore_parent_gas_usage() {
+        let full_parent = parent(1, 1_000, 1_000_000);
+        assert_eq!(FeePolicy::Zero.base_fee(Some(&full_parent)), Wei::ZERO);
+        assert_eq!(FeePolicy::Fixed(7u64.into()).base_fee(Some(&full_parent)), 7u64.into());
+    }
+}
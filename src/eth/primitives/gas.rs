@@ -20,12 +20,15 @@ use sqlx::types::BigDecimal;
 
 use crate::gen_newtype_from;
 
-#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, derive_more::Add, serde::Serialize, serde::Deserialize)]
 #[serde(transparent)]
 pub struct Gas(U256);
 
 impl Gas {
     pub const ZERO: Gas = Gas(U256::zero());
+
+    /// Maximum gas a single transaction can use.
+    pub const TRANSACTION_LIMIT: Gas = Gas(U256([100_000_000, 0, 0, 0]));
 }
 
 impl Display for Gas {
@@ -79,6 +82,12 @@ impl From<Gas> for U256 {
     }
 }
 
+impl From<Gas> for u64 {
+    fn from(value: Gas) -> Self {
+        value.0.as_u64()
+    }
+}
+
 impl From<Gas> for usize {
     fn from(value: Gas) -> Self {
         value.0.as_usize()
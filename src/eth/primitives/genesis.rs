@@ -0,0 +1,149 @@
+//! Genesis Module
+//!
+//! Describes the initial state of the chain: the timestamp of the genesis
+//! block and the accounts, with their balances, nonces, bytecodes and slots,
+//! that exist before any transaction is executed. The state is read from a
+//! geth-style genesis file, so the same file can be used to start other
+//! Ethereum nodes with an equivalent state.
+
+use std::path::Path;
+
+use anyhow::Context;
+use ethers_core::utils::Genesis as EthersGenesis;
+
+use crate::eth::primitives::Account;
+use crate::eth::primitives::Address;
+use crate::eth::primitives::ChainId;
+use crate::eth::primitives::Slot;
+use crate::eth::primitives::UnixTime;
+use crate::eth::primitives::Wei;
+
+/// Timestamp of the genesis block when not specified.
+const DEFAULT_TIMESTAMP: u64 = 1702568764;
+
+/// Initial state of the chain.
+#[derive(Debug, Clone)]
+pub struct Genesis {
+    /// Chain the genesis was created for, if specified.
+    pub chain_id: Option<ChainId>,
+
+    /// Timestamp of the genesis block.
+    pub timestamp: UnixTime,
+
+    /// Base fee of the genesis block.
+    pub base_fee: Wei,
+
+    /// Miner of the genesis block.
+    pub coinbase: Address,
+
+    /// Accounts and their slots that exist before any transaction is executed.
+    pub accounts: Vec<(Account, Vec<Slot>)>,
+}
+
+impl Genesis {
+    /// Reads the genesis from a geth-style genesis JSON file.
+    pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+        tracing::info!(path = %path.display(), "reading genesis file");
+
+        let content = std::fs::read_to_string(path).with_context(|| format!("failed to read genesis file {}", path.display()))?;
+        let genesis: EthersGenesis = serde_json::from_str(&content).with_context(|| format!("failed to parse genesis file {}", path.display()))?;
+        Ok(genesis.into())
+    }
+
+    /// Adds accounts to the genesis without slots.
+    pub fn add_accounts(&mut self, accounts: Vec<Account>) {
+        self.accounts.extend(accounts.into_iter().map(|account| (account, Vec::new())));
+    }
+}
+
+impl Default for Genesis {
+    fn default() -> Self {
+        Self {
+            chain_id: None,
+            timestamp: DEFAULT_TIMESTAMP.into(),
+            base_fee: Wei::ZERO,
+            coinbase: Address::COINBASE,
+            accounts: Vec::new(),
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Conversions: Other -> Self
+// -----------------------------------------------------------------------------
+impl From<EthersGenesis> for Genesis {
+    fn from(value: EthersGenesis) -> Self {
+        let accounts = value
+            .alloc
+            .into_iter()
+            .map(|(address, alloc)| {
+                let account = Account {
+                    address: address.into(),
+                    nonce: alloc.nonce.unwrap_or_default().into(),
+                    balance: alloc.balance.into(),
+                    bytecode: alloc.code.map(Into::into),
+                };
+                let slots = alloc
+                    .storage
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(index, value)| Slot::new(index.to_fixed_bytes(), value.to_fixed_bytes()))
+                    .collect();
+                (account, slots)
+            })
+            .collect();
+
+        Self {
+            // geth-style files without chain configuration are parsed with chain id zero
+            chain_id: (value.config.chain_id != 0).then(|| value.config.chain_id.into()),
+            timestamp: value.timestamp.as_u64().into(),
+            base_fee: value.base_fee_per_gas.unwrap_or_default().into(),
+            coinbase: value.coinbase.into(),
+            accounts,
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Tests
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use ethers_core::utils::Genesis as EthersGenesis;
+
+    use crate::eth::primitives::*;
+
+    #[test]
+    fn parse_geth_genesis() {
+        let json = r#"{
+            "config": { "chainId": 2008 },
+            "timestamp": "0x10",
+            "baseFeePerGas": "0x3b9aca00",
+            "coinbase": "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            "difficulty": "0x1",
+            "alloc": {
+                "f39fd6e51aad88f6f4ce6ab8827279cfffb92266": { "balance": "0x64", "nonce": "0x2" },
+                "0x70997970c51812dc3a010c7d01b50e0d17dc79c8": {
+                    "balance": "0x0",
+                    "code": "0x6000",
+                    "storage": { "0x0000000000000000000000000000000000000000000000000000000000000001": "0x0000000000000000000000000000000000000000000000000000000000000002" }
+                }
+            }
+        }"#;
+        let genesis: Genesis = serde_json::from_str::<EthersGenesis>(json).unwrap().into();
+
+        assert_eq!(genesis.chain_id, Some(2008u64.into()));
+        assert_eq!(*genesis.timestamp, 16);
+        assert_eq!(genesis.base_fee, 1_000_000_000u64.into());
+        assert_eq!(genesis.coinbase, Address::new([0xaa; 20]));
+        assert_eq!(genesis.accounts.len(), 2);
+
+        let (contract, slots) = genesis.accounts.iter().find(|(account, _)| account.is_contract()).unwrap();
+        assert_eq!(contract.bytecode, Some(vec![0x60u8, 0x00].into()));
+        assert_eq!(slots, &vec![Slot::new(1u64, 2u64)]);
+
+        let (wallet, _) = genesis.accounts.iter().find(|(account, _)| !account.is_contract()).unwrap();
+        assert_eq!(wallet.balance, 100u64.into());
+        assert_eq!(wallet.nonce, 2u64.into());
+    }
+}
@@ -14,6 +14,7 @@ use anyhow::anyhow;
 use ethereum_types::H256;
 use fake::Dummy;
 use fake::Faker;
+use revm::primitives::B256 as RevmB256;
 use sqlx::database::HasValueRef;
 use sqlx::error::BoxDynError;
 
@@ -63,6 +64,12 @@ impl AsRef<[u8]> for Hash {
 // -----------------------------------------------------------------------------
 gen_newtype_from!(self = Hash, other = H256, [u8; 32]);
 
+impl From<RevmB256> for Hash {
+    fn from(value: RevmB256) -> Self {
+        Self(value.0.into())
+    }
+}
+
 impl FromStr for Hash {
     type Err = anyhow::Error;
 
@@ -115,3 +122,9 @@ impl From<Hash> for H256 {
         value.0
     }
 }
+
+impl From<Hash> for RevmB256 {
+    fn from(value: Hash) -> Self {
+        RevmB256::from(value.0 .0)
+    }
+}
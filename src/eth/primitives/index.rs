@@ -74,3 +74,9 @@ impl From<Index> for i32 {
         value.0.into()
     }
 }
+
+impl From<Index> for usize {
+    fn from(value: Index) -> Self {
+        value.0.into()
+    }
+}
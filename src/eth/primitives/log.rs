@@ -6,8 +6,11 @@
 //! emitting address, topics, and additional data. It also provides conversion
 //! functions to translate between internal and external log representations.
 
+use ethereum_types::H256;
 use itertools::Itertools;
 use revm::primitives::Log as RevmLog;
+use rlp::Encodable;
+use rlp::RlpStream;
 
 use crate::eth::primitives::Address;
 use crate::eth::primitives::Bytes;
@@ -26,6 +29,21 @@ pub struct Log {
     pub data: Bytes,
 }
 
+// -----------------------------------------------------------------------------
+// Serialization / Deserialization
+// -----------------------------------------------------------------------------
+impl Encodable for Log {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(3);
+        s.append(&*self.address);
+        s.begin_list(self.topics.len());
+        for topic in &self.topics {
+            s.append(&H256::from(topic.clone()));
+        }
+        s.append(&*self.data);
+    }
+}
+
 // -----------------------------------------------------------------------------
 // Conversions: Other -> Self
 // ----------------------------------------------------------------------------
@@ -11,6 +11,8 @@ use std::ops::Deref;
 use std::ops::DerefMut;
 
 use ethereum_types::Bloom;
+use fake::Dummy;
+use fake::Faker;
 use sqlx::database::HasValueRef;
 use sqlx::error::BoxDynError;
 
@@ -34,6 +36,12 @@ impl DerefMut for LogsBloom {
     }
 }
 
+impl Dummy<Faker> for LogsBloom {
+    fn dummy_with_rng<R: ethers_core::rand::prelude::Rng + ?Sized>(_: &Faker, _: &mut R) -> Self {
+        Self::default()
+    }
+}
+
 // -----------------------------------------------------------------------------
 // Conversions: sqlx -> Self
 // -----------------------------------------------------------------------------
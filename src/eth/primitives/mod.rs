@@ -4,7 +4,9 @@
 //!
 //! ## Enumerated Modules and Their Roles
 //!
+//! - `access_list::AccessList`: Lists the accounts and slots a transaction plans to access (EIP-2930).
 //! - `account::Account`: Manages Ethereum accounts, including user wallets and contract accounts.
+//! - `account_proof::AccountProof`: Merkle proofs of accounts and slots against the state root, as returned by `eth_getProof`.
 //! - `address::Address`: Handles Ethereum addresses, serving as unique identifiers for accounts and contracts.
 //! - `alias::*`: Provides type aliases for Ethereum-specific identifiers.
 //! - `block::Block`: Manages the structure of Ethereum blocks, containing transactions and a block header.
@@ -14,7 +16,11 @@
 //! - `bytes::Bytes`: Manages byte arrays, used for data payloads.
 //! - `call_input::CallInput`: Structures input data for smart contract calls.
 //! - `chain_id::ChainId`: Represents unique identifiers for different Ethereum networks.
+//! - `execution_trace::*`: Records call frames and opcodes executed by a transaction.
+//! - `fee_history::FeeHistory`: Summarizes the fees paid in a range of blocks, as returned by `eth_feeHistory`.
+//! - `fee_policy::FeePolicy`: Defines how the base fee of each block is calculated.
 //! - `gas::Gas`: Manages gas units for computational work and transaction fees.
+//! - `genesis::Genesis`: Describes the initial state of the chain, read from geth-style genesis files.
 //! - `hash::Hash`: Manages hash values for data integrity and blockchain consistency.
 //! - `historical_value::*`: Tracks historical state changes over time.
 //! - `index::Index`: Represents indexes, such as transaction positions in a block.
@@ -25,8 +31,12 @@
 //! - `log_topic::LogTopic`: Manages log topics for categorizing and filtering logs.
 //! - `logs_bloom::LogsBloom`: Manages bloom filters for efficient log searching.
 //! - `nonce::Nonce`: Manages nonces for transaction ordering and replay protection.
+//! - `size::Size`: Represents the size in bytes of RLP-encoded data, like blocks.
 //! - `slot::*`: Manages storage slots in contract state storage.
+//! - `state_overlay::StateOverlay`: Accumulates state modified by executions not saved in the storage yet.
 //! - `storage_point_in_time::StoragePointInTime`: References Ethereum storage states at different times.
+//! - `trace_filter::TraceFilter` and `trace_filter_input::TraceFilterInput`: Select which call frames are returned by trace filters.
+//! - `tracer::Tracer` and `tracer_input::TracerInput`: Select and configure how execution traces are rendered.
 //! - `transaction_execution::*`: Manages results of Ethereum transaction executions.
 //! - `transaction_input::TransactionInput`: Structures input data for Ethereum transactions.
 //! - `transaction_mined::TransactionMined`: Represents executed and mined transactions.
@@ -74,7 +84,9 @@
 //!
 //! The outlined interactions among these primitives demonstrate the modular yet interconnected nature of the Ethereum framework. Each primitive plays a critical role in the broader context of Ethereum's operations, from individual transactions to the global state of the blockchain.
 
+mod access_list;
 mod account;
+mod account_proof;
 mod address;
 mod alias;
 mod block;
@@ -90,8 +102,12 @@ mod execution;
 mod execution_account_changes;
 mod execution_conflict;
 mod execution_result;
+mod execution_trace;
 mod execution_value_change;
+mod fee_history;
+mod fee_policy;
 mod gas;
+mod genesis;
 mod hash;
 mod index;
 mod log;
@@ -101,14 +117,24 @@ mod log_mined;
 mod log_topic;
 mod logs_bloom;
 mod nonce;
+mod size;
 mod slot;
+mod state_overlay;
 mod storage_point_in_time;
+mod trace_filter;
+mod trace_filter_input;
+mod tracer;
+mod tracer_input;
 mod transaction_input;
 mod transaction_mined;
 mod unix_time;
 mod wei;
 
+pub use access_list::AccessList;
+pub use access_list::AccessListItem;
 pub use account::Account;
+pub use account_proof::AccountProof;
+pub use account_proof::SlotProof;
 pub use address::Address;
 pub use alias::Signature32Bytes;
 pub use alias::Signature4Bytes;
@@ -129,8 +155,15 @@ pub use execution_conflict::ExecutionConflict;
 pub use execution_conflict::ExecutionConflicts;
 pub use execution_conflict::ExecutionConflictsBuilder;
 pub use execution_result::ExecutionResult;
+pub use execution_trace::ExecutionTrace;
+pub use execution_trace::ExecutionTraceFrame;
+pub use execution_trace::ExecutionTraceFrameKind;
+pub use execution_trace::ExecutionTraceStep;
 pub use execution_value_change::ExecutionValueChange;
+pub use fee_history::FeeHistory;
+pub use fee_policy::FeePolicy;
 pub use gas::Gas;
+pub use genesis::Genesis;
 pub use hash::Hash;
 pub use index::Index;
 pub use log::Log;
@@ -139,11 +172,18 @@ pub use log_filter::LogFilterTopicCombination;
 pub use log_filter_input::LogFilterInput;
 pub use log_mined::LogMined;
 pub use log_topic::LogTopic;
+pub use logs_bloom::LogsBloom;
 pub use nonce::Nonce;
+pub use size::Size;
 pub use slot::Slot;
 pub use slot::SlotIndex;
 pub use slot::SlotValue;
+pub use state_overlay::StateOverlay;
 pub use storage_point_in_time::StoragePointInTime;
+pub use trace_filter::TraceFilter;
+pub use trace_filter_input::TraceFilterInput;
+pub use tracer::Tracer;
+pub use tracer_input::TracerInput;
 pub use transaction_input::TransactionInput;
 pub use transaction_mined::TransactionMined;
 pub use unix_time::UnixTime;
@@ -154,6 +194,9 @@ pub use wei::Wei;
 // -----------------------------------------------------------------------------
 #[cfg(test)]
 mod tests {
+    use fake::Fake;
+    use fake::Faker;
+
     use super::*;
     use crate::gen_test_serde;
 
@@ -194,15 +237,43 @@ mod tests {
         let number: BlockNumber = 0x0.into();
         let timestamp_in_secs = 1234567890;
         let b = BlockHeader::new(number, timestamp_in_secs);
-        assert_eq!(b.hash.to_string(), "0x011b4d03dd8c01f1049143cf9c4c817e4b167f1d1b83e5c6f0f10d89ba1e7bce");
+        assert_eq!(b.hash, b.compute_hash());
+        assert_ne!(b.hash, Hash::zero());
     }
 
     #[test]
-    fn parent_hash() {
+    fn block_hash_depends_on_header_contents() {
         let number: BlockNumber = 0x1.into();
         let timestamp_in_secs = 1234567891;
         let b = BlockHeader::new(number, timestamp_in_secs);
-        assert_eq!(b.parent_hash.to_string(), "0x011b4d03dd8c01f1049143cf9c4c817e4b167f1d1b83e5c6f0f10d89ba1e7bce");
+
+        let mut with_parent = b.clone();
+        with_parent.parent_hash = Hash::new([1; 32]);
+        assert_ne!(with_parent.compute_hash(), b.hash);
+
+        let mut with_transactions = b.clone();
+        with_transactions.transactions_root = Hash::new([2; 32]);
+        assert_ne!(with_transactions.compute_hash(), b.hash);
+        assert_ne!(with_transactions.compute_hash(), with_parent.compute_hash());
+    }
+
+    #[test]
+    fn block_header_keeps_timestamp() {
+        let number: BlockNumber = 0x1.into();
+        let timestamp_in_secs = 1234567891;
+        let b = BlockHeader::new(number, timestamp_in_secs);
+        assert_eq!(*b.timestamp_in_secs, timestamp_in_secs);
+    }
+
+    #[test]
+    fn block_size_grows_with_transactions() {
+        let empty = Block::new_with_capacity(0x1.into(), 1234567891, 1);
+        let empty_size = empty.calculate_size();
+        assert!(*empty_size > 0);
+
+        let mut with_transaction = empty.clone();
+        with_transaction.transactions.push(Faker.fake());
+        assert!(*with_transaction.calculate_size() > *empty_size);
     }
 
     #[test]
@@ -19,11 +19,16 @@ use sqlx::Decode;
 
 use crate::gen_newtype_from;
 
-#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize)]
 pub struct Nonce(U256);
 
 impl Nonce {
     pub const ZERO: Nonce = Nonce(U256::zero());
+
+    /// Returns the nonce that follows the current one.
+    pub fn next(&self) -> Self {
+        Self(self.0 + 1)
+    }
 }
 
 impl Display for Nonce {
@@ -0,0 +1,86 @@
+//! Size Module
+//!
+//! Represents the size in bytes of RLP-encoded data, like the size of a block
+//! returned by JSON-RPC methods. Explorers use it to display how much space
+//! each block takes in the chain.
+
+use std::num::TryFromIntError;
+use std::ops::Deref;
+
+use ethereum_types::U256;
+use fake::Dummy;
+use fake::Faker;
+use sqlx::database::HasValueRef;
+use sqlx::error::BoxDynError;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Size(u64);
+
+impl Size {
+    pub const ZERO: Size = Size(0u64);
+}
+
+impl Deref for Size {
+    type Target = u64;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Dummy<Faker> for Size {
+    fn dummy_with_rng<R: ethers_core::rand::prelude::Rng + ?Sized>(_: &Faker, rng: &mut R) -> Self {
+        rng.next_u32().into()
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Conversions: Other -> Self
+// -----------------------------------------------------------------------------
+
+impl From<u32> for Size {
+    fn from(value: u32) -> Self {
+        Size(value as u64)
+    }
+}
+
+impl From<usize> for Size {
+    fn from(value: usize) -> Self {
+        Size(value as u64)
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Conversions: sqlx -> Self
+// -----------------------------------------------------------------------------
+
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for Size {
+    fn decode(value: <sqlx::Postgres as HasValueRef<'r>>::ValueRef) -> Result<Self, BoxDynError> {
+        let value = <i64 as sqlx::Decode<sqlx::Postgres>>::decode(value)?;
+        Ok(Size(value.try_into()?))
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for Size {
+    fn type_info() -> <sqlx::Postgres as sqlx::Database>::TypeInfo {
+        sqlx::postgres::PgTypeInfo::with_name("INT8")
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Conversions: Self -> Other
+// -----------------------------------------------------------------------------
+
+impl From<Size> for U256 {
+    fn from(value: Size) -> Self {
+        value.0.into()
+    }
+}
+
+impl TryFrom<Size> for i64 {
+    type Error = TryFromIntError;
+
+    fn try_from(value: Size) -> Result<i64, TryFromIntError> {
+        value.0.try_into()
+    }
+}
@@ -0,0 +1,134 @@
+//! State Overlay Module
+//!
+//! Accumulates the state modified by executions that are not saved in the
+//! storage yet, like transactions executed earlier in the same block. Reads
+//! check the overlay before the storage, so a transaction executed on top of
+//! the overlay observes the changes of the transactions before it. It is also
+//! used to validate speculative executions, detecting the ones that read
+//! values later modified by previous transactions of the same block.
+
+use std::collections::HashMap;
+
+use crate::eth::primitives::Account;
+use crate::eth::primitives::Address;
+use crate::eth::primitives::Bytes;
+use crate::eth::primitives::Execution;
+use crate::eth::primitives::ExecutionConflicts;
+use crate::eth::primitives::ExecutionConflictsBuilder;
+use crate::eth::primitives::Nonce;
+use crate::eth::primitives::Slot;
+use crate::eth::primitives::SlotIndex;
+use crate::eth::primitives::Wei;
+
+/// State modified by executions not saved in the storage yet.
+#[derive(Debug, Clone, Default)]
+pub struct StateOverlay {
+    accounts: HashMap<Address, StateOverlayAccount>,
+}
+
+/// Latest values of an account modified by executions in the overlay.
+#[derive(Debug, Clone, Default)]
+struct StateOverlayAccount {
+    nonce: Option<Nonce>,
+    balance: Option<Wei>,
+    bytecode: Option<Option<Bytes>>,
+    slots: HashMap<SlotIndex, Slot>,
+}
+
+impl StateOverlay {
+    /// Checks if no execution modified the state.
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+
+    /// Applies the values modified by an execution over the values modified by previous executions.
+    ///
+    /// Bytecode and slots modified by failed executions are ignored because they are not persisted.
+    pub fn apply(&mut self, execution: &Execution) {
+        let is_success = execution.is_success();
+
+        for changes in &execution.changes {
+            let account = self.accounts.entry(changes.address.clone()).or_default();
+            if let Some(nonce) = changes.nonce.take_modified_ref() {
+                account.nonce = Some(nonce.clone());
+            }
+            if let Some(balance) = changes.balance.take_modified_ref() {
+                account.balance = Some(balance.clone());
+            }
+            if is_success {
+                if let Some(bytecode) = changes.bytecode.take_modified_ref() {
+                    account.bytecode = Some(bytecode.clone());
+                }
+                for (index, slot) in &changes.slots {
+                    if let Some(slot) = slot.take_modified_ref() {
+                        account.slots.insert(index.clone(), slot.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Applies the values in the overlay to an account read from the storage.
+    pub fn apply_to_account(&self, account: &mut Account) {
+        let Some(overlay) = self.accounts.get(&account.address) else {
+            return;
+        };
+        if let Some(ref nonce) = overlay.nonce {
+            account.nonce = nonce.clone();
+        }
+        if let Some(ref balance) = overlay.balance {
+            account.balance = balance.clone();
+        }
+        if let Some(ref bytecode) = overlay.bytecode {
+            account.bytecode = bytecode.clone();
+        }
+    }
+
+    /// Nonce of the account in the overlay.
+    pub fn nonce(&self, address: &Address) -> Option<&Nonce> {
+        self.accounts.get(address)?.nonce.as_ref()
+    }
+
+    /// Balance of the account in the overlay.
+    pub fn balance(&self, address: &Address) -> Option<&Wei> {
+        self.accounts.get(address)?.balance.as_ref()
+    }
+
+    /// Slot of the account in the overlay.
+    pub fn slot(&self, address: &Address, index: &SlotIndex) -> Option<&Slot> {
+        self.accounts.get(address)?.slots.get(index)
+    }
+
+    /// Checks if the execution read values that are different in the overlay, meaning it was executed without observing the changes of
+    /// previous executions.
+    pub fn check_conflicts(&self, execution: &Execution) -> Option<ExecutionConflicts> {
+        let mut conflicts = ExecutionConflictsBuilder::default();
+
+        for changes in &execution.changes {
+            let address = &changes.address;
+
+            // check account info conflicts
+            if let (Some(read_nonce), Some(nonce)) = (changes.nonce.take_original_ref(), self.nonce(address)) {
+                if read_nonce != nonce {
+                    conflicts.add_nonce(address.clone(), nonce.clone(), read_nonce.clone());
+                }
+            }
+            if let (Some(read_balance), Some(balance)) = (changes.balance.take_original_ref(), self.balance(address)) {
+                if read_balance != balance {
+                    conflicts.add_balance(address.clone(), balance.clone(), read_balance.clone());
+                }
+            }
+
+            // check slots conflicts
+            for (index, slot) in &changes.slots {
+                if let (Some(read_slot), Some(overlay_slot)) = (slot.take_original_ref(), self.slot(address, index)) {
+                    if read_slot.value != overlay_slot.value {
+                        conflicts.add_slot(address.clone(), index.clone(), overlay_slot.value.clone(), read_slot.value.clone());
+                    }
+                }
+            }
+        }
+
+        conflicts.build()
+    }
+}
@@ -0,0 +1,45 @@
+//! Trace Filter Module
+//!
+//! Filters call frames of transactions re-executed by the `trace_filter`
+//! JSON-RPC method. Filtering happens on the frames themselves, so the block
+//! range is used only to select which stored blocks are traced, while the
+//! addresses select which of their frames are returned.
+
+use crate::eth::primitives::Address;
+use crate::eth::primitives::BlockNumber;
+use crate::eth::primitives::ExecutionTraceFrame;
+use crate::ext::not;
+
+#[derive(Debug)]
+pub struct TraceFilter {
+    pub from_block: BlockNumber,
+    pub to_block: BlockNumber,
+    pub from_addresses: Vec<Address>,
+    pub to_addresses: Vec<Address>,
+    pub after: usize,
+    pub count: Option<usize>,
+}
+
+impl TraceFilter {
+    /// Number of blocks in the range, each one re-executed to be filtered.
+    pub fn block_count(&self) -> u64 {
+        (u64::from(self.to_block) + 1).saturating_sub(self.from_block.into())
+    }
+
+    /// Checks if a call frame matches the filter.
+    ///
+    /// Empty address lists match any address. When both lists are present, the frame must match both.
+    pub fn matches(&self, frame: &ExecutionTraceFrame) -> bool {
+        // filter sender
+        if not(self.from_addresses.is_empty()) && not(self.from_addresses.contains(&frame.from)) {
+            return false;
+        }
+
+        // filter target
+        if not(self.to_addresses.is_empty()) && not(frame.to.as_ref().is_some_and(|to| self.to_addresses.contains(to))) {
+            return false;
+        }
+
+        true
+    }
+}
@@ -0,0 +1,63 @@
+//! Trace Filter Input Module
+//!
+//! Manages the input structure of the `trace_filter` JSON-RPC method. Block
+//! selections are translated to a closed range of stored blocks, so the
+//! filter always knows which blocks must be re-executed.
+
+use std::sync::Arc;
+
+use crate::eth::primitives::Address;
+use crate::eth::primitives::BlockSelection;
+use crate::eth::primitives::StoragePointInTime;
+use crate::eth::primitives::TraceFilter;
+use crate::eth::storage::EthStorage;
+
+/// JSON-RPC input used in the `trace_filter` method.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+pub struct TraceFilterInput {
+    #[serde(rename = "fromBlock", default)]
+    pub from_block: Option<BlockSelection>,
+
+    #[serde(rename = "toBlock", default)]
+    pub to_block: Option<BlockSelection>,
+
+    #[serde(rename = "fromAddress", default)]
+    pub from_address: Vec<Address>,
+
+    #[serde(rename = "toAddress", default)]
+    pub to_address: Vec<Address>,
+
+    #[serde(rename = "after", default)]
+    pub after: Option<usize>,
+
+    #[serde(rename = "count", default)]
+    pub count: Option<usize>,
+}
+
+impl TraceFilterInput {
+    /// Parses itself into a filter that can be applied to traces of stored blocks.
+    pub async fn parse(self, storage: &Arc<dyn EthStorage>) -> anyhow::Result<TraceFilter> {
+        let current = storage.read_current_block_number().await?;
+
+        // translate point-in-time to block according to context
+        let from = storage.translate_to_point_in_time(&self.from_block.unwrap_or(BlockSelection::Latest)).await?;
+        let from = match from {
+            StoragePointInTime::Present => current,
+            StoragePointInTime::Past(number) => number,
+        };
+        let to = storage.translate_to_point_in_time(&self.to_block.unwrap_or(BlockSelection::Latest)).await?;
+        let to = match to {
+            StoragePointInTime::Present => current,
+            StoragePointInTime::Past(number) => number,
+        };
+
+        Ok(TraceFilter {
+            from_block: from,
+            to_block: to,
+            from_addresses: self.from_address,
+            to_addresses: self.to_address,
+            after: self.after.unwrap_or_default(),
+            count: self.count,
+        })
+    }
+}
@@ -0,0 +1,165 @@
+//! Tracer Module
+//!
+//! Defines the tracers supported by the `debug_trace*` JSON-RPC methods and
+//! how each of them renders an execution and its trace. Output formats follow
+//! geth built-in tracers, so existing tools (Hardhat, Tenderly, block
+//! explorers) can consume them without adaptation.
+
+use std::collections::BTreeMap;
+
+use ethereum_types::H256;
+use ethers_core::types::AccountState as EthersAccountState;
+use ethers_core::types::DiffMode as EthersDiffMode;
+use ethers_core::types::GethTrace as EthersGethTrace;
+use ethers_core::types::GethTraceFrame as EthersGethTraceFrame;
+use ethers_core::types::PreStateFrame as EthersPreStateFrame;
+use ethers_core::types::PreStateMode as EthersPreStateMode;
+use ethers_core::types::H160;
+use serde_json::Value as JsonValue;
+
+use crate::eth::primitives::Execution;
+use crate::eth::primitives::ExecutionAccountChanges;
+use crate::eth::primitives::ExecutionTrace;
+use crate::eth::primitives::Slot;
+use crate::ext::not;
+use crate::if_else;
+
+/// Tracer used to render an execution trace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Tracer {
+    /// Default geth tracer that returns every opcode executed.
+    StructLogger { disable_stack: bool, disable_storage: bool },
+
+    /// Tracer that returns the tree of call frames.
+    CallTracer { only_top_call: bool, with_log: bool },
+
+    /// Tracer that returns the accounts state touched by the execution.
+    PrestateTracer { diff_mode: bool },
+}
+
+impl Default for Tracer {
+    fn default() -> Self {
+        Self::StructLogger {
+            disable_stack: false,
+            disable_storage: false,
+        }
+    }
+}
+
+impl Tracer {
+    /// Checks if the tracer needs opcode steps to be collected during execution.
+    pub fn needs_steps(&self) -> bool {
+        matches!(self, Self::StructLogger { .. })
+    }
+
+    /// Checks if the tracer needs the stack to be collected in opcode steps.
+    pub fn needs_stack(&self) -> bool {
+        matches!(self, Self::StructLogger { disable_stack: false, .. })
+    }
+
+    /// Checks if the tracer needs the storage to be collected in opcode steps.
+    pub fn needs_storage(&self) -> bool {
+        matches!(self, Self::StructLogger { disable_storage: false, .. })
+    }
+
+    /// Renders the execution and its trace according to the tracer format.
+    pub fn to_json_rpc_trace(&self, execution: &Execution, trace: &ExecutionTrace) -> JsonValue {
+        let frame: EthersGethTraceFrame = match self {
+            Self::StructLogger { .. } => trace.to_geth_default_frame(execution).into(),
+            Self::CallTracer { only_top_call, with_log } => trace.to_geth_call_frame(*only_top_call, *with_log).into(),
+            Self::PrestateTracer { diff_mode: false } => EthersPreStateFrame::Default(EthersPreStateMode(prestate(execution))).into(),
+            Self::PrestateTracer { diff_mode: true } => {
+                let (pre, post) = prestate_diff(execution);
+                EthersPreStateFrame::Diff(EthersDiffMode { pre, post }).into()
+            }
+        };
+        serde_json::to_value(EthersGethTrace::Known(frame)).unwrap()
+    }
+}
+
+/// State of all accounts touched by the execution before it happened.
+fn prestate(execution: &Execution) -> BTreeMap<H160, EthersAccountState> {
+    let mut accounts = BTreeMap::new();
+    for changes in &execution.changes {
+        if changes.is_account_creation() {
+            continue;
+        }
+        let storage = changes
+            .slots
+            .values()
+            .filter_map(|slot| slot.take_original_ref())
+            .map(|slot| (slot_index_to_h256(slot), slot_value_to_h256(slot)))
+            .collect();
+        accounts.insert(changes.address.clone().into(), original_account_state(changes, storage));
+    }
+    accounts
+}
+
+/// State of modified accounts before and after the execution.
+fn prestate_diff(execution: &Execution) -> (BTreeMap<H160, EthersAccountState>, BTreeMap<H160, EthersAccountState>) {
+    let mut pre = BTreeMap::new();
+    let mut post = BTreeMap::new();
+
+    for changes in &execution.changes {
+        let modified_slots = changes.slots.values().filter(|slot| slot.is_modified()).collect::<Vec<_>>();
+        let account_modified = changes.nonce.is_modified() || changes.balance.is_modified() || changes.bytecode.is_modified();
+        if not(account_modified) && modified_slots.is_empty() {
+            continue;
+        }
+        let address: H160 = changes.address.clone().into();
+
+        // pre: original values of the account and its modified slots
+        if changes.is_account_update() {
+            let storage = modified_slots
+                .iter()
+                .filter_map(|slot| slot.take_original_ref())
+                .map(|slot| (slot_index_to_h256(slot), slot_value_to_h256(slot)))
+                .collect();
+            pre.insert(address, original_account_state(changes, storage));
+        }
+
+        // post: only the values that were modified
+        let storage: BTreeMap<H256, H256> = modified_slots
+            .iter()
+            .filter_map(|slot| slot.take_modified_ref())
+            .map(|slot| (slot_index_to_h256(slot), slot_value_to_h256(slot)))
+            .collect();
+        let state = EthersAccountState {
+            balance: if_modified(changes.balance.is_modified(), changes.balance.take_modified_ref()).map(|balance| balance.clone().into()),
+            nonce: if_modified(changes.nonce.is_modified(), changes.nonce.take_modified_ref()).map(|nonce| nonce.clone().into()),
+            code: if_modified(changes.bytecode.is_modified(), changes.bytecode.take_modified_ref())
+                .and_then(|bytecode| bytecode.as_ref())
+                .map(const_hex::encode_prefixed),
+            storage: if_else!(storage.is_empty(), None, Some(storage)),
+        };
+        post.insert(address, state);
+    }
+
+    (pre, post)
+}
+
+fn original_account_state(changes: &ExecutionAccountChanges, storage: BTreeMap<H256, H256>) -> EthersAccountState {
+    EthersAccountState {
+        balance: changes.balance.take_original_ref().map(|balance| balance.clone().into()),
+        nonce: changes.nonce.take_original_ref().map(|nonce| nonce.clone().into()),
+        code: changes
+            .bytecode
+            .take_original_ref()
+            .and_then(|bytecode| bytecode.as_ref())
+            .filter(|bytecode| not(bytecode.is_empty()))
+            .map(const_hex::encode_prefixed),
+        storage: if_else!(storage.is_empty(), None, Some(storage)),
+    }
+}
+
+fn if_modified<T>(modified: bool, value: Option<T>) -> Option<T> {
+    if_else!(modified, value, None)
+}
+
+fn slot_index_to_h256(slot: &Slot) -> H256 {
+    H256::from(<[u8; 32]>::from(slot.index.clone()))
+}
+
+fn slot_value_to_h256(slot: &Slot) -> H256 {
+    H256::from(<[u8; 32]>::from(slot.value.clone()))
+}
@@ -0,0 +1,108 @@
+//! Tracer Input Module
+//!
+//! Manages the options object accepted by `debug_traceTransaction`,
+//! `debug_traceCall` and `debug_traceBlockByNumber`. The options select one of
+//! geth built-in tracers and its configuration, and are parsed into a
+//! [`Tracer`] before any execution happens, so unsupported tracers are
+//! rejected early.
+
+use ethers_core::types::CallConfig as EthersCallConfig;
+use ethers_core::types::PreStateConfig as EthersPreStateConfig;
+use serde_json::Value as JsonValue;
+
+use crate::eth::primitives::Tracer;
+use crate::if_else;
+
+/// JSON-RPC input used in methods like `debug_traceTransaction` and `debug_traceCall`.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+pub struct TracerInput {
+    #[serde(rename = "tracer", default)]
+    pub tracer: Option<String>,
+
+    #[serde(rename = "tracerConfig", default)]
+    pub tracer_config: Option<JsonValue>,
+
+    #[serde(rename = "disableStack", default)]
+    pub disable_stack: bool,
+
+    #[serde(rename = "disableStorage", default)]
+    pub disable_storage: bool,
+}
+
+impl TracerInput {
+    /// Parses itself into the tracer that will render the execution trace.
+    pub fn parse(self) -> anyhow::Result<Tracer> {
+        let config = self.tracer_config.unwrap_or(JsonValue::Null);
+        let config = if_else!(config.is_null(), JsonValue::Object(Default::default()), config);
+
+        match self.tracer.as_deref() {
+            None | Some("") => Ok(Tracer::StructLogger {
+                disable_stack: self.disable_stack,
+                disable_storage: self.disable_storage,
+            }),
+            Some("callTracer") => {
+                let config: EthersCallConfig = serde_json::from_value(config)?;
+                Ok(Tracer::CallTracer {
+                    only_top_call: config.only_top_call.unwrap_or_default(),
+                    with_log: config.with_log.unwrap_or_default(),
+                })
+            }
+            Some("prestateTracer") => {
+                let config: EthersPreStateConfig = serde_json::from_value(config)?;
+                Ok(Tracer::PrestateTracer {
+                    diff_mode: config.diff_mode.unwrap_or_default(),
+                })
+            }
+            Some(tracer) => Err(anyhow::anyhow!("Tracer '{}' is not supported.", tracer)),
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Tests
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::eth::primitives::*;
+
+    #[test]
+    fn parse_tracer_with_default_tracer() {
+        let json = json!({"disableStorage": true});
+        let tracer = serde_json::from_value::<TracerInput>(json).unwrap().parse().unwrap();
+        assert_eq!(
+            tracer,
+            Tracer::StructLogger {
+                disable_stack: false,
+                disable_storage: true
+            }
+        );
+    }
+
+    #[test]
+    fn parse_tracer_with_call_tracer() {
+        let json = json!({"tracer": "callTracer", "tracerConfig": {"withLog": true}});
+        let tracer = serde_json::from_value::<TracerInput>(json).unwrap().parse().unwrap();
+        assert_eq!(
+            tracer,
+            Tracer::CallTracer {
+                only_top_call: false,
+                with_log: true
+            }
+        );
+    }
+
+    #[test]
+    fn parse_tracer_with_prestate_tracer_in_diff_mode() {
+        let json = json!({"tracer": "prestateTracer", "tracerConfig": {"diffMode": true}});
+        let tracer = serde_json::from_value::<TracerInput>(json).unwrap().parse().unwrap();
+        assert_eq!(tracer, Tracer::PrestateTracer { diff_mode: true });
+    }
+
+    #[test]
+    fn parse_tracer_with_unsupported_tracer() {
+        let json = json!({"tracer": "4byteTracer"});
+        assert!(serde_json::from_value::<TracerInput>(json).unwrap().parse().is_err());
+    }
+}
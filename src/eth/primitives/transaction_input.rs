@@ -9,13 +9,20 @@ use anyhow::anyhow;
 use ethereum_types::U256;
 use ethereum_types::U64;
 use ethers_core::types::Transaction as EthersTransaction;
+use ethers_core::types::H160;
+use ethers_core::utils::keccak256;
 use fake::Dummy;
 use fake::Fake;
 use fake::Faker;
 use rlp::Decodable;
+use rlp::RlpStream;
+use serde_json::Value as JsonValue;
 
+use crate::config::EvmHardfork;
+use crate::eth::primitives::AccessList;
 use crate::eth::primitives::Address;
 use crate::eth::primitives::Bytes;
+use crate::eth::primitives::CallInput;
 use crate::eth::primitives::ChainId;
 use crate::eth::primitives::Gas;
 use crate::eth::primitives::Hash;
@@ -23,11 +30,43 @@ use crate::eth::primitives::Nonce;
 use crate::eth::primitives::Wei;
 use crate::ext::not;
 use crate::ext::OptionExt;
+use crate::if_else;
+
+/// Gas charged for every transaction.
+const TRANSACTION_BASE_GAS: u64 = 21_000;
+
+/// Additional gas charged for transactions that deploy contracts.
+const CONTRACT_CREATION_GAS: u64 = 32_000;
+
+/// Gas charged for each zero byte of the transaction input.
+const ZERO_BYTE_GAS: u64 = 4;
+
+/// Gas charged for each non-zero byte of the transaction input.
+const NON_ZERO_BYTE_GAS: u64 = 16;
+
+/// Gas charged for each 32-byte word of the creation code of contract deployments, since `shanghai` (EIP-3860).
+const INITCODE_WORD_GAS: u64 = 2;
+
+/// Gas charged for each account in the access list.
+const ACCESS_LIST_ADDRESS_GAS: u64 = 2_400;
+
+/// Gas charged for each slot in the access list.
+const ACCESS_LIST_SLOT_GAS: u64 = 1_900;
+
+/// Type of transactions without an envelope.
+const LEGACY_TRANSACTION_TYPE: u64 = 0;
+
+/// Type of transactions with fee caps instead of a gas price (EIP-1559).
+const DYNAMIC_FEE_TRANSACTION_TYPE: u64 = 2;
 
 #[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct TransactionInput {
     pub chain_id: ChainId,
     pub hash: Hash,
+
+    /// Envelope type (EIP-2718): legacy, access list or dynamic fee.
+    pub transaction_type: U64,
+
     pub nonce: Nonce,
     pub signer: Address,
     pub from: Address,
@@ -35,7 +74,12 @@ pub struct TransactionInput {
     pub value: Wei,
     pub input: Bytes,
     pub gas: Gas,
+
+    /// Gas price of legacy and access list transactions, or the maximum fee per gas of dynamic fee transactions.
     pub gas_price: Wei,
+    pub max_fee_per_gas: Option<Wei>,
+    pub max_priority_fee_per_gas: Option<Wei>,
+    pub access_list: AccessList,
 
     pub v: U64,
     pub r: U256,
@@ -43,10 +87,80 @@ pub struct TransactionInput {
 }
 
 impl TransactionInput {
+    /// Creates an unsigned transaction sent by an impersonated account.
+    ///
+    /// There is no signature to hash, so the hash is derived from the sender and the transaction fields.
+    pub fn new_impersonated(chain_id: ChainId, nonce: Nonce, input: CallInput) -> Self {
+        let mut rlp = RlpStream::new_list(6);
+        rlp.append(&H160::from(input.from.clone()));
+        rlp.append(&U256::from(nonce.clone()));
+        match input.to {
+            Some(ref to) => rlp.append(&H160::from(to.clone())),
+            None => rlp.append_empty_data(),
+        };
+        rlp.append(&U256::from(input.value.clone()));
+        rlp.append(&input.data.as_ref().to_vec());
+        rlp.append(&U256::from(chain_id.clone()));
+
+        Self {
+            chain_id,
+            hash: Hash::new(keccak256(rlp.out())),
+            nonce,
+            signer: input.from.clone(),
+            from: input.from,
+            to: input.to,
+            value: input.value,
+            input: input.data,
+            gas: Gas::TRANSACTION_LIMIT,
+            ..Default::default()
+        }
+    }
+
     /// Checks if the current transaction is for a contract deployment.
     pub fn is_contract_deployment(&self) -> bool {
         self.to.is_none() && not(self.input.is_empty())
     }
+
+    /// Checks if the transaction has no envelope, as transactions before EIP-2718.
+    pub fn is_legacy(&self) -> bool {
+        self.transaction_type.as_u64() == LEGACY_TRANSACTION_TYPE
+    }
+
+    /// Gas consumed before any code is executed in the given hardfork, covering the transaction itself, its input data and its access list.
+    pub fn intrinsic_gas(&self, hardfork: EvmHardfork) -> Gas {
+        intrinsic_gas(self.to.as_ref(), &self.input, &self.access_list, hardfork)
+    }
+
+    /// Gas price effectively paid by the transaction when mined in a block with the given base fee.
+    ///
+    /// Dynamic fee transactions pay the base fee plus their priority fee, limited by their maximum fee.
+    pub fn effective_gas_price(&self, base_fee: &Wei) -> Wei {
+        match (self.transaction_type.as_u64(), &self.max_fee_per_gas, &self.max_priority_fee_per_gas) {
+            (DYNAMIC_FEE_TRANSACTION_TYPE, Some(max_fee), Some(max_priority_fee)) => {
+                let price = U256::from(base_fee.clone()).saturating_add(max_priority_fee.clone().into());
+                max_fee.clone().min(price.into())
+            }
+            _ => self.gas_price.clone(),
+        }
+    }
+
+    /// Serializes itself to JSON-RPC transaction format of a transaction that is not mined yet.
+    pub fn to_json_rpc_transaction(self) -> JsonValue {
+        let json_rpc_format: EthersTransaction = self.into();
+        serde_json::to_value(json_rpc_format).unwrap()
+    }
+}
+
+/// Gas consumed before any code is executed by a transaction or call with the given recipient, input data and access list.
+pub(super) fn intrinsic_gas(to: Option<&Address>, input: &[u8], access_list: &AccessList, hardfork: EvmHardfork) -> Gas {
+    let creation_gas = match to {
+        None if hardfork >= EvmHardfork::Shanghai => CONTRACT_CREATION_GAS + (input.len() as u64).div_ceil(32) * INITCODE_WORD_GAS,
+        None => CONTRACT_CREATION_GAS,
+        Some(_) => 0,
+    };
+    let input_gas: u64 = input.iter().map(|byte| if_else!(*byte == 0, ZERO_BYTE_GAS, NON_ZERO_BYTE_GAS)).sum();
+    let access_list_gas = access_list.len() as u64 * ACCESS_LIST_ADDRESS_GAS + access_list.slots_len() as u64 * ACCESS_LIST_SLOT_GAS;
+    (TRANSACTION_BASE_GAS + creation_gas + input_gas + access_list_gas).into()
 }
 
 impl Dummy<Faker> for TransactionInput {
@@ -54,6 +168,7 @@ impl Dummy<Faker> for TransactionInput {
         Self {
             chain_id: faker.fake_with_rng(rng),
             hash: faker.fake_with_rng(rng),
+            transaction_type: LEGACY_TRANSACTION_TYPE.into(),
             nonce: faker.fake_with_rng(rng),
             signer: faker.fake_with_rng(rng),
             from: faker.fake_with_rng(rng),
@@ -62,6 +177,9 @@ impl Dummy<Faker> for TransactionInput {
             input: faker.fake_with_rng(rng),
             gas: faker.fake_with_rng(rng),
             gas_price: faker.fake_with_rng(rng),
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+            access_list: AccessList::default(),
             v: rng.next_u64().into(),
             r: rng.next_u64().into(),
             s: rng.next_u64().into(),
@@ -118,18 +236,21 @@ impl TryFrom<EthersTransaction> for TransactionInput {
             }
         };
 
-        // extract gas price
-        let gas_price: Wei = match value.gas_price {
+        // extract gas price, replaced by the maximum fee per gas in dynamic fee transactions
+        let gas_price: Wei = match value.gas_price.or(value.max_fee_per_gas) {
             Some(wei) => wei.into(),
             None => {
-                tracing::warn!(reason = %"transaction without gasPrice");
-                return Err(ConversionError::NoWei(anyhow!("Transaction sent without gasPrice is not allowed.")));
+                tracing::warn!(reason = %"transaction without gasPrice or maxFeePerGas");
+                return Err(ConversionError::NoWei(anyhow!(
+                    "Transaction sent without gasPrice or maxFeePerGas is not allowed."
+                )));
             }
         };
 
         Ok(Self {
             chain_id,
             hash: value.hash.into(),
+            transaction_type: value.transaction_type.unwrap_or_else(|| LEGACY_TRANSACTION_TYPE.into()),
             nonce: value.nonce.into(),
             signer,
             from: value.from.into(),
@@ -138,6 +259,9 @@ impl TryFrom<EthersTransaction> for TransactionInput {
             input: value.input.clone().into(),
             gas: value.gas.into(),
             gas_price,
+            max_fee_per_gas: value.max_fee_per_gas.map_into(),
+            max_priority_fee_per_gas: value.max_priority_fee_per_gas.map_into(),
+            access_list: value.access_list.map_into().unwrap_or_default(),
             v: value.v,
             r: value.r,
             s: value.s,
@@ -145,6 +269,34 @@ impl TryFrom<EthersTransaction> for TransactionInput {
     }
 }
 
+// -----------------------------------------------------------------------------
+// Conversions: Self -> Other
+// -----------------------------------------------------------------------------
+impl From<TransactionInput> for EthersTransaction {
+    fn from(value: TransactionInput) -> Self {
+        let is_legacy = value.is_legacy();
+        Self {
+            chain_id: Some(value.chain_id.into()),
+            hash: value.hash.into(),
+            transaction_type: Some(value.transaction_type),
+            nonce: value.nonce.into(),
+            from: value.signer.into(),
+            to: value.to.map_into(),
+            value: value.value.into(),
+            gas_price: Some(value.gas_price.into()),
+            max_fee_per_gas: value.max_fee_per_gas.map_into(),
+            max_priority_fee_per_gas: value.max_priority_fee_per_gas.map_into(),
+            access_list: if_else!(is_legacy, None, Some(value.access_list.into())),
+            gas: value.gas.into(),
+            input: value.input.into(),
+            v: value.v,
+            r: value.r,
+            s: value.s,
+            ..Default::default()
+        }
+    }
+}
+
 // impl TryFrom<Vec<u8>> for TransactionInput {
 //     type Error = EthError;
 
@@ -162,3 +314,106 @@ impl TryFrom<EthersTransaction> for TransactionInput {
 //         Ok(input)
 //     }
 // }
+
+// -----------------------------------------------------------------------------
+// Tests
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use ethereum_types::H160;
+    use ethereum_types::H256;
+    use ethereum_types::U256;
+    use ethers_core::k256::ecdsa::SigningKey;
+    use ethers_core::types::transaction::eip2718::TypedTransaction;
+    use ethers_core::types::transaction::eip2930::AccessList as EthersAccessList;
+    use ethers_core::types::transaction::eip2930::AccessListItem as EthersAccessListItem;
+    use ethers_core::types::Eip1559TransactionRequest;
+    use ethers_core::types::Signature as EthersSignature;
+    use ethers_core::types::Transaction as EthersTransaction;
+    use ethers_core::utils::secret_key_to_address;
+    use fake::Fake;
+    use fake::Faker;
+
+    use super::DYNAMIC_FEE_TRANSACTION_TYPE;
+    use crate::config::EvmHardfork;
+    use crate::eth::primitives::*;
+
+    #[test]
+    fn intrinsic_gas_charges_creation_and_input_bytes() {
+        let transfer = TransactionInput {
+            to: Some(Faker.fake()),
+            input: Bytes::default(),
+            ..Faker.fake()
+        };
+        assert_eq!(transfer.intrinsic_gas(EvmHardfork::London), 21_000u64.into());
+
+        let deployment = TransactionInput {
+            to: None,
+            input: vec![0u8, 0, 1].into(),
+            ..Faker.fake()
+        };
+        assert_eq!(deployment.intrinsic_gas(EvmHardfork::London), (21_000u64 + 32_000 + 4 + 4 + 16).into());
+    }
+
+    #[test]
+    fn intrinsic_gas_charges_initcode_words_since_shanghai() {
+        let deployment = TransactionInput {
+            to: None,
+            input: vec![1u8; 33].into(),
+            ..Faker.fake()
+        };
+        let london_gas = 21_000u64 + 32_000 + 33 * 16;
+        assert_eq!(deployment.intrinsic_gas(EvmHardfork::Paris), london_gas.into());
+        assert_eq!(deployment.intrinsic_gas(EvmHardfork::Shanghai), (london_gas + 2 * 2).into());
+        assert_eq!(deployment.intrinsic_gas(EvmHardfork::Cancun), (london_gas + 2 * 2).into());
+
+        // calls do not execute their input as creation code
+        let call = TransactionInput {
+            to: Some(Faker.fake()),
+            ..deployment
+        };
+        assert_eq!(call.intrinsic_gas(EvmHardfork::Shanghai), (21_000u64 + 33 * 16).into());
+    }
+
+    #[test]
+    fn decode_dynamic_fee_transaction() {
+        let key = SigningKey::from_bytes(&[1u8; 32].into()).unwrap();
+        let request = Eip1559TransactionRequest::new()
+            .chain_id(2008u64)
+            .nonce(1u64)
+            .to(H160::repeat_byte(2))
+            .value(3u64)
+            .gas(50_000u64)
+            .max_fee_per_gas(10u64)
+            .max_priority_fee_per_gas(4u64)
+            .access_list(EthersAccessList(vec![EthersAccessListItem {
+                address: H160::repeat_byte(2),
+                storage_keys: vec![H256::zero()],
+            }]));
+        let unsigned = TypedTransaction::Eip1559(request);
+
+        let (signature, recovery_id) = key.sign_prehash_recoverable(unsigned.sighash().as_bytes()).unwrap();
+        let (r, s) = signature.split_bytes();
+        let signature = EthersSignature {
+            r: U256::from_big_endian(&r),
+            s: U256::from_big_endian(&s),
+            v: recovery_id.to_byte() as u64,
+        };
+        let raw = unsigned.rlp_signed(&signature);
+
+        // decoded with the fee caps and access list
+        let transaction: TransactionInput = rlp::decode(&raw).unwrap();
+        assert_eq!(transaction.transaction_type, DYNAMIC_FEE_TRANSACTION_TYPE.into());
+        assert_eq!(transaction.signer, secret_key_to_address(&key).into());
+        assert_eq!(transaction.max_fee_per_gas, Some(10u64.into()));
+        assert_eq!(transaction.max_priority_fee_per_gas, Some(4u64.into()));
+        assert_eq!(transaction.effective_gas_price(&Wei::ZERO), 4u64.into());
+        assert_eq!(transaction.effective_gas_price(&5u64.into()), 9u64.into());
+        assert_eq!(transaction.effective_gas_price(&8u64.into()), 10u64.into());
+        assert_eq!(transaction.access_list.len(), 1);
+        assert_eq!(transaction.intrinsic_gas(EvmHardfork::London), (21_000u64 + 2_400 + 1_900).into());
+
+        // encoded back to the same bytes
+        assert_eq!(EthersTransaction::from(transaction).rlp(), raw);
+    }
+}
@@ -6,17 +6,22 @@
 //! tracking transaction history and understanding the state of transactions in
 //! the blockchain.
 
+use ethereum_types::U256;
 use ethers_core::types::Transaction as EthersTransaction;
 use ethers_core::types::TransactionReceipt as EthersReceipt;
 use itertools::Itertools;
+use rlp::RlpStream;
 use serde_json::Value as JsonValue;
 
 use crate::eth::primitives::BlockNumber;
 use crate::eth::primitives::Execution;
+use crate::eth::primitives::Gas;
 use crate::eth::primitives::Hash;
 use crate::eth::primitives::Index;
 use crate::eth::primitives::LogMined;
+use crate::eth::primitives::LogsBloom;
 use crate::eth::primitives::TransactionInput;
+use crate::eth::primitives::Wei;
 use crate::ext::OptionExt;
 use crate::if_else;
 
@@ -29,12 +34,21 @@ pub struct TransactionMined {
     /// Transaction EVM execution result.
     pub execution: Execution,
 
+    /// Gas price paid by the transaction, considering the base fee of the block.
+    pub effective_gas_price: Wei,
+
     /// Logs added to the block.
     pub logs: Vec<LogMined>,
 
     /// Position of the transaction inside the block.
     pub transaction_index: Index,
 
+    /// Gas used by this transaction and all transactions before it in the block.
+    pub cumulative_gas_used: Gas,
+
+    /// Bloom filter of the logs emitted by this transaction.
+    pub logs_bloom: LogsBloom,
+
     /// Block number where the transaction was mined.
     pub block_number: BlockNumber,
 
@@ -59,6 +73,28 @@ impl TransactionMined {
         let json_rpc_format: EthersReceipt = self.into();
         serde_json::to_value(json_rpc_format).unwrap()
     }
+
+    /// Serializes its receipt to RLP format, used to calculate the block receipts root.
+    ///
+    /// Receipts of typed transactions are prefixed by the transaction type (EIP-2718).
+    pub fn to_rlp_receipt(&self) -> Vec<u8> {
+        let mut s = RlpStream::new_list(4);
+        s.append(&if_else!(self.is_success(), 1u8, 0u8));
+        s.append(&U256::from(self.cumulative_gas_used.clone()));
+        s.append(&*self.logs_bloom);
+        s.begin_list(self.logs.len());
+        for log in &self.logs {
+            s.append(&log.log);
+        }
+
+        if self.input.is_legacy() {
+            s.out().to_vec()
+        } else {
+            let mut encoded = vec![self.input.transaction_type.as_u64() as u8];
+            encoded.extend_from_slice(&s.out());
+            encoded
+        }
+    }
 }
 
 // -----------------------------------------------------------------------------
@@ -66,25 +102,12 @@ impl TransactionMined {
 // -----------------------------------------------------------------------------
 impl From<TransactionMined> for EthersTransaction {
     fn from(value: TransactionMined) -> Self {
-        let input = value.input;
-        Self {
-            chain_id: Some(input.chain_id.into()),
-            hash: input.hash.into(),
-            nonce: input.nonce.into(),
-            block_hash: Some(value.block_hash.into()),
-            block_number: Some(value.block_number.into()),
-            transaction_index: Some(value.transaction_index.into()),
-            from: input.signer.into(),
-            to: input.to.map_into(),
-            value: input.value.into(),
-            gas_price: Some(input.gas_price.into()),
-            gas: input.gas.into(),
-            input: input.input.into(),
-            v: input.v,
-            r: input.r,
-            s: input.s,
-            ..Default::default()
-        }
+        let mut transaction: EthersTransaction = value.input.into();
+        transaction.gas_price = Some(value.effective_gas_price.into());
+        transaction.block_hash = Some(value.block_hash.into());
+        transaction.block_number = Some(value.block_number.into());
+        transaction.transaction_index = Some(value.transaction_index.into());
+        transaction
     }
 }
 
@@ -99,7 +122,10 @@ impl From<TransactionMined> for EthersReceipt {
             transaction_hash: value.input.hash.into(),
             from: value.input.signer.into(),
             to: value.input.to.map_into(),
-            gas_used: Some(value.input.gas.into()),
+            gas_used: Some(value.execution.gas.into()),
+            cumulative_gas_used: value.cumulative_gas_used.into(),
+            effective_gas_price: Some(value.effective_gas_price.into()),
+            transaction_type: Some(value.input.transaction_type),
 
             // block
             block_hash: Some(value.block_hash.into()),
@@ -108,9 +134,45 @@ impl From<TransactionMined> for EthersReceipt {
 
             // logs
             logs: value.logs.into_iter().map_into().collect(),
+            logs_bloom: *value.logs_bloom,
 
-            // TODO: there are more fields to populate here
+            // not used after byzantium or specific to other chains
             ..Default::default()
         }
     }
 }
+
+// -----------------------------------------------------------------------------
+// Tests
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use ethers_core::types::TransactionReceipt as EthersReceipt;
+    use fake::Fake;
+    use fake::Faker;
+
+    use crate::eth::primitives::*;
+
+    #[test]
+    fn receipt_reports_gas_used_by_execution() {
+        let mut transaction: TransactionMined = Faker.fake();
+        transaction.input.gas = 100_000u64.into();
+        transaction.execution.gas = 21_000u64.into();
+        transaction.cumulative_gas_used = 42_000u64.into();
+
+        let receipt: EthersReceipt = transaction.into();
+        assert_eq!(receipt.gas_used, Some(21_000u64.into()));
+        assert_eq!(receipt.cumulative_gas_used, 42_000u64.into());
+    }
+
+    #[test]
+    fn typed_receipt_is_prefixed_by_transaction_type() {
+        let mut transaction: TransactionMined = Faker.fake();
+        let legacy = transaction.to_rlp_receipt();
+
+        transaction.input.transaction_type = 2u64.into();
+        let typed = transaction.to_rlp_receipt();
+        assert_eq!(typed[0], 2);
+        assert_eq!(&typed[1..], &legacy[..]);
+    }
+}
@@ -2,6 +2,7 @@
 
 mod rpc_context;
 mod rpc_error;
+mod rpc_filters;
 mod rpc_middleware;
 mod rpc_parser;
 mod rpc_server;
@@ -9,11 +10,18 @@ mod rpc_subscriptions;
 
 use rpc_context::RpcContext;
 pub use rpc_error::RpcError;
+use rpc_filters::FilterId;
+use rpc_filters::RpcFilterChanges;
+pub use rpc_filters::RpcFilters;
 use rpc_middleware::RpcMiddleware;
 use rpc_parser::next_rpc_param;
 use rpc_parser::next_rpc_param_or_default;
 use rpc_parser::parse_rpc_rlp;
+use rpc_parser::rpc_conflict_error;
+use rpc_parser::rpc_execution_reverted_error;
 use rpc_parser::rpc_internal_error;
+use rpc_parser::rpc_invalid_transaction_error;
 use rpc_parser::rpc_parsing_error;
+use rpc_parser::RpcQuantity;
 pub use rpc_server::serve_rpc;
 pub use rpc_subscriptions::RpcSubscriptions;
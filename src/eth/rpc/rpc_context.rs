@@ -1,30 +1,38 @@
 use std::fmt::Debug;
 use std::sync::Arc;
 
+use crate::eth::rpc::RpcFilters;
 use crate::eth::rpc::RpcSubscriptions;
 use crate::eth::storage::EthStorage;
 use crate::eth::EthExecutor;
 
 pub struct RpcContext {
     // blockchain config
-    pub chain_id: u16,
-    pub client_version: &'static str,
+    pub chain_id: u64,
+    pub network_id: u64,
+    pub client_version: String,
 
-    // gas config
-    pub gas_price: usize,
+    // limits
+    pub trace_filter_max_blocks: u64,
 
     // services
-    pub executor: EthExecutor,
+    pub executor: Arc<EthExecutor>,
     pub storage: Arc<dyn EthStorage>,
     pub subs: Arc<RpcSubscriptions>,
+    pub filters: Arc<RpcFilters>,
+
+    // development mode
+    pub dev: bool,
 }
 
 impl Debug for RpcContext {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("RpcContext")
             .field("chain_id", &self.chain_id)
+            .field("network_id", &self.network_id)
             .field("client_version", &self.client_version)
-            .field("gas_price", &self.gas_price)
+            .field("trace_filter_max_blocks", &self.trace_filter_max_blocks)
+            .field("dev", &self.dev)
             .finish_non_exhaustive()
     }
 }
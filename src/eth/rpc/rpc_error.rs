@@ -3,6 +3,11 @@ use jsonrpsee::types::error::INTERNAL_ERROR_MSG;
 use jsonrpsee::types::ErrorObject;
 use jsonrpsee::types::ErrorObjectOwned;
 
+use crate::eth::rpc::rpc_conflict_error;
+use crate::eth::rpc::rpc_execution_reverted_error;
+use crate::eth::rpc::rpc_invalid_transaction_error;
+use crate::eth::EthExecutorError;
+
 #[derive(Debug, thiserror::Error)]
 pub enum RpcError {
     /// Generic error executing RPC method.
@@ -19,6 +24,15 @@ pub enum RpcError {
 // -----------------------------------------------------------------------------
 impl From<anyhow::Error> for RpcError {
     fn from(value: anyhow::Error) -> Self {
+        // executor errors have specific codes, so clients can distinguish invalid transactions and contention from other failures
+        if let Some(err) = value.downcast_ref::<EthExecutorError>() {
+            return match err {
+                EthExecutorError::ConflictMaxRetries(_) | EthExecutorError::ConflictTimeout(_) => RpcError::Response(rpc_conflict_error(err.to_string())),
+                EthExecutorError::ExecutionReverted(output) => RpcError::Response(rpc_execution_reverted_error(output.clone())),
+                _ => RpcError::Response(rpc_invalid_transaction_error(err.to_string())),
+            };
+        }
+
         match value.downcast::<ErrorObject>() {
             Ok(err) => RpcError::Response(err),
             Err(err) => RpcError::Generic(err),
@@ -0,0 +1,325 @@
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use ethereum_types::U256;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::RwLock;
+use tokio::time::Duration;
+use tokio::time::Instant;
+
+use crate::eth::primitives::Block;
+use crate::eth::primitives::Hash;
+use crate::eth::primitives::LogFilter;
+use crate::eth::primitives::LogMined;
+use crate::ext::not;
+
+/// Frequency of cleaning up idle filters.
+const CLEANING_FREQUENCY: Duration = Duration::from_secs(10);
+
+/// Time a filter can stay without being polled before it is uninstalled.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Maximum number of changes a filter accumulates between polls. The oldest changes are dropped when it is reached.
+const MAX_PENDING_CHANGES: usize = 10_000;
+
+pub type FilterId = U256;
+
+/// State of JSON-RPC filters installed with `eth_newFilter` and `eth_newBlockFilter`.
+#[derive(Debug, Default)]
+pub struct RpcFilters {
+    /// Installed filters.
+    ///
+    /// Ids are random, so clients cannot guess the ids of filters installed by other clients.
+    filters: RwLock<HashMap<FilterId, RpcFilter>>,
+}
+
+/// Installed filter and the changes that happened since it was last polled.
+#[derive(Debug)]
+struct RpcFilter {
+    kind: RpcFilterKind,
+    last_polled_at: Instant,
+}
+
+#[derive(Debug)]
+enum RpcFilterKind {
+    Logs { filter: LogFilter, pending: VecDeque<LogMined> },
+    NewBlocks { pending: VecDeque<Hash> },
+}
+
+/// Changes returned by `eth_getFilterChanges`.
+#[derive(Debug)]
+pub enum RpcFilterChanges {
+    Logs(Vec<LogMined>),
+    NewBlocks(Vec<Hash>),
+}
+
+impl RpcFilters {
+    /// Spawns a new thread to uninstall filters that were not polled for a while.
+    pub fn spawn_filters_cleaner(self: Arc<Self>) {
+        tokio::spawn(async move {
+            loop {
+                self.remove_idle(Instant::now()).await;
+                tokio::time::sleep(CLEANING_FREQUENCY).await;
+            }
+        });
+    }
+
+    /// Spawns a new thread that accumulates new created blocks in block filters.
+    pub fn spawn_new_heads_collector(self: Arc<Self>, mut rx: broadcast::Receiver<Block>) {
+        tokio::spawn(async move {
+            loop {
+                match rx.recv().await {
+                    Ok(block) => self.collect_block(&block).await,
+                    Err(RecvError::Lagged(skipped)) => tracing::warn!(%skipped, "newHeads collector lagged behind and skipped blocks"),
+                    Err(RecvError::Closed) => {
+                        tracing::warn!("stopping newHeads collector because channel was closed");
+                        return;
+                    }
+                }
+            }
+        });
+    }
+
+    /// Spawns a new thread that accumulates new transactions logs in log filters.
+    pub fn spawn_logs_collector(self: Arc<Self>, mut rx: broadcast::Receiver<LogMined>) {
+        tokio::spawn(async move {
+            loop {
+                match rx.recv().await {
+                    Ok(log) => self.collect_log(&log).await,
+                    Err(RecvError::Lagged(skipped)) => tracing::warn!(%skipped, "logs collector lagged behind and skipped logs"),
+                    Err(RecvError::Closed) => {
+                        tracing::warn!("stopping logs collector because channel was closed");
+                        return;
+                    }
+                }
+            }
+        });
+    }
+
+    /// Accumulates a new created block in block filters.
+    async fn collect_block(&self, block: &Block) {
+        let mut filters = self.filters.write().await;
+        for filter in filters.values_mut() {
+            if let RpcFilterKind::NewBlocks { ref mut pending } = filter.kind {
+                push_bounded(pending, block.hash().clone());
+            }
+        }
+    }
+
+    /// Accumulates a new transaction log in the log filters it matches.
+    async fn collect_log(&self, log: &LogMined) {
+        let mut filters = self.filters.write().await;
+        for filter in filters.values_mut() {
+            if let RpcFilterKind::Logs { ref filter, ref mut pending } = filter.kind {
+                if filter.matches(log) {
+                    push_bounded(pending, log.clone());
+                }
+            }
+        }
+    }
+
+    /// Uninstalls filters that were not polled for a while at the given instant.
+    async fn remove_idle(&self, now: Instant) {
+        let any_idle = self.filters.read().await.values().any(|filter| filter.is_idle(now));
+        if any_idle {
+            let mut filters = self.filters.write().await;
+            let before = filters.len();
+            filters.retain(|_, filter| not(filter.is_idle(now)));
+            tracing::info!(%before, after = filters.len(), "removed idle filters");
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    // Queries
+    // -------------------------------------------------------------------------
+
+    /// Takes the changes accumulated by a filter since it was last polled.
+    pub async fn take_changes(&self, id: &FilterId) -> Option<RpcFilterChanges> {
+        let mut filters = self.filters.write().await;
+        let filter = filters.get_mut(id)?;
+        filter.last_polled_at = Instant::now();
+
+        let changes = match filter.kind {
+            RpcFilterKind::Logs { ref mut pending, .. } => RpcFilterChanges::Logs(std::mem::take(pending).into()),
+            RpcFilterKind::NewBlocks { ref mut pending } => RpcFilterChanges::NewBlocks(std::mem::take(pending).into()),
+        };
+        Some(changes)
+    }
+
+    /// Reads the log filter used by a filter. Returns `None` if the filter does not exist or is not a log filter.
+    pub async fn read_log_filter(&self, id: &FilterId) -> Option<LogFilter> {
+        let mut filters = self.filters.write().await;
+        let filter = filters.get_mut(id)?;
+        filter.last_polled_at = Instant::now();
+
+        match filter.kind {
+            RpcFilterKind::Logs { ref filter, .. } => Some(filter.clone()),
+            RpcFilterKind::NewBlocks { .. } => None,
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    // Mutations
+    // -------------------------------------------------------------------------
+
+    /// Installs a new filter for logs matching the log filter.
+    pub async fn add_logs(&self, filter: LogFilter) -> FilterId {
+        self.add(RpcFilterKind::Logs {
+            filter,
+            pending: VecDeque::new(),
+        })
+        .await
+    }
+
+    /// Installs a new filter for new created blocks.
+    pub async fn add_new_blocks(&self) -> FilterId {
+        self.add(RpcFilterKind::NewBlocks { pending: VecDeque::new() }).await
+    }
+
+    /// Uninstalls a filter. Returns `true` if the filter existed.
+    pub async fn remove(&self, id: &FilterId) -> bool {
+        self.filters.write().await.remove(id).is_some()
+    }
+
+    async fn add(&self, kind: RpcFilterKind) -> FilterId {
+        let filter = RpcFilter {
+            kind,
+            last_polled_at: Instant::now(),
+        };
+
+        let mut filters = self.filters.write().await;
+        let id = loop {
+            let id = FilterId::from(ethers_core::rand::random::<u128>());
+            if not(filters.contains_key(&id)) {
+                break id;
+            }
+        };
+        filters.insert(id, filter);
+        id
+    }
+}
+
+impl RpcFilter {
+    fn is_idle(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.last_polled_at) > IDLE_TIMEOUT
+    }
+}
+
+/// Adds a change to the changes accumulated by a filter, dropping the oldest change if the filter is full.
+fn push_bounded<T>(pending: &mut VecDeque<T>, change: T) {
+    if pending.len() >= MAX_PENDING_CHANGES {
+        pending.pop_front();
+    }
+    pending.push_back(change);
+}
+
+// -----------------------------------------------------------------------------
+// Tests
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use fake::Fake;
+    use fake::Faker;
+
+    use super::*;
+    use crate::eth::primitives::BlockNumber;
+
+    fn log_filter(address: &LogMined) -> LogFilter {
+        LogFilter {
+            from_block: BlockNumber::ZERO,
+            to_block: None,
+            addresses: vec![address.address().clone()],
+            topics_combinations: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn changes_are_taken_once_since_last_poll() {
+        let filters = RpcFilters::default();
+        let block_id = filters.add_new_blocks().await;
+
+        let block1: Block = Faker.fake();
+        let block2: Block = Faker.fake();
+        filters.collect_block(&block1).await;
+        filters.collect_block(&block2).await;
+
+        // first poll returns all blocks in order
+        let Some(RpcFilterChanges::NewBlocks(hashes)) = filters.take_changes(&block_id).await else {
+            panic!("expected new blocks changes");
+        };
+        assert_eq!(hashes, vec![block1.hash().clone(), block2.hash().clone()]);
+
+        // second poll returns only blocks after the first poll
+        let block3: Block = Faker.fake();
+        filters.collect_block(&block3).await;
+        let Some(RpcFilterChanges::NewBlocks(hashes)) = filters.take_changes(&block_id).await else {
+            panic!("expected new blocks changes");
+        };
+        assert_eq!(hashes, vec![block3.hash().clone()]);
+    }
+
+    #[tokio::test]
+    async fn logs_are_collected_only_by_matching_filters() {
+        let filters = RpcFilters::default();
+        let matching_log: LogMined = Faker.fake();
+        let other_log: LogMined = Faker.fake();
+        let logs_id = filters.add_logs(log_filter(&matching_log)).await;
+
+        filters.collect_log(&matching_log).await;
+        filters.collect_log(&other_log).await;
+
+        let Some(RpcFilterChanges::Logs(logs)) = filters.take_changes(&logs_id).await else {
+            panic!("expected logs changes");
+        };
+        assert_eq!(logs, vec![matching_log]);
+    }
+
+    #[tokio::test]
+    async fn pending_changes_are_bounded() {
+        let filters = RpcFilters::default();
+        let id = filters.add_new_blocks().await;
+
+        let first: Block = Faker.fake();
+        let other: Block = Faker.fake();
+        filters.collect_block(&first).await;
+        for _ in 0..MAX_PENDING_CHANGES {
+            filters.collect_block(&other).await;
+        }
+
+        let Some(RpcFilterChanges::NewBlocks(hashes)) = filters.take_changes(&id).await else {
+            panic!("expected new blocks changes");
+        };
+        assert_eq!(hashes.len(), MAX_PENDING_CHANGES);
+        assert!(not(hashes.contains(first.hash())));
+    }
+
+    #[tokio::test]
+    async fn idle_filters_are_removed() {
+        let filters = RpcFilters::default();
+        let idle_id = filters.add_new_blocks().await;
+        let polled_id = filters.add_new_blocks().await;
+        assert_ne!(idle_id, polled_id);
+
+        // filters are kept before the timeout
+        filters.remove_idle(Instant::now()).await;
+        assert!(filters.take_changes(&idle_id).await.is_some());
+
+        // only the filter that was not polled is removed after the timeout
+        let later = Instant::now() + IDLE_TIMEOUT;
+        filters.filters.write().await.get_mut(&polled_id).unwrap().last_polled_at = later;
+        filters.remove_idle(later + Duration::from_secs(1)).await;
+        assert!(filters.take_changes(&idle_id).await.is_none());
+        assert!(filters.take_changes(&polled_id).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn removed_filters_have_no_changes() {
+        let filters = RpcFilters::default();
+        let id = filters.add_new_blocks().await;
+        assert!(filters.remove(&id).await);
+        assert!(not(filters.remove(&id).await));
+        assert!(filters.take_changes(&id).await.is_none());
+    }
+}
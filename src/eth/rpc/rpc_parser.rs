@@ -1,6 +1,9 @@
 //! Helper functions for parsing RPC requests and responses.
 
 use anyhow::anyhow;
+use ethereum_types::U64;
+use ethers_core::abi;
+use ethers_core::abi::ParamType;
 use jsonrpsee::types::error::INTERNAL_ERROR_CODE;
 use jsonrpsee::types::error::INTERNAL_ERROR_MSG;
 use jsonrpsee::types::error::PARSE_ERROR_CODE;
@@ -9,6 +12,42 @@ use jsonrpsee::types::ErrorObjectOwned;
 use jsonrpsee::types::ParamsSequence;
 use rlp::Decodable;
 
+use crate::eth::primitives::Bytes;
+
+/// Error code returned when a transaction is rejected by validation, the same used by geth.
+const INVALID_TRANSACTION_ERROR_CODE: i32 = -32000;
+
+/// Error code returned when a transaction is rejected because it kept conflicting with concurrent transactions.
+///
+/// Clients should retry later with backoff, as the transaction was not executed.
+const CONFLICT_ERROR_CODE: i32 = -32005;
+
+/// Error message returned when a transaction is rejected because it kept conflicting with concurrent transactions.
+const CONFLICT_ERROR_MSG: &str = "Transaction conflicted with concurrent transactions";
+
+/// Error code returned when an execution is reverted, the same used by geth.
+const EXECUTION_REVERTED_ERROR_CODE: i32 = 3;
+
+/// Selector of `Error(string)`, the revert reason emitted by `require` and `revert` in Solidity.
+const REVERT_REASON_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// Numeric RPC parameter that some clients send as a JSON number and others as a hex quantity.
+#[derive(Debug, Clone, Copy, serde::Deserialize)]
+#[serde(untagged)]
+pub enum RpcQuantity {
+    Number(u64),
+    Hex(U64),
+}
+
+impl From<RpcQuantity> for u64 {
+    fn from(value: RpcQuantity) -> Self {
+        match value {
+            RpcQuantity::Number(value) => value,
+            RpcQuantity::Hex(value) => value.as_u64(),
+        }
+    }
+}
+
 /// Extracts the next RPC parameter. Fails if parameter not present.
 pub fn next_rpc_param<'a, T: serde::Deserialize<'a>>(mut params: ParamsSequence<'a>) -> anyhow::Result<(ParamsSequence, T)> {
     match params.next::<T>() {
@@ -52,3 +91,32 @@ pub fn rpc_parsing_error<S: serde::Serialize>(message: S) -> ErrorObjectOwned {
 pub fn rpc_internal_error<S: serde::Serialize>(message: S) -> ErrorObjectOwned {
     ErrorObjectOwned::owned(INTERNAL_ERROR_CODE, INTERNAL_ERROR_MSG, Some(message))
 }
+
+/// Creates an RPC error response for transactions rejected because of conflicts with concurrent transactions.
+pub fn rpc_conflict_error<S: serde::Serialize>(message: S) -> ErrorObjectOwned {
+    ErrorObjectOwned::owned(CONFLICT_ERROR_CODE, CONFLICT_ERROR_MSG, Some(message))
+}
+
+/// Creates an RPC error response for transactions rejected by validation.
+///
+/// The message is the validation failure itself, as clients match on it.
+pub fn rpc_invalid_transaction_error(message: String) -> ErrorObjectOwned {
+    ErrorObjectOwned::owned::<()>(INVALID_TRANSACTION_ERROR_CODE, message, None)
+}
+
+/// Creates an RPC error response for reverted executions, with the revert data as error data.
+///
+/// The message includes the revert reason when the contract reverted with a string.
+pub fn rpc_execution_reverted_error(output: Bytes) -> ErrorObjectOwned {
+    let message = match decode_revert_reason(&output) {
+        Some(reason) => format!("execution reverted: {}", reason),
+        None => "execution reverted".to_owned(),
+    };
+    ErrorObjectOwned::owned(EXECUTION_REVERTED_ERROR_CODE, message, Some(output))
+}
+
+/// Decodes the string a contract reverted with, if it reverted with one.
+fn decode_revert_reason(output: &[u8]) -> Option<String> {
+    let data = output.strip_prefix(REVERT_REASON_SELECTOR.as_slice())?;
+    abi::decode(&[ParamType::String], data).ok()?.pop()?.into_string()
+}
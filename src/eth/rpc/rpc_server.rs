@@ -1,10 +1,14 @@
 //! RPC server for HTTP and WS.
 
-use std::net::SocketAddr;
 use std::ops::Deref;
 use std::sync::Arc;
 
+use anyhow::anyhow;
 use ethereum_types::U256;
+use ethers_core::types::BlockTrace as EthersBlockTrace;
+use ethers_core::types::EIP1186ProofResponse as EthersProof;
+use ethers_core::types::FeeHistory as EthersFeeHistory;
+use ethers_core::types::TraceType as EthersTraceType;
 use jsonrpsee::server::middleware::http::ProxyGetRequestLayer;
 use jsonrpsee::server::RandomStringIdProvider;
 use jsonrpsee::server::RpcModule;
@@ -13,9 +17,11 @@ use jsonrpsee::server::Server;
 use jsonrpsee::types::Params;
 use jsonrpsee::IntoSubscriptionCloseResponse;
 use jsonrpsee::PendingSubscriptionSink;
+use serde_json::json;
 use serde_json::Value as JsonValue;
 use tokio::sync::broadcast;
 
+use crate::config::Config;
 use crate::eth::primitives::Address;
 use crate::eth::primitives::BlockNumber;
 use crate::eth::primitives::BlockSelection;
@@ -23,20 +29,35 @@ use crate::eth::primitives::Bytes;
 use crate::eth::primitives::CallInput;
 use crate::eth::primitives::Hash;
 use crate::eth::primitives::LogFilterInput;
+use crate::eth::primitives::Nonce;
+use crate::eth::primitives::Slot;
 use crate::eth::primitives::SlotIndex;
+use crate::eth::primitives::SlotValue;
 use crate::eth::primitives::StoragePointInTime;
+use crate::eth::primitives::TraceFilterInput;
+use crate::eth::primitives::Tracer;
+use crate::eth::primitives::TracerInput;
 use crate::eth::primitives::TransactionInput;
+use crate::eth::primitives::Wei;
 use crate::eth::rpc::next_rpc_param;
 use crate::eth::rpc::next_rpc_param_or_default;
 use crate::eth::rpc::parse_rpc_rlp;
 use crate::eth::rpc::rpc_internal_error;
 use crate::eth::rpc::rpc_parsing_error;
+use crate::eth::rpc::FilterId;
 use crate::eth::rpc::RpcContext;
 use crate::eth::rpc::RpcError;
+use crate::eth::rpc::RpcFilterChanges;
+use crate::eth::rpc::RpcFilters;
 use crate::eth::rpc::RpcMiddleware;
+use crate::eth::rpc::RpcQuantity;
 use crate::eth::rpc::RpcSubscriptions;
 use crate::eth::storage::EthStorage;
+use crate::eth::AccountOverride;
 use crate::eth::EthExecutor;
+use crate::eth::EthExecutorError;
+use crate::ext::not;
+use crate::if_else;
 
 // -----------------------------------------------------------------------------
 // Server
@@ -44,22 +65,33 @@ use crate::eth::EthExecutor;
 
 /// Starts JSON-RPC server.
 pub async fn serve_rpc(
-    executor: EthExecutor,
+    executor: Arc<EthExecutor>,
     eth_storage: Arc<dyn EthStorage>,
-    address: SocketAddr,
+    config: Arc<Config>,
     mut cancel_signal: broadcast::Receiver<()>,
 ) -> anyhow::Result<()> {
+    let address = config.address;
+
     // configure subscriptions
     let subs = Arc::new(RpcSubscriptions::default());
     Arc::clone(&subs).spawn_subscriptions_cleaner();
     Arc::clone(&subs).spawn_logs_notifier(executor.subscribe_to_logs());
     Arc::clone(&subs).spawn_new_heads_notifier(executor.subscribe_to_new_heads());
 
+    // configure filters
+    let filters = Arc::new(RpcFilters::default());
+    Arc::clone(&filters).spawn_filters_cleaner();
+    Arc::clone(&filters).spawn_logs_collector(executor.subscribe_to_logs());
+    Arc::clone(&filters).spawn_new_heads_collector(executor.subscribe_to_new_heads());
+
     // configure context
     let ctx = RpcContext {
-        chain_id: 2008,
-        client_version: "stratus",
-        gas_price: 0,
+        chain_id: config.chain_id,
+        network_id: config.network_id.unwrap_or(config.chain_id),
+        client_version: config.client_version.clone(),
+
+        // limits
+        trace_filter_max_blocks: config.trace_filter_max_blocks,
 
         // services
         executor,
@@ -67,12 +99,21 @@ pub async fn serve_rpc(
 
         // subscriptions
         subs,
+
+        // filters
+        filters,
+
+        // development mode
+        dev: config.dev,
     };
     tracing::info!(%address, ?ctx, "starting rpc server");
 
     // configure module
     let mut module = RpcModule::<RpcContext>::new(ctx);
     module = register_methods(module)?;
+    if config.dev {
+        module = register_dev_methods(module)?;
+    }
 
     // configure middleware
     let rpc_middleware = RpcServiceBuilder::new().layer_fn(RpcMiddleware::new);
@@ -99,6 +140,15 @@ pub async fn serve_rpc(
 fn register_methods(mut module: RpcModule<RpcContext>) -> anyhow::Result<RpcModule<RpcContext>> {
     // debug
     module.register_async_method("debug_setHead", debug_set_head)?;
+    module.register_async_method("debug_traceTransaction", debug_trace_transaction)?;
+    module.register_async_method("debug_traceCall", debug_trace_call)?;
+    module.register_async_method("debug_traceBlockByNumber", debug_trace_block_by_number)?;
+
+    // trace
+    module.register_async_method("trace_transaction", trace_transaction)?;
+    module.register_async_method("trace_block", trace_block)?;
+    module.register_async_method("trace_replayTransaction", trace_replay_transaction)?;
+    module.register_async_method("trace_filter", trace_filter)?;
 
     // blockchain
     module.register_async_method("net_version", net_version)?;
@@ -108,6 +158,8 @@ fn register_methods(mut module: RpcModule<RpcContext>) -> anyhow::Result<RpcModu
 
     // gas
     module.register_async_method("eth_gasPrice", eth_gas_price)?;
+    module.register_async_method("eth_maxPriorityFeePerGas", eth_max_priority_fee_per_gas)?;
+    module.register_async_method("eth_feeHistory", eth_fee_history)?;
 
     // block
     module.register_async_method("eth_blockNumber", eth_block_number)?;
@@ -123,9 +175,21 @@ fn register_methods(mut module: RpcModule<RpcContext>) -> anyhow::Result<RpcModu
     module.register_async_method("eth_call", eth_call)?;
     module.register_async_method("eth_sendRawTransaction", eth_send_raw_transaction)?;
 
+    // txpool
+    module.register_async_method("txpool_status", txpool_status)?;
+    module.register_async_method("txpool_content", txpool_content)?;
+    module.register_async_method("txpool_inspect", txpool_inspect)?;
+
     // logs
     module.register_async_method("eth_getLogs", eth_get_logs)?;
 
+    // filters
+    module.register_async_method("eth_newFilter", eth_new_filter)?;
+    module.register_async_method("eth_newBlockFilter", eth_new_block_filter)?;
+    module.register_async_method("eth_getFilterChanges", eth_get_filter_changes)?;
+    module.register_async_method("eth_getFilterLogs", eth_get_filter_logs)?;
+    module.register_async_method("eth_uninstallFilter", eth_uninstall_filter)?;
+
     // account
     module.register_async_method("eth_getBalance", eth_get_balance)?;
     module.register_async_method("eth_getCode", eth_get_code)?;
@@ -135,6 +199,30 @@ fn register_methods(mut module: RpcModule<RpcContext>) -> anyhow::Result<RpcModu
 
     // storage
     module.register_async_method("eth_getStorageAt", eth_get_storage_at)?;
+    module.register_async_method("eth_getProof", eth_get_proof)?;
+
+    Ok(module)
+}
+
+/// Registers methods that manipulate the chain state for test automation. Must be used only in development mode.
+fn register_dev_methods(mut module: RpcModule<RpcContext>) -> anyhow::Result<RpcModule<RpcContext>> {
+    // evm
+    module.register_async_method("evm_snapshot", evm_snapshot)?;
+    module.register_async_method("evm_revert", evm_revert)?;
+    module.register_async_method("evm_mine", evm_mine)?;
+    module.register_async_method("evm_increaseTime", evm_increase_time)?;
+    module.register_async_method("evm_setNextBlockTimestamp", evm_set_next_block_timestamp)?;
+
+    // hardhat
+    module.register_async_method("hardhat_setBalance", hardhat_set_balance)?;
+    module.register_async_method("hardhat_setCode", hardhat_set_code)?;
+    module.register_async_method("hardhat_setStorageAt", hardhat_set_storage_at)?;
+    module.register_async_method("hardhat_setNonce", hardhat_set_nonce)?;
+    module.register_async_method("hardhat_impersonateAccount", hardhat_impersonate_account)?;
+    module.register_async_method("hardhat_stopImpersonatingAccount", hardhat_stop_impersonating_account)?;
+
+    // transactions
+    module.register_async_method("eth_sendTransaction", eth_send_transaction)?;
 
     Ok(module)
 }
@@ -143,6 +231,12 @@ fn register_methods(mut module: RpcModule<RpcContext>) -> anyhow::Result<RpcModu
 // Handlers
 // -----------------------------------------------------------------------------
 
+/// Tracer used to collect the call frames rendered by `trace_*` methods.
+const PARITY_TRACER: Tracer = Tracer::CallTracer {
+    only_top_call: false,
+    with_log: false,
+};
+
 // Debug
 async fn debug_set_head(params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Result<JsonValue, RpcError> {
     let (_, number) = next_rpc_param::<BlockNumber>(params.sequence())?;
@@ -150,6 +244,256 @@ async fn debug_set_head(params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Res
     Ok(serde_json::to_value(number).unwrap())
 }
 
+async fn debug_trace_transaction(params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Result<JsonValue, RpcError> {
+    let (params, hash) = next_rpc_param::<Hash>(params.sequence())?;
+    let (_, tracer) = next_rpc_param_or_default::<TracerInput>(params)?;
+    let tracer = tracer.parse()?;
+
+    let Some(mined) = ctx.storage.read_mined_transaction(&hash).await? else {
+        return Err(anyhow!("Transaction {} not found.", hash).into());
+    };
+    let (execution, trace) = ctx.executor.trace_transaction(&mined, tracer.clone()).await?;
+    Ok(tracer.to_json_rpc_trace(&execution, &trace))
+}
+
+async fn debug_trace_call(params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Result<JsonValue, RpcError> {
+    let (params, call) = next_rpc_param::<CallInput>(params.sequence())?;
+    let (params, block_selection) = next_rpc_param_or_default::<BlockSelection>(params)?;
+    let (_, tracer) = next_rpc_param_or_default::<TracerInput>(params)?;
+    let tracer = tracer.parse()?;
+
+    let point_in_time = ctx.storage.translate_to_point_in_time(&block_selection).await?;
+    let (execution, trace) = ctx.executor.trace_call(call, point_in_time, tracer.clone()).await?;
+    Ok(tracer.to_json_rpc_trace(&execution, &trace))
+}
+
+async fn debug_trace_block_by_number(params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Result<JsonValue, RpcError> {
+    let (params, block_selection) = next_rpc_param::<BlockSelection>(params.sequence())?;
+    let (_, tracer) = next_rpc_param_or_default::<TracerInput>(params)?;
+    let tracer = tracer.parse()?;
+
+    let Some(block) = ctx.storage.read_block(&block_selection).await? else {
+        return Err(anyhow!("Block {:?} not found.", block_selection).into());
+    };
+
+    let traces = ctx.executor.trace_block(&block, tracer.clone()).await?;
+    let traces = block
+        .transactions
+        .iter()
+        .zip(traces)
+        .map(|(mined, (execution, trace))| json!({ "txHash": mined.input.hash, "result": tracer.to_json_rpc_trace(&execution, &trace) }))
+        .collect();
+    Ok(JsonValue::Array(traces))
+}
+
+// Trace
+async fn trace_transaction(params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Result<JsonValue, RpcError> {
+    let (_, hash) = next_rpc_param::<Hash>(params.sequence())?;
+
+    let Some(mined) = ctx.storage.read_mined_transaction(&hash).await? else {
+        return Ok(JsonValue::Null);
+    };
+    let (_, trace) = ctx.executor.trace_transaction(&mined, PARITY_TRACER).await?;
+    Ok(serde_json::to_value(trace.to_parity_traces(&mined)).unwrap())
+}
+
+async fn trace_block(params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Result<JsonValue, RpcError> {
+    let (_, block_selection) = next_rpc_param::<BlockSelection>(params.sequence())?;
+
+    let Some(block) = ctx.storage.read_block(&block_selection).await? else {
+        return Ok(JsonValue::Null);
+    };
+
+    let mut traces = Vec::new();
+    for (mined, (_, trace)) in block.transactions.iter().zip(ctx.executor.trace_block(&block, PARITY_TRACER).await?) {
+        traces.extend(trace.to_parity_traces(mined));
+    }
+    Ok(serde_json::to_value(traces).unwrap())
+}
+
+async fn trace_replay_transaction(params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Result<JsonValue, RpcError> {
+    let (params, hash) = next_rpc_param::<Hash>(params.sequence())?;
+    let (_, trace_types) = next_rpc_param_or_default::<Vec<EthersTraceType>>(params)?;
+    if trace_types.contains(&EthersTraceType::VmTrace) {
+        return Err(anyhow!("Trace type 'vmTrace' is not supported.").into());
+    }
+
+    let Some(mined) = ctx.storage.read_mined_transaction(&hash).await? else {
+        return Err(anyhow!("Transaction {} not found.", hash).into());
+    };
+    let (execution, trace) = ctx.executor.trace_transaction(&mined, PARITY_TRACER).await?;
+
+    let block_trace = EthersBlockTrace {
+        output: execution.output.clone().into(),
+        trace: if_else!(trace_types.contains(&EthersTraceType::Trace), Some(trace.to_parity_transaction_traces()), None),
+        vm_trace: None,
+        state_diff: if_else!(trace_types.contains(&EthersTraceType::StateDiff), Some(execution.to_parity_state_diff()), None),
+        transaction_hash: Some(hash.into()),
+    };
+    Ok(serde_json::to_value(block_trace).unwrap())
+}
+
+async fn trace_filter(params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Result<JsonValue, RpcError> {
+    let (_, filter) = next_rpc_param::<TraceFilterInput>(params.sequence())?;
+    let filter = filter.parse(&ctx.storage).await?;
+    if filter.block_count() > ctx.trace_filter_max_blocks {
+        return Err(anyhow!(
+            "Block range of {} blocks is greater than the maximum of {} blocks.",
+            filter.block_count(),
+            ctx.trace_filter_max_blocks
+        )
+        .into());
+    }
+
+    // traces before the offset are collected too, so stop only when the requested page is complete
+    let limit = filter.count.map(|count| filter.after + count);
+    let mut traces = Vec::new();
+
+    'blocks: for number in u64::from(filter.from_block)..=u64::from(filter.to_block) {
+        let Some(block) = ctx.storage.read_block(&BlockSelection::Number(number.into())).await? else {
+            break;
+        };
+        for (mined, (_, trace)) in block.transactions.iter().zip(ctx.executor.trace_block(&block, PARITY_TRACER).await?) {
+            for (frame, parity_trace) in trace.frames.iter().zip(trace.to_parity_traces(mined)) {
+                if not(filter.matches(frame)) {
+                    continue;
+                }
+                traces.push(parity_trace);
+                if limit.is_some_and(|limit| traces.len() >= limit) {
+                    break 'blocks;
+                }
+            }
+        }
+    }
+
+    let traces = traces.into_iter().skip(filter.after).collect::<Vec<_>>();
+    Ok(serde_json::to_value(traces).unwrap())
+}
+
+// Development
+async fn evm_snapshot(_params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Result<String, RpcError> {
+    let id = ctx.executor.snapshot().await?;
+    Ok(hex_num(id))
+}
+
+async fn evm_revert(params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Result<bool, RpcError> {
+    let (_, id) = next_rpc_param::<RpcQuantity>(params.sequence())?;
+    Ok(ctx.executor.revert(u64::from(id) as usize).await?)
+}
+
+async fn evm_mine(params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Result<String, RpcError> {
+    let (_, timestamp) = next_rpc_param_or_default::<Option<RpcQuantity>>(params.sequence())?;
+    ctx.executor.mine_empty_block(timestamp.map(u64::from)).await?;
+    Ok(hex_zero())
+}
+
+async fn evm_increase_time(params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Result<String, RpcError> {
+    let (_, secs) = next_rpc_param::<RpcQuantity>(params.sequence())?;
+    let offset = ctx.executor.increase_time(secs.into());
+    Ok(offset.to_string())
+}
+
+async fn evm_set_next_block_timestamp(params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Result<String, RpcError> {
+    let (_, timestamp) = next_rpc_param::<RpcQuantity>(params.sequence())?;
+    let timestamp = u64::from(timestamp);
+    ctx.executor.set_next_block_timestamp(timestamp).await;
+    Ok(timestamp.to_string())
+}
+
+async fn hardhat_set_balance(params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Result<bool, RpcError> {
+    let (params, address) = next_rpc_param::<Address>(params.sequence())?;
+    let (_, balance) = next_rpc_param::<Wei>(params)?;
+
+    let account_override = AccountOverride {
+        balance: Some(balance),
+        ..AccountOverride::default()
+    };
+    ctx.executor.override_account(&address, account_override).await?;
+    Ok(true)
+}
+
+async fn hardhat_set_code(params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Result<bool, RpcError> {
+    let (params, address) = next_rpc_param::<Address>(params.sequence())?;
+    let (_, bytecode) = next_rpc_param::<Bytes>(params)?;
+
+    let account_override = AccountOverride {
+        bytecode: Some(if_else!(bytecode.is_empty(), None, Some(bytecode))),
+        ..AccountOverride::default()
+    };
+    ctx.executor.override_account(&address, account_override).await?;
+    Ok(true)
+}
+
+async fn hardhat_set_storage_at(params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Result<bool, RpcError> {
+    let (params, address) = next_rpc_param::<Address>(params.sequence())?;
+    let (params, index) = next_rpc_param::<SlotIndex>(params)?;
+    let (_, value) = next_rpc_param::<SlotValue>(params)?;
+
+    let account_override = AccountOverride {
+        slots: vec![Slot::new(index, value)],
+        ..AccountOverride::default()
+    };
+    ctx.executor.override_account(&address, account_override).await?;
+    Ok(true)
+}
+
+async fn hardhat_set_nonce(params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Result<bool, RpcError> {
+    let (params, address) = next_rpc_param::<Address>(params.sequence())?;
+    let (_, nonce) = next_rpc_param::<Nonce>(params)?;
+
+    let account_override = AccountOverride {
+        nonce: Some(nonce),
+        ..AccountOverride::default()
+    };
+    ctx.executor.override_account(&address, account_override).await?;
+    Ok(true)
+}
+
+async fn hardhat_impersonate_account(params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Result<bool, RpcError> {
+    let (_, address) = next_rpc_param::<Address>(params.sequence())?;
+    ctx.executor.impersonate_account(address).await;
+    Ok(true)
+}
+
+async fn hardhat_stop_impersonating_account(params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Result<bool, RpcError> {
+    let (_, address) = next_rpc_param::<Address>(params.sequence())?;
+    Ok(ctx.executor.stop_impersonating_account(&address).await)
+}
+
+async fn eth_send_transaction(params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Result<String, RpcError> {
+    let (_, call) = next_rpc_param::<CallInput>(params.sequence())?;
+
+    // gas and gas price sent by the caller are kept, defaulting to the transaction gas limit and to the base fee of the next block
+    let gas = call.gas.clone().unwrap_or_else(|| ctx.executor.transaction_gas_limit());
+    let gas_price = match call.gas_price.clone() {
+        Some(gas_price) => gas_price,
+        None => ctx.executor.next_base_fee().await?,
+    };
+
+    let nonce = ctx.executor.read_pending_nonce(&call.from).await?;
+    let mut transaction = TransactionInput::new_impersonated(ctx.chain_id.into(), nonce, call);
+    transaction.gas = gas;
+    transaction.gas_price = gas_price;
+    let transaction_hash = transaction.hash.clone();
+
+    match ctx.executor.submit_impersonated(transaction).await {
+        // result is queued
+        Ok(None) => Ok(hex_data(transaction_hash)),
+
+        // result is success
+        Ok(Some(result)) if result.is_success() => Ok(hex_data(transaction_hash)),
+
+        // result is failure
+        Ok(Some(result)) => Err(RpcError::Response(rpc_internal_error(hex_data(result.output)))),
+
+        // internal error
+        Err(e) => {
+            tracing::error!(reason = ?e, "failed to execute eth_sendTransaction");
+            Err(e.context("failed to execute eth_sendTransaction").into())
+        }
+    }
+}
+
 // Status
 async fn net_listening(_: Params<'_>, _: Arc<RpcContext>) -> &'static str {
     "true"
@@ -158,7 +502,7 @@ async fn net_listening(_: Params<'_>, _: Arc<RpcContext>) -> &'static str {
 // Blockchain
 
 async fn net_version(_: Params<'_>, ctx: Arc<RpcContext>) -> String {
-    ctx.chain_id.to_string()
+    ctx.network_id.to_string()
 }
 
 async fn eth_chain_id(_: Params<'_>, ctx: Arc<RpcContext>) -> String {
@@ -166,13 +510,41 @@ async fn eth_chain_id(_: Params<'_>, ctx: Arc<RpcContext>) -> String {
 }
 
 async fn web3_client_version(_: Params<'_>, ctx: Arc<RpcContext>) -> String {
-    ctx.client_version.to_owned()
+    ctx.client_version.clone()
 }
 
 // Gas
 
-async fn eth_gas_price(_: Params<'_>, _: Arc<RpcContext>) -> String {
-    hex_zero()
+async fn eth_gas_price(_: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Result<String, RpcError> {
+    let base_fee = ctx.executor.next_base_fee().await?;
+    let priority_fee = ctx.executor.suggest_priority_fee().await?;
+    Ok(hex_num(U256::from(base_fee).saturating_add(priority_fee.into())))
+}
+
+async fn eth_max_priority_fee_per_gas(_: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Result<String, RpcError> {
+    let priority_fee = ctx.executor.suggest_priority_fee().await?;
+    Ok(hex_num(priority_fee))
+}
+
+async fn eth_fee_history(params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Result<JsonValue, RpcError> {
+    let (params, block_count) = next_rpc_param::<RpcQuantity>(params.sequence())?;
+    let (params, block_selection) = next_rpc_param::<BlockSelection>(params)?;
+    let (_, reward_percentiles) = next_rpc_param_or_default::<Vec<f64>>(params)?;
+
+    // percentiles must be increasing values between 0 and 100
+    if reward_percentiles.iter().any(|percentile| not((0.0..=100.0).contains(percentile))) {
+        return Err(anyhow!("Reward percentiles must be between 0 and 100.").into());
+    }
+    if reward_percentiles.windows(2).any(|pair| pair[0] > pair[1]) {
+        return Err(anyhow!("Reward percentiles must be in ascending order.").into());
+    }
+
+    let newest = match ctx.storage.translate_to_point_in_time(&block_selection).await? {
+        StoragePointInTime::Present => ctx.storage.read_current_block_number().await?,
+        StoragePointInTime::Past(number) => number,
+    };
+    let history = ctx.executor.read_fee_history(block_count.into(), newest, &reward_percentiles).await?;
+    Ok(serde_json::to_value(EthersFeeHistory::from(history)).unwrap())
 }
 
 // Block
@@ -185,7 +557,11 @@ async fn eth_get_block_by_selector(params: Params<'_>, ctx: Arc<RpcContext>) ->
     let (params, block_selection) = next_rpc_param::<BlockSelection>(params.sequence())?;
     let (_, full_transactions) = next_rpc_param::<bool>(params)?;
 
-    let block = ctx.storage.read_block(&block_selection).await?;
+    // pending block only has the header of the next block, without the transactions waiting in the pool
+    let block = match block_selection {
+        BlockSelection::Pending => Some(ctx.executor.read_pending_block().await?),
+        block_selection => ctx.storage.read_block(&block_selection).await?,
+    };
 
     match (block, full_transactions) {
         (Some(block), true) => Ok(block.to_json_rpc_with_full_transactions()),
@@ -204,6 +580,11 @@ async fn eth_get_transaction_count(params: Params<'_>, ctx: Arc<RpcContext>) ->
     let (params, address) = next_rpc_param::<Address>(params.sequence())?;
     let (_, block_selection) = next_rpc_param_or_default::<BlockSelection>(params)?;
 
+    if block_selection == BlockSelection::Pending {
+        let nonce = ctx.executor.read_pending_nonce(&address).await?;
+        return Ok(hex_num(nonce));
+    }
+
     let point_in_time = ctx.storage.translate_to_point_in_time(&block_selection).await?;
     let account = ctx.storage.read_account(&address, &point_in_time).await?;
     Ok(hex_num(account.nonce))
@@ -215,7 +596,10 @@ async fn eth_get_transaction_by_hash(params: Params<'_>, ctx: Arc<RpcContext>) -
 
     match mined {
         Some(mined) => Ok(mined.to_json_rpc_transaction()),
-        None => Ok(JsonValue::Null),
+        None => match ctx.executor.read_pool_transaction(&hash).await {
+            Some(transaction) => Ok(transaction.to_json_rpc_transaction()),
+            None => Ok(JsonValue::Null),
+        },
     }
 }
 
@@ -228,14 +612,18 @@ async fn eth_get_transaction_receipt(params: Params<'_>, ctx: Arc<RpcContext>) -
 }
 
 async fn eth_estimate_gas(params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Result<String, RpcError> {
-    let (_, call) = next_rpc_param::<CallInput>(params.sequence())?;
+    let (params, call) = next_rpc_param::<CallInput>(params.sequence())?;
+    let (_, block_selection) = next_rpc_param_or_default::<BlockSelection>(params)?;
 
-    match ctx.executor.call(call, StoragePointInTime::Present).await {
-        // result is success
-        Ok(result) if result.is_success() => Ok(hex_num(result.gas)),
+    let point_in_time = ctx.storage.translate_to_point_in_time(&block_selection).await?;
+    match ctx.executor.estimate_gas(call, point_in_time).await {
+        Ok(gas) => Ok(hex_num(gas)),
 
-        // result is failure
-        Ok(result) => Err(RpcError::Response(rpc_internal_error(hex_data(result.output)))),
+        // failure, expected when the call reverts or cannot afford the gas it needs
+        Err(e) if e.is::<EthExecutorError>() => {
+            tracing::warn!(reason = ?e, "failed to estimate gas in eth_estimateGas");
+            Err(e.into())
+        }
 
         // internal error
         Err(e) => {
@@ -267,12 +655,15 @@ async fn eth_send_raw_transaction(params: Params<'_>, ctx: Arc<RpcContext>) -> a
     let transaction = parse_rpc_rlp::<TransactionInput>(&data)?;
 
     let hash = transaction.hash.clone();
-    match ctx.executor.transact(transaction).await {
+    match ctx.executor.submit(transaction).await {
+        // result is queued
+        Ok(None) => Ok(hex_data(hash)),
+
         // result is success
-        Ok(result) if result.is_success() => Ok(hex_data(hash)),
+        Ok(Some(result)) if result.is_success() => Ok(hex_data(hash)),
 
         // result is failure
-        Ok(result) => Err(RpcError::Response(rpc_internal_error(hex_data(result.output)))),
+        Ok(Some(result)) => Err(RpcError::Response(rpc_internal_error(hex_data(result.output)))),
 
         // internal error
         Err(e) => {
@@ -282,6 +673,22 @@ async fn eth_send_raw_transaction(params: Params<'_>, ctx: Arc<RpcContext>) -> a
     }
 }
 
+// Txpool
+async fn txpool_status(_params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Result<JsonValue, RpcError> {
+    let txpool = ctx.executor.read_pool().await;
+    Ok(serde_json::to_value(txpool.to_txpool_status()).unwrap())
+}
+
+async fn txpool_content(_params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Result<JsonValue, RpcError> {
+    let txpool = ctx.executor.read_pool().await;
+    Ok(serde_json::to_value(txpool.to_txpool_content()).unwrap())
+}
+
+async fn txpool_inspect(_params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Result<JsonValue, RpcError> {
+    let txpool = ctx.executor.read_pool().await;
+    Ok(serde_json::to_value(txpool.to_txpool_inspect()).unwrap())
+}
+
 // Logs
 async fn eth_get_logs(params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Result<JsonValue, RpcError> {
     let (_, filter_input) = next_rpc_param::<LogFilterInput>(params.sequence())?;
@@ -291,6 +698,45 @@ async fn eth_get_logs(params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Resul
     Ok(JsonValue::Array(logs.into_iter().map(|x| x.to_json_rpc_log()).collect()))
 }
 
+// Filters
+async fn eth_new_filter(params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Result<String, RpcError> {
+    let (_, filter_input) = next_rpc_param::<LogFilterInput>(params.sequence())?;
+    let filter = filter_input.parse(&ctx.storage).await?;
+
+    let id = ctx.filters.add_logs(filter).await;
+    Ok(hex_num(id))
+}
+
+async fn eth_new_block_filter(_params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Result<String, RpcError> {
+    let id = ctx.filters.add_new_blocks().await;
+    Ok(hex_num(id))
+}
+
+async fn eth_get_filter_changes(params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Result<JsonValue, RpcError> {
+    let (_, id) = next_rpc_param::<FilterId>(params.sequence())?;
+
+    match ctx.filters.take_changes(&id).await {
+        Some(RpcFilterChanges::Logs(logs)) => Ok(JsonValue::Array(logs.into_iter().map(|x| x.to_json_rpc_log()).collect())),
+        Some(RpcFilterChanges::NewBlocks(hashes)) => Ok(serde_json::to_value(hashes).unwrap()),
+        None => Err(anyhow!("Filter {} not found.", hex_num(id)).into()),
+    }
+}
+
+async fn eth_get_filter_logs(params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Result<JsonValue, RpcError> {
+    let (_, id) = next_rpc_param::<FilterId>(params.sequence())?;
+
+    let Some(filter) = ctx.filters.read_log_filter(&id).await else {
+        return Err(anyhow!("Filter {} not found.", hex_num(id)).into());
+    };
+    let logs = ctx.storage.read_logs(&filter).await?;
+    Ok(JsonValue::Array(logs.into_iter().map(|x| x.to_json_rpc_log()).collect()))
+}
+
+async fn eth_uninstall_filter(params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Result<bool, RpcError> {
+    let (_, id) = next_rpc_param::<FilterId>(params.sequence())?;
+    Ok(ctx.filters.remove(&id).await)
+}
+
 // Account
 
 async fn eth_get_balance(params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Result<String, RpcError> {
@@ -352,6 +798,17 @@ async fn eth_get_storage_at(params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow:
     Ok(hex_num_zero_padded(slot.value.as_u256()))
 }
 
+async fn eth_get_proof(params: Params<'_>, ctx: Arc<RpcContext>) -> anyhow::Result<JsonValue, RpcError> {
+    let (params, address) = next_rpc_param::<Address>(params.sequence())?;
+    let (params, indexes) = next_rpc_param::<Vec<SlotIndex>>(params)?;
+    let (_, block_selection) = next_rpc_param_or_default::<BlockSelection>(params)?;
+
+    let point_in_time = ctx.storage.translate_to_point_in_time(&block_selection).await?;
+    let proof = ctx.storage.read_proof(&address, &indexes, &point_in_time).await?;
+
+    Ok(serde_json::to_value(EthersProof::from(proof)).unwrap())
+}
+
 // -----------------------------------------------------------------------------
 // Helpers
 // -----------------------------------------------------------------------------
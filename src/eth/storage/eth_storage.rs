@@ -3,11 +3,14 @@ use async_trait::async_trait;
 
 use super::EthStorageError;
 use crate::eth::primitives::Account;
+use crate::eth::primitives::AccountProof;
 use crate::eth::primitives::Address;
 use crate::eth::primitives::Block;
 use crate::eth::primitives::BlockNumber;
 use crate::eth::primitives::BlockSelection;
+use crate::eth::primitives::Bytes;
 use crate::eth::primitives::Execution;
+use crate::eth::primitives::ExecutionAccountChanges;
 use crate::eth::primitives::ExecutionConflicts;
 use crate::eth::primitives::Hash;
 use crate::eth::primitives::LogFilter;
@@ -46,9 +49,18 @@ pub trait EthStorage: Send + Sync {
     /// Retrieves an slot from the storage.
     async fn read_slot(&self, address: &Address, slot: &SlotIndex, point_in_time: &StoragePointInTime) -> anyhow::Result<Slot>;
 
+    /// Retrieves a contract bytecode by its hash from the bytecodes deployed so far.
+    async fn read_bytecode(&self, code_hash: &Hash) -> anyhow::Result<Option<Bytes>>;
+
+    /// Retrieves the proof of an account and some of its slots against the state root at the given point in time.
+    async fn read_proof(&self, address: &Address, slots: &[SlotIndex], point_in_time: &StoragePointInTime) -> anyhow::Result<AccountProof>;
+
     /// Retrieves a block from the storage.
     async fn read_block(&self, block_selection: &BlockSelection) -> anyhow::Result<Option<Block>>;
 
+    /// Retrieves the hash of a mined block without reading its transactions.
+    async fn read_block_hash(&self, number: &BlockNumber) -> anyhow::Result<Option<Hash>>;
+
     /// Retrieves a transaction from the storage.
     async fn read_mined_transaction(&self, hash: &Hash) -> anyhow::Result<Option<TransactionMined>>;
 
@@ -56,7 +68,15 @@ pub trait EthStorage: Send + Sync {
     async fn read_logs(&self, filter: &LogFilter) -> anyhow::Result<Vec<LogMined>>;
 
     /// Persist atomically all changes from a block.
-    async fn save_block(&self, block: Block) -> anyhow::Result<(), EthStorageError>;
+    ///
+    /// Returns the block as it was saved, because the storage may fill header fields that depend on the state, like the state root.
+    async fn save_block(&self, block: Block) -> anyhow::Result<Block, EthStorageError>;
+
+    /// Persist atomically all changes from a block together with changes that override the state of some accounts without executing a transaction.
+    ///
+    /// The overrides are applied after the changes of the transactions and before the state root and the block hash are calculated.
+    /// Used only by development methods, which record the changes in an empty block mined for them.
+    async fn save_block_with_overrides(&self, block: Block, overrides: Vec<ExecutionAccountChanges>) -> anyhow::Result<Block, EthStorageError>;
 
     /// Resets all state to a specific block number.
     async fn reset(&self, number: BlockNumber) -> anyhow::Result<()>;
@@ -76,7 +96,7 @@ pub trait EthStorage: Send + Sync {
     /// Translates a block selection to a specific storage point-in-time indicator.
     async fn translate_to_point_in_time(&self, block_selection: &BlockSelection) -> anyhow::Result<StoragePointInTime> {
         match block_selection {
-            BlockSelection::Latest => Ok(StoragePointInTime::Present),
+            BlockSelection::Latest | BlockSelection::Pending => Ok(StoragePointInTime::Present),
             BlockSelection::Number(number) => {
                 let current_block = self.read_current_block_number().await?;
                 if number <= &current_block {
@@ -95,9 +115,7 @@ pub trait EthStorage: Send + Sync {
     }
 }
 
-/// Retrieves test accounts.
-///
-/// TODO: use a feature-flag to determine if test accounts should be returned.
+/// Retrieves test accounts, funded at genesis only when test accounts are enabled.
 pub fn test_accounts() -> Vec<Account> {
     use hex_literal::hex;
 
@@ -1,10 +1,12 @@
 //! In-memory storage implementations.
 
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use ethers_core::utils::keccak256;
 use indexmap::IndexMap;
 use metrics::atomics::AtomicU64;
 use tokio::sync::RwLock;
@@ -14,24 +16,32 @@ use tokio::sync::RwLockWriteGuard;
 use super::InMemoryAccount;
 use crate::eth::miner::BlockMiner;
 use crate::eth::primitives::Account;
+use crate::eth::primitives::AccountProof;
 use crate::eth::primitives::Address;
 use crate::eth::primitives::Block;
 use crate::eth::primitives::BlockNumber;
 use crate::eth::primitives::BlockSelection;
+use crate::eth::primitives::Bytes;
 use crate::eth::primitives::Execution;
+use crate::eth::primitives::ExecutionAccountChanges;
 use crate::eth::primitives::ExecutionConflicts;
 use crate::eth::primitives::ExecutionConflictsBuilder;
+use crate::eth::primitives::Genesis;
 use crate::eth::primitives::Hash;
 use crate::eth::primitives::LogFilter;
 use crate::eth::primitives::LogMined;
 use crate::eth::primitives::Slot;
 use crate::eth::primitives::SlotIndex;
+use crate::eth::primitives::SlotProof;
+use crate::eth::primitives::SlotValue;
+use crate::eth::primitives::StateOverlay;
 use crate::eth::primitives::StoragePointInTime;
 use crate::eth::primitives::TransactionMined;
 use crate::eth::storage::inmemory::InMemoryHistory;
-use crate::eth::storage::test_accounts;
+use crate::eth::storage::inmemory::InMemoryTrie;
 use crate::eth::storage::EthStorage;
 use crate::eth::storage::EthStorageError;
+use crate::if_else;
 
 /// In-memory implementation using maps.
 #[derive(Debug)]
@@ -55,27 +65,35 @@ impl InMemoryStorage {
 #[derive(Debug, Default)]
 struct InMemoryStorageState {
     accounts: HashMap<Address, InMemoryAccount>,
+    bytecodes: HashMap<Hash, Bytes>,
     transactions: HashMap<Hash, TransactionMined>,
     blocks_by_number: IndexMap<BlockNumber, Arc<Block>>,
     blocks_by_hash: IndexMap<Hash, Arc<Block>>,
     logs: Vec<LogMined>,
+    trie: InMemoryTrie,
 }
 
-impl Default for InMemoryStorage {
-    fn default() -> Self {
+impl InMemoryStorage {
+    /// Creates a new storage seeded with the genesis block and accounts.
+    pub fn new(genesis: &Genesis) -> Self {
         let mut state = InMemoryStorageState::default();
 
-        // add genesis block to state
-        let genesis = Arc::new(BlockMiner::genesis());
-        state.blocks_by_number.insert(*genesis.number(), Arc::clone(&genesis));
-        state.blocks_by_hash.insert(genesis.hash().clone(), Arc::clone(&genesis));
-
-        // add test accounts to state
-        for account in test_accounts() {
-            state
-                .accounts
-                .insert(account.address.clone(), InMemoryAccount::new_with_balance(account.address, account.balance));
+        // add genesis accounts to state
+        for (account, slots) in genesis.accounts.iter().cloned() {
+            if let Some(ref bytecode) = account.bytecode {
+                state.index_bytecode(bytecode);
+            }
+            state.accounts.insert(account.address.clone(), InMemoryAccount::new_with_state(account, slots));
         }
+        state.trie = InMemoryTrie::new(state.accounts.values(), &StoragePointInTime::Present);
+
+        // add genesis block to state
+        let mut genesis_block = BlockMiner::genesis(genesis);
+        genesis_block.header.state_root = state.trie.root();
+        genesis_block.update_hash();
+        let genesis_block = Arc::new(genesis_block);
+        state.blocks_by_number.insert(*genesis_block.number(), Arc::clone(&genesis_block));
+        state.blocks_by_hash.insert(genesis_block.hash().clone(), Arc::clone(&genesis_block));
 
         Self {
             state: RwLock::new(state),
@@ -84,6 +102,76 @@ impl Default for InMemoryStorage {
     }
 }
 
+impl InMemoryStorageState {
+    /// Indexes a deployed bytecode by its hash, so it can be found without knowing the contract address.
+    fn index_bytecode(&mut self, bytecode: &Bytes) {
+        self.bytecodes.insert(Hash::new(keccak256(bytecode)), bytecode.clone());
+    }
+
+    /// Records the changes of an account at a block number, collecting the slots it modified.
+    ///
+    /// Changes of failed executions only modify the nonce and the balance.
+    fn apply_changes(
+        &mut self,
+        block_number: BlockNumber,
+        changes: ExecutionAccountChanges,
+        is_success: bool,
+        modified_slots: &mut HashMap<Address, HashSet<SlotIndex>>,
+    ) {
+        let bytecode = if_else!(is_success, changes.bytecode.take_modified(), None);
+        if let Some(Some(ref bytecode)) = bytecode {
+            self.index_bytecode(bytecode);
+        }
+
+        let account = self
+            .accounts
+            .entry(changes.address.clone())
+            .or_insert_with(|| InMemoryAccount::new(changes.address.clone()));
+        let account_modified_slots = modified_slots.entry(changes.address).or_default();
+
+        // nonce
+        if let Some(nonce) = changes.nonce.take_modified() {
+            account.set_nonce(block_number, nonce);
+        }
+
+        // balance
+        if let Some(balance) = changes.balance.take_modified() {
+            account.set_balance(block_number, balance);
+        }
+
+        // bytecode
+        // an override can also remove the bytecode of an account
+        match bytecode {
+            Some(Some(bytecode)) => account.set_bytecode(block_number, bytecode),
+            Some(None) => account.bytecode.push(block_number, None),
+            None => {}
+        }
+
+        // slots
+        if is_success {
+            for (slot_index, slot) in changes.slots {
+                if let Some(slot) = slot.take_modified() {
+                    match account.slots.get_mut(&slot_index) {
+                        Some(slot_history) => {
+                            slot_history.push(block_number, slot);
+                        }
+                        None => {
+                            account.slots.insert(slot_index.clone(), InMemoryHistory::new(block_number, slot));
+                        }
+                    };
+                    account_modified_slots.insert(slot_index);
+                }
+            }
+        }
+    }
+}
+
+impl Default for InMemoryStorage {
+    fn default() -> Self {
+        Self::new(&Genesis::default())
+    }
+}
+
 #[async_trait]
 impl EthStorage for InMemoryStorage {
     // -------------------------------------------------------------------------
@@ -105,7 +193,7 @@ impl EthStorage for InMemoryStorage {
 
     async fn check_conflicts(&self, execution: &Execution) -> anyhow::Result<Option<ExecutionConflicts>> {
         let state_lock = self.state.read().await;
-        Ok(check_conflicts(&state_lock, execution))
+        Ok(check_conflicts(&state_lock, &StateOverlay::default(), execution))
     }
 
     async fn read_account(&self, address: &Address, point_in_time: &StoragePointInTime) -> anyhow::Result<Account> {
@@ -116,12 +204,7 @@ impl EthStorage for InMemoryStorage {
         match state.accounts.get(address) {
             // account found
             Some(account) => {
-                let account = Account {
-                    address: address.clone(),
-                    balance: account.balance.get_at_point(point_in_time).unwrap_or_default(),
-                    nonce: account.nonce.get_at_point(point_in_time).unwrap_or_default(),
-                    bytecode: account.bytecode.get_at_point(point_in_time).unwrap_or_default(),
-                };
+                let account = account.get_at_point(point_in_time);
                 tracing::trace!(%address, ?account, "account found");
                 Ok(account)
             }
@@ -162,12 +245,61 @@ impl EthStorage for InMemoryStorage {
         }
     }
 
+    async fn read_bytecode(&self, code_hash: &Hash) -> anyhow::Result<Option<Bytes>> {
+        tracing::debug!(%code_hash, "reading bytecode");
+
+        let state = self.lock_read().await;
+        Ok(state.bytecodes.get(code_hash).cloned())
+    }
+
+    async fn read_proof(&self, address: &Address, slots: &[SlotIndex], point_in_time: &StoragePointInTime) -> anyhow::Result<AccountProof> {
+        tracing::debug!(%address, ?slots, ?point_in_time, "reading proof");
+
+        let state = self.lock_read().await;
+
+        // only the present trie is kept, past ones are rebuilt from the account history
+        let past_trie = match point_in_time {
+            StoragePointInTime::Present => None,
+            StoragePointInTime::Past(_) => Some(InMemoryTrie::new(state.accounts.values(), point_in_time)),
+        };
+        let trie = past_trie.as_ref().unwrap_or(&state.trie);
+
+        let account = state.accounts.get(address);
+        let slots = slots
+            .iter()
+            .map(|index| {
+                let slot = account
+                    .and_then(|account| account.slots.get(index))
+                    .and_then(|slot| slot.get_at_point(point_in_time))
+                    .unwrap_or_else(|| Slot::new(index.clone(), SlotValue::default()));
+                SlotProof {
+                    proof: trie.slot_proof(address, index),
+                    slot,
+                }
+            })
+            .collect();
+
+        Ok(AccountProof {
+            account: match account {
+                Some(account) => account.get_at_point(point_in_time),
+                None => Account {
+                    address: address.clone(),
+                    ..Account::default()
+                },
+            },
+            storage_root: trie.storage_root(address),
+            proof: trie.account_proof(address),
+            slots,
+        })
+    }
+
     async fn read_block(&self, selection: &BlockSelection) -> anyhow::Result<Option<Block>> {
         tracing::debug!(?selection, "reading block");
 
         let state_lock = self.lock_read().await;
         let block = match selection {
-            BlockSelection::Latest => state_lock.blocks_by_number.values().last().cloned(),
+            // storage only knows mined blocks, so pending is the latest one
+            BlockSelection::Latest | BlockSelection::Pending => state_lock.blocks_by_number.values().last().cloned(),
             BlockSelection::Earliest => state_lock.blocks_by_number.values().next().cloned(),
             BlockSelection::Number(number) => state_lock.blocks_by_number.get(number).cloned(),
             BlockSelection::Hash(hash) => state_lock.blocks_by_hash.get(hash).cloned(),
@@ -184,6 +316,13 @@ impl EthStorage for InMemoryStorage {
         }
     }
 
+    async fn read_block_hash(&self, number: &BlockNumber) -> anyhow::Result<Option<Hash>> {
+        tracing::debug!(%number, "reading block hash");
+
+        let state_lock = self.lock_read().await;
+        Ok(state_lock.blocks_by_number.get(number).map(|block| block.hash().clone()))
+    }
+
     async fn read_mined_transaction(&self, hash: &Hash) -> anyhow::Result<Option<TransactionMined>> {
         tracing::debug!(%hash, "reading transaction");
         let state_lock = self.lock_read().await;
@@ -218,16 +357,46 @@ impl EthStorage for InMemoryStorage {
         Ok(logs)
     }
 
-    async fn save_block(&self, block: Block) -> anyhow::Result<(), EthStorageError> {
-        let mut state = self.lock_write().await;
+    async fn save_block(&self, block: Block) -> anyhow::Result<Block, EthStorageError> {
+        self.save_block_with_overrides(block, Vec::new()).await
+    }
+
+    async fn save_block_with_overrides(&self, mut block: Block, overrides: Vec<ExecutionAccountChanges>) -> anyhow::Result<Block, EthStorageError> {
+        let mut state_lock = self.lock_write().await;
+        let state = &mut *state_lock;
 
         // check conflicts
+        // transactions in the same block are checked in order, each one on top of the changes of the previous ones
+        let mut block_overlay = StateOverlay::default();
         for transaction in &block.transactions {
-            if let Some(conflicts) = check_conflicts(&state, &transaction.execution) {
+            if let Some(conflicts) = check_conflicts(state, &block_overlay, &transaction.execution) {
                 return Err(EthStorageError::Conflict(conflicts));
             }
+            block_overlay.apply(&transaction.execution);
+        }
+
+        // save execution changes
+        // overrides are applied after them as changes of a successful execution
+        let mut modified_slots: HashMap<Address, HashSet<SlotIndex>> = HashMap::new();
+        for transaction in &block.transactions {
+            for changes in transaction.execution.changes.clone() {
+                state.apply_changes(*block.number(), changes, transaction.is_success(), &mut modified_slots);
+            }
+        }
+        for changes in overrides {
+            state.apply_changes(*block.number(), changes, true, &mut modified_slots);
         }
 
+        // calculate state root
+        // the block hash depends on the state root, so it must be calculated again
+        for (address, slots) in &modified_slots {
+            if let Some(account) = state.accounts.get(address) {
+                state.trie.update(account, slots);
+            }
+        }
+        block.header.state_root = state.trie.root();
+        block.update_hash();
+
         // save block
         tracing::debug!(number = %block.number(), "saving block");
         let block = Arc::new(block);
@@ -238,57 +407,16 @@ impl EthStorage for InMemoryStorage {
         for transaction in block.transactions.clone() {
             tracing::debug!(hash = %transaction.input.hash, "saving transaction");
             state.transactions.insert(transaction.input.hash.clone(), transaction.clone());
-            let is_success = transaction.is_success();
 
             // save logs
-            if is_success {
+            if transaction.is_success() {
                 for log in transaction.logs {
                     state.logs.push(log);
                 }
             }
-
-            // save execution changes
-            for changes in transaction.execution.changes {
-                let account = state
-                    .accounts
-                    .entry(changes.address.clone())
-                    .or_insert_with(|| InMemoryAccount::new(changes.address));
-
-                // nonce
-                if let Some(nonce) = changes.nonce.take_modified() {
-                    account.set_nonce(*block.number(), nonce);
-                }
-
-                // balance
-                if let Some(balance) = changes.balance.take_modified() {
-                    account.set_balance(*block.number(), balance);
-                }
-
-                // bytecode
-                if is_success {
-                    if let Some(Some(bytecode)) = changes.bytecode.take_modified() {
-                        account.set_bytecode(*block.number(), bytecode);
-                    }
-                }
-
-                // slots
-                if is_success {
-                    for (slot_index, slot) in changes.slots {
-                        if let Some(slot) = slot.take_modified() {
-                            match account.slots.get_mut(&slot_index) {
-                                Some(slot_history) => {
-                                    slot_history.push(*block.number(), slot);
-                                }
-                                None => {
-                                    account.slots.insert(slot_index, InMemoryHistory::new(*block.number(), slot));
-                                }
-                            };
-                        }
-                    }
-                }
-            }
         }
-        Ok(())
+
+        Ok((*block).clone())
     }
 
     async fn reset(&self, block_number: BlockNumber) -> anyhow::Result<()> {
@@ -315,12 +443,18 @@ impl EthStorage for InMemoryStorage {
         for account in state.accounts.values_mut() {
             account.reset(block_number);
         }
+        state.trie = InMemoryTrie::new(state.accounts.values(), &StoragePointInTime::Present);
 
         Ok(())
     }
 }
 
-fn check_conflicts(state: &InMemoryStorageState, execution: &Execution) -> Option<ExecutionConflicts> {
+fn check_conflicts(state: &InMemoryStorageState, overlay: &StateOverlay, execution: &Execution) -> Option<ExecutionConflicts> {
+    // values modified by previous transactions of the same block must be checked against the overlay
+    if let Some(conflicts) = overlay.check_conflicts(execution) {
+        return Some(conflicts);
+    }
+
     let mut conflicts = ExecutionConflictsBuilder::default();
 
     for change in &execution.changes {
@@ -329,18 +463,21 @@ fn check_conflicts(state: &InMemoryStorageState, execution: &Execution) -> Optio
         if let Some(account) = state.accounts.get(address) {
             // check account info conflicts
             if let Some(touched_nonce) = change.nonce.take_original_ref() {
-                if touched_nonce != account.nonce.get_current_ref() {
+                if overlay.nonce(address).is_none() && touched_nonce != account.nonce.get_current_ref() {
                     conflicts.add_nonce(address.clone(), account.nonce.get_current(), touched_nonce.clone());
                 }
             }
             if let Some(touched_balance) = change.balance.take_original_ref() {
-                if touched_balance != account.balance.get_current_ref() {
+                if overlay.balance(address).is_none() && touched_balance != account.balance.get_current_ref() {
                     conflicts.add_balance(address.clone(), account.balance.get_current(), touched_balance.clone());
                 }
             }
 
             // check slots conflicts
             for (touched_slot_index, touched_slot) in &change.slots {
+                if overlay.slot(address, touched_slot_index).is_some() {
+                    continue;
+                }
                 if let Some(slot) = account.slots.get(touched_slot_index) {
                     if let Some(touched_slot) = touched_slot.take_original_ref() {
                         let slot_value = slot.get_current().value;
@@ -1,11 +1,13 @@
 use std::collections::HashMap;
 
+use crate::eth::primitives::Account;
 use crate::eth::primitives::Address;
 use crate::eth::primitives::BlockNumber;
 use crate::eth::primitives::Bytes;
 use crate::eth::primitives::Nonce;
 use crate::eth::primitives::Slot;
 use crate::eth::primitives::SlotIndex;
+use crate::eth::primitives::StoragePointInTime;
 use crate::eth::primitives::Wei;
 use crate::eth::storage::inmemory::InMemoryHistory;
 
@@ -35,6 +37,27 @@ impl InMemoryAccount {
         }
     }
 
+    /// Creates a new account with its state and slots at the genesis block.
+    pub fn new_with_state(account: Account, slots: Vec<Slot>) -> Self {
+        Self {
+            address: account.address,
+            balance: InMemoryHistory::new_at_zero(account.balance),
+            nonce: InMemoryHistory::new_at_zero(account.nonce),
+            bytecode: InMemoryHistory::new_at_zero(account.bytecode),
+            slots: slots.into_iter().map(|slot| (slot.index.clone(), InMemoryHistory::new_at_zero(slot))).collect(),
+        }
+    }
+
+    /// Returns the account state at the given point in time.
+    pub fn get_at_point(&self, point_in_time: &StoragePointInTime) -> Account {
+        Account {
+            address: self.address.clone(),
+            balance: self.balance.get_at_point(point_in_time).unwrap_or_default(),
+            nonce: self.nonce.get_at_point(point_in_time).unwrap_or_default(),
+            bytecode: self.bytecode.get_at_point(point_in_time).unwrap_or_default(),
+        }
+    }
+
     /// Resets all account changes to the specified block number.
     pub fn reset(&mut self, block_number: BlockNumber) {
         // SAFETY: ok to unwrap because all historical values starts at block 0
@@ -0,0 +1,330 @@
+//! In-memory state trie.
+//!
+//! Tracks the Merkle Patricia trie leaves of the accounts and slots stored in memory, so the state root can be calculated after each
+//! block. Trie nodes are not kept in memory, they are rebuilt from the leaves whenever a root or a proof is needed:
+//!
+//! - Updating an account rebuilds its storage trie, hashing all its slots. The resulting storage root is cached with the account leaf.
+//! - Calculating the state root rebuilds the account trie, hashing all accounts. It happens once per mined block, under the storage
+//!   write lock, so the cost of mining grows linearly with the number of accounts.
+//!
+//! It is meant for development and test chains, where the state is small enough for this cost to be negligible.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+
+use ethereum_types::H256;
+use ethereum_types::U256;
+use ethers_core::utils::keccak256;
+use keccak_hasher::KeccakHasher;
+use rlp::RlpStream;
+
+use crate::eth::primitives::Address;
+use crate::eth::primitives::Bytes;
+use crate::eth::primitives::Hash;
+use crate::eth::primitives::SlotIndex;
+use crate::eth::primitives::StoragePointInTime;
+use crate::eth::storage::inmemory::InMemoryAccount;
+use crate::ext::not;
+
+/// Trie leaves indexed by the keccak of their keys, as expected by the secure trie used by Ethereum.
+type TrieLeaves = BTreeMap<H256, Vec<u8>>;
+
+#[derive(Debug, Default)]
+pub struct InMemoryTrie {
+    /// RLP-encoded accounts indexed by the keccak of their addresses.
+    accounts: TrieLeaves,
+
+    /// RLP-encoded slot values indexed by the keccak of their indexes.
+    slots: HashMap<Address, TrieLeaves>,
+
+    /// Storage roots of accounts with slots, calculated when the account is updated.
+    storage_roots: HashMap<Address, H256>,
+}
+
+impl InMemoryTrie {
+    /// Creates a trie with the state of all accounts at the given point in time.
+    pub fn new<'a>(accounts: impl IntoIterator<Item = &'a InMemoryAccount>, point_in_time: &StoragePointInTime) -> Self {
+        let mut trie = Self::default();
+        for account in accounts {
+            trie.update_at_point(account, account.slots.keys(), point_in_time);
+        }
+        trie
+    }
+
+    /// Updates the leaves of an account and its modified slots using their current values.
+    pub fn update<'a>(&mut self, account: &InMemoryAccount, modified_slots: impl IntoIterator<Item = &'a SlotIndex>) {
+        self.update_at_point(account, modified_slots, &StoragePointInTime::Present);
+    }
+
+    fn update_at_point<'a>(&mut self, account: &InMemoryAccount, modified_slots: impl IntoIterator<Item = &'a SlotIndex>, point_in_time: &StoragePointInTime) {
+        // update slots
+        let slots = self.slots.entry(account.address.clone()).or_default();
+        for index in modified_slots {
+            let key = H256(keccak256(<[u8; 32]>::from(index.clone())));
+            let value = account
+                .slots
+                .get(index)
+                .and_then(|slot| slot.get_at_point(point_in_time))
+                .map(|slot| slot.value.as_u256())
+                .unwrap_or_default();
+            if value.is_zero() {
+                slots.remove(&key);
+            } else {
+                slots.insert(key, rlp::encode(&value).to_vec());
+            }
+        }
+        let storage_root = trie_root(slots);
+        let has_slots = not(slots.is_empty());
+        if has_slots {
+            self.storage_roots.insert(account.address.clone(), storage_root);
+        } else {
+            self.slots.remove(&account.address);
+            self.storage_roots.remove(&account.address);
+        }
+
+        // update account
+        let key = H256(keccak256(account.address.as_ref()));
+        let account = account.get_at_point(point_in_time);
+        let nonce = U256::from(account.nonce.clone());
+        let balance = U256::from(account.balance.clone());
+
+        // empty accounts are not part of the state (EIP-161)
+        if nonce.is_zero() && balance.is_zero() && account.bytecode.is_none() && not(has_slots) {
+            self.accounts.remove(&key);
+            return;
+        }
+
+        let mut stream = RlpStream::new_list(4);
+        stream.append(&nonce);
+        stream.append(&balance);
+        stream.append(&storage_root);
+        stream.append(&H256::from(account.code_hash()));
+        self.accounts.insert(key, stream.out().to_vec());
+    }
+
+    /// Calculates the state root, hashing all accounts.
+    pub fn root(&self) -> Hash {
+        trie_root(&self.accounts).into()
+    }
+
+    /// Storage root of an account, calculated when the account was last updated.
+    pub fn storage_root(&self, address: &Address) -> Hash {
+        match self.storage_roots.get(address) {
+            Some(storage_root) => (*storage_root).into(),
+            None => trie_root(&TrieLeaves::new()).into(),
+        }
+    }
+
+    /// Generates the nodes that prove the account is present or absent in the state trie, starting from the root.
+    pub fn account_proof(&self, address: &Address) -> Vec<Bytes> {
+        let key = H256(keccak256(address.as_ref()));
+        trie_proof(&self.accounts, &key)
+    }
+
+    /// Generates the nodes that prove the slot is present or absent in the storage trie of the account, starting from the root.
+    pub fn slot_proof(&self, address: &Address, index: &SlotIndex) -> Vec<Bytes> {
+        let Some(slots) = self.slots.get(address) else {
+            return Vec::new();
+        };
+        let key = H256(keccak256(<[u8; 32]>::from(index.clone())));
+        trie_proof(slots, &key)
+    }
+}
+
+fn trie_root(leaves: &TrieLeaves) -> H256 {
+    H256(triehash::trie_root::<KeccakHasher, _, _, _>(leaves))
+}
+
+// -----------------------------------------------------------------------------
+// Proofs
+// -----------------------------------------------------------------------------
+
+/// Trie leaf with its key split in nibbles.
+type NibbleLeaf<'a> = (Vec<u8>, &'a [u8]);
+
+/// Builds the trie nodes from the leaves and collects the ones in the path of the key.
+fn trie_proof(leaves: &TrieLeaves, key: &H256) -> Vec<Bytes> {
+    if leaves.is_empty() {
+        return Vec::new();
+    }
+
+    let leaves: Vec<NibbleLeaf> = leaves.iter().map(|(key, value)| (to_nibbles(key.as_bytes()), value.as_slice())).collect();
+    let key = to_nibbles(key.as_bytes());
+
+    let mut proof = Vec::new();
+    encode_node(&leaves, 0, Some(&key), &mut proof);
+    proof
+}
+
+/// Encodes the node containing the leaves that share the first `depth` nibbles.
+///
+/// Nodes in the path of the key are added to the proof, except the ones embedded in their parents because they are smaller than a hash.
+/// Children are encoded before their parents, so nodes are inserted at the beginning to keep the proof ordered from the root.
+fn encode_node(leaves: &[NibbleLeaf], depth: usize, key: Option<&[u8]>, proof: &mut Vec<Bytes>) -> Vec<u8> {
+    let encoded = match leaves {
+        // leaf
+        [(leaf_key, value)] => {
+            let mut stream = RlpStream::new_list(2);
+            stream.append(&hex_prefix(&leaf_key[depth..], true));
+            stream.append(value);
+            stream.out().to_vec()
+        }
+
+        // extension or branch
+        _ => {
+            let prefix_len = common_prefix_len(leaves, depth);
+            if prefix_len > 0 {
+                let child_key = key.filter(|key| key[depth..depth + prefix_len] == leaves[0].0[depth..depth + prefix_len]);
+                let child = encode_node(leaves, depth + prefix_len, child_key, proof);
+
+                let mut stream = RlpStream::new_list(2);
+                stream.append(&hex_prefix(&leaves[0].0[depth..depth + prefix_len], false));
+                append_child(&mut stream, &child);
+                stream.out().to_vec()
+            } else {
+                let mut stream = RlpStream::new_list(17);
+                for nibble in 0..16 {
+                    let start = leaves.partition_point(|(leaf_key, _)| leaf_key[depth] < nibble);
+                    let end = leaves.partition_point(|(leaf_key, _)| leaf_key[depth] <= nibble);
+                    if start == end {
+                        stream.append_empty_data();
+                        continue;
+                    }
+                    let child_key = key.filter(|key| key[depth] == nibble);
+                    let child = encode_node(&leaves[start..end], depth + 1, child_key, proof);
+                    append_child(&mut stream, &child);
+                }
+                // keys have the same length, so branches never have values
+                stream.append_empty_data();
+                stream.out().to_vec()
+            }
+        }
+    };
+
+    if key.is_some() && (depth == 0 || encoded.len() >= 32) {
+        proof.insert(0, encoded.clone().into());
+    }
+    encoded
+}
+
+/// Appends the reference to a child node: the node itself if it is smaller than a hash, otherwise its hash.
+fn append_child(stream: &mut RlpStream, child: &[u8]) {
+    if child.len() < 32 {
+        stream.append_raw(child, 1);
+    } else {
+        stream.append(&H256(keccak256(child)));
+    }
+}
+
+/// Counts the nibbles after `depth` shared by all leaves.
+fn common_prefix_len(leaves: &[NibbleLeaf], depth: usize) -> usize {
+    let first = &leaves[0].0;
+    let last = &leaves[leaves.len() - 1].0;
+    first[depth..].iter().zip(&last[depth..]).take_while(|(a, b)| a == b).count()
+}
+
+/// Encodes nibbles with the hex-prefix encoding used by leaf and extension nodes.
+fn hex_prefix(nibbles: &[u8], is_leaf: bool) -> Vec<u8> {
+    let flag = if is_leaf { 2 } else { 0 };
+    let mut encoded = Vec::with_capacity(nibbles.len() / 2 + 1);
+    let rest = if nibbles.len() % 2 == 1 {
+        encoded.push(((flag + 1) << 4) | nibbles[0]);
+        &nibbles[1..]
+    } else {
+        encoded.push(flag << 4);
+        nibbles
+    };
+    encoded.extend(rest.chunks(2).map(|pair| (pair[0] << 4) | pair[1]));
+    encoded
+}
+
+fn to_nibbles(bytes: &[u8]) -> Vec<u8> {
+    bytes.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]).collect()
+}
+
+// -----------------------------------------------------------------------------
+// Tests
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use ethers_core::utils::keccak256;
+    use hex_literal::hex;
+
+    use crate::eth::primitives::*;
+    use crate::eth::storage::inmemory::InMemoryAccount;
+    use crate::eth::storage::inmemory::InMemoryHistory;
+    use crate::eth::storage::inmemory::InMemoryTrie;
+
+    #[test]
+    fn empty_trie_root() {
+        let trie = InMemoryTrie::default();
+        assert_eq!(trie.root(), Hash::new(hex!("56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421")));
+    }
+
+    #[test]
+    fn root_changes_with_accounts_and_slots() {
+        let address = Address::new([1; 20]);
+        let mut account = InMemoryAccount::new_with_balance(address, 100u64.into());
+        let mut trie = InMemoryTrie::new([&account], &StoragePointInTime::Present);
+        let balance_root = trie.root();
+        assert_ne!(balance_root, InMemoryTrie::default().root());
+
+        // slot modifies the root
+        let slot = Slot::new(1u64, 2u64);
+        account.slots.insert(slot.index.clone(), InMemoryHistory::new(1u64.into(), slot.clone()));
+        trie.update(&account, [&slot.index]);
+        let slot_root = trie.root();
+        assert_ne!(slot_root, balance_root);
+
+        // incremental update matches a trie built from scratch
+        assert_eq!(slot_root, InMemoryTrie::new([&account], &StoragePointInTime::Present).root());
+
+        // trie built in the past does not see the slot
+        assert_eq!(InMemoryTrie::new([&account], &StoragePointInTime::Past(0u64.into())).root(), balance_root);
+
+        // zeroed slot is removed from the trie
+        account.slots.get_mut(&slot.index).unwrap().push(2u64.into(), Slot::new(1u64, 0u64));
+        trie.update(&account, [&slot.index]);
+        assert_eq!(trie.root(), balance_root);
+    }
+
+    #[test]
+    fn proofs_start_at_root() {
+        let accounts: Vec<InMemoryAccount> = (1..=20u8)
+            .map(|i| InMemoryAccount::new_with_balance(Address::new([i; 20]), (i as u64).into()))
+            .collect();
+        let trie = InMemoryTrie::new(&accounts, &StoragePointInTime::Present);
+
+        // existing account
+        let proof = trie.account_proof(&accounts[0].address);
+        assert!(proof.len() > 1);
+        assert_eq!(Hash::new(keccak256(&proof[0])), trie.root());
+
+        // missing account
+        let proof = trie.account_proof(&Address::new([0xff; 20]));
+        assert_eq!(Hash::new(keccak256(&proof[0])), trie.root());
+
+        // empty storage
+        assert!(trie.slot_proof(&accounts[0].address, &SlotIndex::from(1u64)).is_empty());
+    }
+
+    #[test]
+    fn storage_root_follows_updated_slots() {
+        let address = Address::new([2; 20]);
+        let mut account = InMemoryAccount::new_with_balance(address.clone(), 100u64.into());
+        let mut trie = InMemoryTrie::new([&account], &StoragePointInTime::Present);
+        let empty_storage_root = trie.storage_root(&address);
+
+        // storage root is the root of the slot proofs
+        let slot = Slot::new(1u64, 2u64);
+        account.slots.insert(slot.index.clone(), InMemoryHistory::new(1u64.into(), slot.clone()));
+        trie.update(&account, [&slot.index]);
+        let proof = trie.slot_proof(&address, &slot.index);
+        assert_eq!(Hash::new(keccak256(&proof[0])), trie.storage_root(&address));
+
+        // zeroed slots empty the storage
+        account.slots.get_mut(&slot.index).unwrap().push(2u64.into(), Slot::new(1u64, 0u64));
+        trie.update(&account, [&slot.index]);
+        assert_eq!(trie.storage_root(&address), empty_storage_root);
+    }
+}
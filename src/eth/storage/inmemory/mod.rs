@@ -2,7 +2,9 @@
 mod inmemory;
 mod inmemory_account;
 mod inmemory_history;
+mod inmemory_trie;
 
 pub use inmemory::InMemoryStorage;
 pub use inmemory_account::InMemoryAccount;
 pub use inmemory_history::InMemoryHistory;
+pub use inmemory_trie::InMemoryTrie;
@@ -3,11 +3,14 @@ use std::time::Instant;
 use async_trait::async_trait;
 
 use crate::eth::primitives::Account;
+use crate::eth::primitives::AccountProof;
 use crate::eth::primitives::Address;
 use crate::eth::primitives::Block;
 use crate::eth::primitives::BlockNumber;
 use crate::eth::primitives::BlockSelection;
+use crate::eth::primitives::Bytes;
 use crate::eth::primitives::Execution;
+use crate::eth::primitives::ExecutionAccountChanges;
 use crate::eth::primitives::ExecutionConflicts;
 use crate::eth::primitives::Hash;
 use crate::eth::primitives::LogFilter;
@@ -62,6 +65,18 @@ impl<T: EthStorage> EthStorage for MetrifiedStorage<T> {
         result
     }
 
+    async fn read_bytecode(&self, code_hash: &Hash) -> anyhow::Result<Option<Bytes>> {
+        let start = Instant::now();
+        let result = self.inner.read_bytecode(code_hash).await;
+        metrics::inc_storage_bytecodes_read(start.elapsed(), result.is_ok());
+        result
+    }
+
+    // TODO: track metric
+    async fn read_proof(&self, address: &Address, slots: &[SlotIndex], point_in_time: &StoragePointInTime) -> anyhow::Result<AccountProof> {
+        self.inner.read_proof(address, slots, point_in_time).await
+    }
+
     async fn read_block(&self, block_selection: &BlockSelection) -> anyhow::Result<Option<Block>> {
         let start = Instant::now();
         let result = self.inner.read_block(block_selection).await;
@@ -69,6 +84,13 @@ impl<T: EthStorage> EthStorage for MetrifiedStorage<T> {
         result
     }
 
+    async fn read_block_hash(&self, number: &BlockNumber) -> anyhow::Result<Option<Hash>> {
+        let start = Instant::now();
+        let result = self.inner.read_block_hash(number).await;
+        metrics::inc_storage_block_hashes_read(start.elapsed(), result.is_ok());
+        result
+    }
+
     async fn read_mined_transaction(&self, hash: &Hash) -> anyhow::Result<Option<TransactionMined>> {
         let start = Instant::now();
         let result = self.inner.read_mined_transaction(hash).await;
@@ -83,13 +105,20 @@ impl<T: EthStorage> EthStorage for MetrifiedStorage<T> {
         result
     }
 
-    async fn save_block(&self, block: Block) -> anyhow::Result<(), EthStorageError> {
+    async fn save_block(&self, block: Block) -> anyhow::Result<Block, EthStorageError> {
         let start = Instant::now();
         let result = self.inner.save_block(block).await;
         metrics::inc_storage_blocks_written(start.elapsed(), result.is_ok());
         result
     }
 
+    async fn save_block_with_overrides(&self, block: Block, overrides: Vec<ExecutionAccountChanges>) -> anyhow::Result<Block, EthStorageError> {
+        let start = Instant::now();
+        let result = self.inner.save_block_with_overrides(block, overrides).await;
+        metrics::inc_storage_blocks_written(start.elapsed(), result.is_ok());
+        result
+    }
+
     // TODO: track metric
     async fn reset(&self, number: BlockNumber) -> anyhow::Result<()> {
         self.inner.reset(number).await
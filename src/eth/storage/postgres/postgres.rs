@@ -1,18 +1,23 @@
 use std::collections::HashMap;
 
+use anyhow::anyhow;
 use anyhow::Context;
 use async_trait::async_trait;
+use ethers_core::utils::keccak256;
 use sqlx::query_builder::QueryBuilder;
 use sqlx::types::BigDecimal;
 use sqlx::Row;
 
 use crate::eth::primitives::Account;
+use crate::eth::primitives::AccountProof;
 use crate::eth::primitives::Address;
 use crate::eth::primitives::Block;
 use crate::eth::primitives::BlockHeader;
 use crate::eth::primitives::BlockNumber;
 use crate::eth::primitives::BlockSelection;
+use crate::eth::primitives::Bytes;
 use crate::eth::primitives::Execution;
+use crate::eth::primitives::ExecutionAccountChanges;
 use crate::eth::primitives::ExecutionConflicts;
 use crate::eth::primitives::Hash;
 use crate::eth::primitives::Index;
@@ -29,6 +34,7 @@ use crate::eth::storage::postgres::types::PostgresTopic;
 use crate::eth::storage::postgres::types::PostgresTransaction;
 use crate::eth::storage::EthStorage;
 use crate::eth::storage::EthStorageError;
+use crate::if_else;
 use crate::infra::postgres::Postgres;
 
 #[async_trait]
@@ -117,11 +123,25 @@ impl EthStorage for Postgres {
         Ok(s)
     }
 
+    async fn read_bytecode(&self, code_hash: &Hash) -> anyhow::Result<Option<Bytes>> {
+        tracing::debug!(%code_hash, "reading bytecode");
+
+        let bytecode: Option<Bytes> = sqlx::query_file_scalar!("src/eth/storage/postgres/queries/select_bytecode_by_hash.sql", code_hash.as_ref())
+            .fetch_optional(&self.connection_pool)
+            .await?;
+        Ok(bytecode)
+    }
+
+    async fn read_proof(&self, _address: &Address, _slots: &[SlotIndex], _point_in_time: &StoragePointInTime) -> anyhow::Result<AccountProof> {
+        Err(anyhow!("proofs are not supported by postgres storage"))
+    }
+
     async fn read_block(&self, block: &BlockSelection) -> anyhow::Result<Option<Block>> {
         tracing::debug!(block = ?block, "reading block");
 
         match block {
-            BlockSelection::Latest => {
+            // storage only knows mined blocks, so pending is the latest one
+            BlockSelection::Latest | BlockSelection::Pending => {
                 let current = self.read_current_block_number().await?;
 
                 let block_number = i64::try_from(current)?;
@@ -318,6 +338,15 @@ impl EthStorage for Postgres {
         }
     }
 
+    async fn read_block_hash(&self, number: &BlockNumber) -> anyhow::Result<Option<Hash>> {
+        tracing::debug!(%number, "reading block hash");
+
+        let hash: Option<Hash> = sqlx::query_file_scalar!("src/eth/storage/postgres/queries/select_block_hash_by_number.sql", i64::try_from(*number)?)
+            .fetch_optional(&self.connection_pool)
+            .await?;
+        Ok(hash)
+    }
+
     async fn read_mined_transaction(&self, hash: &Hash) -> anyhow::Result<Option<TransactionMined>> {
         tracing::debug!(%hash, "reading transaction");
         let transaction = sqlx::query_file_as!(
@@ -413,23 +442,29 @@ impl EthStorage for Postgres {
     // this much easier to work with (note: I tried implementing Encode for both Hash and Nonce and
     // neither worked for some reason I was not able to determine at this time)
     // TODO: save slots
-    async fn save_block(&self, block: Block) -> anyhow::Result<(), EthStorageError> {
+    async fn save_block(&self, block: Block) -> anyhow::Result<Block, EthStorageError> {
         tracing::debug!(block = ?block, "saving block");
         sqlx::query_file!(
             "src/eth/storage/postgres/queries/insert_block.sql",
             i64::try_from(block.header.number).context("failed to convert block number")?,
             block.header.hash.as_ref(),
             block.header.transactions_root.as_ref(),
-            BigDecimal::try_from(block.header.gas)?,
+            block.header.state_root.as_ref(),
+            block.header.receipts_root.as_ref(),
+            BigDecimal::try_from(block.header.gas.clone())?,
+            BigDecimal::try_from(block.header.gas_limit.clone())?,
+            BigDecimal::try_from(block.header.base_fee.clone())?,
             block.header.bloom.as_ref(),
-            i32::try_from(block.header.timestamp_in_secs).context("failed to convert block timestamp")?,
+            block.header.miner.as_ref(),
+            i32::try_from(block.header.timestamp_in_secs.clone()).context("failed to convert block timestamp")?,
+            i64::try_from(block.header.size).context("failed to convert block size")?,
             block.header.parent_hash.as_ref()
         )
         .execute(&self.connection_pool)
         .await
         .context("failed to insert block")?;
 
-        for transaction in block.transactions {
+        for transaction in block.transactions.clone() {
             let is_success = transaction.is_success();
             let to = <[u8; 20]>::from(*transaction.input.to.unwrap_or_default());
             sqlx::query_file!(
@@ -450,42 +485,52 @@ impl EthStorage for Postgres {
                 &<[u8; 32]>::from(transaction.input.r),
                 &<[u8; 32]>::from(transaction.input.s),
                 BigDecimal::try_from(transaction.input.value)?,
-                transaction.execution.result.to_string()
+                transaction.execution.result.to_string(),
+                BigDecimal::try_from(transaction.cumulative_gas_used)?,
+                transaction.logs_bloom.as_ref(),
+                i32::try_from(transaction.input.transaction_type.as_u64()).context("failed to convert transaction type")?,
+                transaction.input.max_fee_per_gas.map(BigDecimal::try_from).transpose()?,
+                transaction.input.max_priority_fee_per_gas.map(BigDecimal::try_from).transpose()?,
+                rlp::encode(&transaction.input.access_list).to_vec(),
+                BigDecimal::try_from(transaction.effective_gas_price)?
             )
             .execute(&self.connection_pool)
             .await
             .context("failed to insert transaction")?;
 
-            if is_success {
-                for change in transaction.execution.changes {
-                    let nonce = change.nonce.take().unwrap_or_else(|| {
-                        tracing::debug!("Nonce not set, defaulting to 0");
-                        0.into()
-                    });
-                    let balance = change.balance.take().unwrap_or_else(|| {
-                        tracing::debug!("Balance not set, defaulting to 0");
-                        0.into()
-                    });
-                    let bytecode = change
-                        .bytecode
-                        .take()
-                        .unwrap_or_else(|| {
-                            tracing::debug!("Bytecode not set, defaulting to None");
-                            None
-                        })
-                        .map(|val| val.as_ref().to_owned());
+            // failed transactions still increment the sender nonce and pay for gas, but do not modify bytecodes and slots
+            for change in transaction.execution.changes {
+                let nonce = change.nonce.take().unwrap_or_else(|| {
+                    tracing::debug!("Nonce not set, defaulting to 0");
+                    0.into()
+                });
+                let balance = change.balance.take().unwrap_or_else(|| {
+                    tracing::debug!("Balance not set, defaulting to 0");
+                    0.into()
+                });
+                let bytecode = if_else!(is_success, change.bytecode.take(), change.bytecode.take_original())
+                    .unwrap_or_else(|| {
+                        tracing::debug!("Bytecode not set, defaulting to None");
+                        None
+                    })
+                    .map(|val| val.as_ref().to_owned());
 
-                    sqlx::query_file!(
-                        "src/eth/storage/postgres/queries/insert_account.sql",
-                        change.address.as_ref(),
-                        BigDecimal::try_from(nonce)?,
-                        BigDecimal::try_from(balance)?,
-                        bytecode,
-                        i64::try_from(block.header.number).context("failed to convert block number")?
-                    )
-                    .execute(&self.connection_pool)
-                    .await
-                    .context("failed to insert account")?;
+                sqlx::query_file!(
+                    "src/eth/storage/postgres/queries/insert_account.sql",
+                    change.address.as_ref(),
+                    BigDecimal::try_from(nonce)?,
+                    BigDecimal::try_from(balance)?,
+                    bytecode,
+                    i64::try_from(block.header.number).context("failed to convert block number")?
+                )
+                .execute(&self.connection_pool)
+                .await
+                .context("failed to insert account")?;
+                if let Some(ref bytecode) = bytecode {
+                    self.insert_bytecode(bytecode).await?;
+                }
+
+                if is_success {
                     for (slot_idx, value) in change.slots {
                         sqlx::query_file!(
                             "src/eth/storage/postgres/queries/insert_account_slot.sql",
@@ -540,7 +585,53 @@ impl EthStorage for Postgres {
             }
         }
 
-        Ok(())
+        Ok(block)
+    }
+
+    async fn save_block_with_overrides(&self, block: Block, overrides: Vec<ExecutionAccountChanges>) -> anyhow::Result<Block, EthStorageError> {
+        let block = self.save_block(block).await?;
+        let block_number = i64::try_from(block.header.number).context("failed to convert block number")?;
+
+        // overrides are saved atomically, so a failure does not leave a partial override
+        let mut tx = self.connection_pool.begin().await.context("failed to init transaction")?;
+        for changes in overrides {
+            tracing::debug!(address = %changes.address, "saving account override");
+
+            let bytecode = changes.bytecode.take().flatten();
+            if let Some(ref bytecode) = bytecode {
+                sqlx::query_file!("src/eth/storage/postgres/queries/insert_bytecode.sql", &keccak256(bytecode), bytecode.as_ref())
+                    .execute(&mut *tx)
+                    .await
+                    .context("failed to insert bytecode")?;
+            }
+            sqlx::query_file!(
+                "src/eth/storage/postgres/queries/insert_account.sql",
+                changes.address.as_ref(),
+                BigDecimal::try_from(changes.nonce.take().unwrap_or_default())?,
+                BigDecimal::try_from(changes.balance.take().unwrap_or_default())?,
+                bytecode.map(|bytecode| bytecode.as_ref().to_owned()),
+                block_number
+            )
+            .execute(&mut *tx)
+            .await
+            .context("failed to insert account")?;
+
+            for slot in changes.slots.into_values().filter_map(|slot| slot.take_modified()) {
+                sqlx::query_file!(
+                    "src/eth/storage/postgres/queries/insert_account_slot.sql",
+                    &<[u8; 32]>::from(slot.index),
+                    &<[u8; 32]>::from(slot.value),
+                    changes.address.as_ref(),
+                    block_number
+                )
+                .execute(&mut *tx)
+                .await
+                .context("failed to insert slot")?;
+            }
+        }
+        tx.commit().await.context("failed to commit transaction")?;
+
+        Ok(block)
     }
 
     async fn read_current_block_number(&self) -> anyhow::Result<BlockNumber> {
@@ -1,2 +1,2 @@
-INSERT INTO blocks(number, hash, transactions_root, gas, logs_bloom, timestamp_in_secs, parent_hash, created_at)
-VALUES ($1, $2, $3, $4, $5, $6, $7, current_timestamp)
+INSERT INTO blocks(number, hash, transactions_root, state_root, receipts_root, gas, gas_limit, base_fee, logs_bloom, miner, timestamp_in_secs, size, parent_hash, created_at)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, current_timestamp)
\ No newline at end of file
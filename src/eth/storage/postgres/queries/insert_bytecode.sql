@@ -0,0 +1,2 @@
+INSERT INTO bytecodes VALUES ($1, $2)
+ON CONFLICT (code_hash) DO NOTHING
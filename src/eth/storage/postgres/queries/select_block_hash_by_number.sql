@@ -0,0 +1,4 @@
+SELECT
+    hash as "hash: _"
+FROM blocks
+WHERE number = $1
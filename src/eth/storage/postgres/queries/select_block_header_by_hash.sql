@@ -2,9 +2,15 @@ SELECT
     number as "number: _"
     ,hash as "hash: _"
     ,transactions_root as "transactions_root: _"
+    ,state_root as "state_root: _"
+    ,receipts_root as "receipts_root: _"
     ,gas as "gas: _"
+    ,gas_limit as "gas_limit: _"
+    ,base_fee as "base_fee: _"
     ,logs_bloom as "bloom: _"
+    ,miner as "miner: _"
     ,timestamp_in_secs as "timestamp_in_secs: _"
+    ,size as "size: _"
     ,parent_hash as "parent_hash: _"
 FROM blocks
 WHERE hash = $1
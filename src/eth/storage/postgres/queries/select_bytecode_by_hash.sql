@@ -0,0 +1,4 @@
+SELECT
+    bytecode as "bytecode: _"
+FROM bytecodes
+WHERE code_hash = $1
@@ -16,5 +16,12 @@ SELECT
     ,s as "s: _"
     ,r as "r: _"
     ,result as "result: _"
+    ,cumulative_gas_used as "cumulative_gas_used: _"
+    ,logs_bloom as "logs_bloom: _"
+    ,transaction_type as "transaction_type: _"
+    ,max_fee_per_gas as "max_fee_per_gas: _"
+    ,max_priority_fee_per_gas as "max_priority_fee_per_gas: _"
+    ,access_list as "access_list: _"
+    ,effective_gas_price as "effective_gas_price: _"
 FROM transactions
 WHERE block_number = $1
\ No newline at end of file
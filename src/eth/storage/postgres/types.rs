@@ -1,5 +1,8 @@
 use std::collections::HashMap;
 
+use ethereum_types::U64;
+
+use crate::eth::primitives::AccessList;
 use crate::eth::primitives::Address;
 use crate::eth::primitives::BlockNumber;
 use crate::eth::primitives::Bytes;
@@ -14,6 +17,7 @@ use crate::eth::primitives::Index;
 use crate::eth::primitives::Log;
 use crate::eth::primitives::LogMined;
 use crate::eth::primitives::LogTopic;
+use crate::eth::primitives::LogsBloom;
 use crate::eth::primitives::Nonce;
 use crate::eth::primitives::TransactionInput;
 use crate::eth::primitives::TransactionMined;
@@ -38,6 +42,13 @@ pub struct PostgresTransaction {
     pub r: EcdsaRs,
     pub s: EcdsaRs,
     pub v: EcdsaV,
+    pub cumulative_gas_used: Gas,
+    pub logs_bloom: LogsBloom,
+    pub transaction_type: i32,
+    pub max_fee_per_gas: Option<Wei>,
+    pub max_priority_fee_per_gas: Option<Wei>,
+    pub access_list: AccessList,
+    pub effective_gas_price: Wei,
 }
 
 impl PostgresTransaction {
@@ -62,6 +73,7 @@ impl PostgresTransaction {
         let input = TransactionInput {
             chain_id: ChainId::default(),
             hash: self.hash,
+            transaction_type: U64::from(self.transaction_type as u64),
             nonce: self.nonce,
             signer: self.signer_address,
             from: self.address_from,
@@ -69,13 +81,19 @@ impl PostgresTransaction {
             input: self.input,
             gas: self.gas,
             gas_price: self.gas_price,
+            max_fee_per_gas: self.max_fee_per_gas,
+            max_priority_fee_per_gas: self.max_priority_fee_per_gas,
+            access_list: self.access_list,
             v: self.v.into(),
             r: self.r.into(),
             s: self.s.into(),
             value: self.value,
         };
         TransactionMined {
+            effective_gas_price: self.effective_gas_price,
             transaction_index: self.idx_in_block,
+            cumulative_gas_used: self.cumulative_gas_used,
+            logs_bloom: self.logs_bloom,
             block_number: self.block_number,
             block_hash: self.block_hash,
             logs: mined_logs,
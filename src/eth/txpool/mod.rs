@@ -0,0 +1,10 @@
+//! Transaction Pool
+//!
+//! This module holds the transactions that were accepted by the node but are not mined yet. Transactions sent with the nonce expected by their sender are executed right away and stay in the pool only until their block is mined, while transactions sent with a nonce gap are queued until all previous nonces of the same sender are mined.
+//!
+//! Components:
+//! - `transaction_pool`: Tracks pending and queued transactions per sender and nonce, providing the pending view of the chain used by JSON-RPC methods like `eth_getTransactionCount` and `txpool_*`.
+
+mod transaction_pool;
+pub use transaction_pool::TransactionPool;
+pub use transaction_pool::TransactionPoolLimits;
@@ -0,0 +1,461 @@
+//! Transaction Pool
+//!
+//! Keeps track of transactions accepted by the node that are not mined yet. Transactions are indexed by sender and nonce, so
+//! queued transactions can be released in nonce order once the nonce gap of their sender is filled, and the pending nonce of
+//! a sender can be computed without touching the storage.
+//!
+//! Queued transactions are limited like geth does: in total, per sender, by how far their nonce is ahead of the sender nonce and by
+//! how long their sender stays without new activity, so transactions that may never become executable do not accumulate forever.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::time::Duration;
+use std::time::Instant;
+
+use ethereum_types::U256;
+use ethers_core::types::Transaction as EthersTransaction;
+use ethers_core::types::TxpoolContent as EthersTxpoolContent;
+use ethers_core::types::TxpoolInspect as EthersTxpoolInspect;
+use ethers_core::types::TxpoolInspectSummary as EthersTxpoolInspectSummary;
+use ethers_core::types::TxpoolStatus as EthersTxpoolStatus;
+use ethers_core::types::H160;
+
+use crate::eth::primitives::Address;
+use crate::eth::primitives::Hash;
+use crate::eth::primitives::Nonce;
+use crate::eth::primitives::TransactionInput;
+use crate::eth::EthExecutorError;
+use crate::ext::not;
+use crate::ext::OptionExt;
+
+/// Transactions indexed by sender and nonce.
+type TransactionsBySender = HashMap<Address, BTreeMap<Nonce, TransactionInput>>;
+
+/// Limits of queued transactions.
+#[derive(Debug, Clone)]
+pub struct TransactionPoolLimits {
+    /// Maximum number of queued transactions of all senders.
+    pub max_queued: usize,
+
+    /// Maximum number of queued transactions of a single sender.
+    pub max_queued_per_sender: usize,
+
+    /// Maximum distance between the nonce of a queued transaction and the nonce of its sender.
+    pub max_nonce_distance: u64,
+
+    /// Time queued transactions of a sender are kept without new activity from the sender.
+    pub queued_lifetime: Duration,
+}
+
+impl Default for TransactionPoolLimits {
+    /// Same limits used by geth.
+    fn default() -> Self {
+        Self {
+            max_queued: 1024,
+            max_queued_per_sender: 64,
+            max_nonce_distance: 1024,
+            queued_lifetime: Duration::from_secs(3 * 60 * 60),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TransactionPool {
+    /// Transactions with the nonce expected by their sender that are being executed or waiting to be mined.
+    pending: TransactionsBySender,
+
+    /// Transactions waiting for previous nonces of the same sender to be mined.
+    queued: TransactionsBySender,
+
+    /// Last time each sender with queued transactions had a transaction queued or promoted.
+    queued_heartbeats: HashMap<Address, Instant>,
+
+    limits: TransactionPoolLimits,
+}
+
+impl TransactionPool {
+    /// Creates an empty pool with the given limits of queued transactions.
+    pub fn new(limits: TransactionPoolLimits) -> Self {
+        Self { limits, ..Self::default() }
+    }
+
+    // -------------------------------------------------------------------------
+    // Queries
+    // -------------------------------------------------------------------------
+
+    /// Nonce the next transaction of the sender should use, considering both the mined nonce and the transactions in the pool.
+    pub fn pending_nonce(&self, sender: &Address, mined_nonce: Nonce) -> Nonce {
+        let mut nonce = mined_nonce;
+        while self.contains(sender, &nonce) {
+            nonce = nonce.next();
+        }
+        nonce
+    }
+
+    /// Nonce the next transaction of the sender should use to be executed after its pending transactions, ignoring queued ones.
+    pub fn executable_nonce(&self, sender: &Address, mined_nonce: Nonce) -> Nonce {
+        let mut nonce = mined_nonce;
+        while self.pending.get(sender).is_some_and(|transactions| transactions.contains_key(&nonce)) {
+            nonce = nonce.next();
+        }
+        nonce
+    }
+
+    /// Finds a pending or queued transaction by its hash.
+    pub fn find(&self, hash: &Hash) -> Option<&TransactionInput> {
+        self.pending
+            .values()
+            .chain(self.queued.values())
+            .flat_map(|transactions| transactions.values())
+            .find(|transaction| &transaction.hash == hash)
+    }
+
+    /// Senders with queued transactions.
+    pub fn queued_senders(&self) -> Vec<Address> {
+        self.queued.keys().cloned().collect()
+    }
+
+    fn contains(&self, sender: &Address, nonce: &Nonce) -> bool {
+        [&self.pending, &self.queued]
+            .into_iter()
+            .any(|transactions| transactions.get(sender).is_some_and(|transactions| transactions.contains_key(nonce)))
+    }
+
+    // -------------------------------------------------------------------------
+    // Mutations
+    // -------------------------------------------------------------------------
+
+    /// Adds a transaction that is about to be executed.
+    pub fn add_pending(&mut self, transaction: TransactionInput) {
+        insert(&mut self.pending, transaction);
+    }
+
+    /// Removes a pending transaction after it was mined or failed to be executed.
+    pub fn remove_pending(&mut self, transaction: &TransactionInput) {
+        remove(&mut self.pending, &transaction.signer, &transaction.nonce);
+    }
+
+    /// Adds a transaction with a nonce gap, replacing the queued transaction of the same sender with the same nonce if there is one.
+    ///
+    /// When the sender or the pool is full, the queued transaction with the highest nonce of the sender, or of the sender that stayed
+    /// without activity for longer, is evicted to make room for it. Transactions that would be evicted themselves are rejected.
+    pub fn add_queued(&mut self, transaction: TransactionInput, mined_nonce: &Nonce, now: Instant) -> Result<(), EthExecutorError> {
+        self.remove_expired(now);
+
+        // reject nonces too far ahead of the sender nonce
+        // nonces are user input and may not fit in 64 bits
+        let distance = U256::from(transaction.nonce.clone()).saturating_sub(mined_nonce.clone().into());
+        if distance > U256::from(self.limits.max_nonce_distance) {
+            tracing::warn!(nonce = %transaction.nonce, %mined_nonce, %distance, "rejecting queued transaction with nonce too far ahead");
+            return Err(EthExecutorError::NonceTooHigh {
+                address: transaction.signer.clone(),
+                expected: mined_nonce.clone(),
+                actual: transaction.nonce.clone(),
+            });
+        }
+
+        // replacements do not need room
+        let sender = transaction.signer.clone();
+        let is_replacement = self
+            .queued
+            .get(&sender)
+            .is_some_and(|transactions| transactions.contains_key(&transaction.nonce));
+        if not(is_replacement) {
+            let sender_len = self.queued.get(&sender).map(|transactions| transactions.len()).unwrap_or_default();
+            if sender_len >= self.limits.max_queued_per_sender {
+                // make room in the sender queue
+                self.evict_highest_nonce_of_sender(&sender, &transaction.nonce)?;
+            } else if count(&self.queued) as usize >= self.limits.max_queued {
+                // make room in the pool
+                match self.oldest_other_sender(&sender) {
+                    Some(oldest) => self.evict_highest_nonce(&oldest),
+                    None => self.evict_highest_nonce_of_sender(&sender, &transaction.nonce)?,
+                }
+            }
+        }
+
+        insert(&mut self.queued, transaction);
+        self.queued_heartbeats.insert(sender, now);
+        Ok(())
+    }
+
+    /// Removes and returns the queued transaction of the sender with the given nonce.
+    pub fn take_queued(&mut self, sender: &Address, nonce: &Nonce) -> Option<TransactionInput> {
+        let transaction = remove(&mut self.queued, sender, nonce);
+        if transaction.is_some() {
+            self.beat(sender, Instant::now());
+        }
+        transaction
+    }
+
+    /// Removes the queued transactions of senders that stayed without activity for longer than the queued lifetime.
+    pub fn remove_expired(&mut self, now: Instant) {
+        let lifetime = self.limits.queued_lifetime;
+        let expired = self
+            .queued_heartbeats
+            .iter()
+            .filter(|(_, heartbeat)| now.saturating_duration_since(**heartbeat) > lifetime)
+            .map(|(sender, _)| sender.clone())
+            .collect::<Vec<_>>();
+
+        for sender in expired {
+            let removed = self.queued.remove(&sender).map(|transactions| transactions.len()).unwrap_or_default();
+            self.queued_heartbeats.remove(&sender);
+            tracing::info!(%sender, %removed, "removed expired queued transactions");
+        }
+    }
+
+    /// Records activity of a sender with queued transactions, or forgets the sender if it has no more queued transactions.
+    fn beat(&mut self, sender: &Address, now: Instant) {
+        if self.queued.contains_key(sender) {
+            self.queued_heartbeats.insert(sender.clone(), now);
+        } else {
+            self.queued_heartbeats.remove(sender);
+        }
+    }
+
+    /// Sender with queued transactions other than the given one that stayed without activity for longer.
+    fn oldest_other_sender(&self, sender: &Address) -> Option<Address> {
+        self.queued_heartbeats
+            .iter()
+            .filter(|(other, _)| *other != sender)
+            .min_by_key(|(_, heartbeat)| **heartbeat)
+            .map(|(other, _)| other.clone())
+    }
+
+    /// Evicts the queued transaction with the highest nonce of the sender, failing if it is not higher than the given nonce.
+    fn evict_highest_nonce_of_sender(&mut self, sender: &Address, nonce: &Nonce) -> Result<(), EthExecutorError> {
+        let highest = self.queued.get(sender).and_then(|transactions| transactions.keys().next_back());
+        if highest.is_some_and(|highest| highest > nonce) {
+            self.evict_highest_nonce(sender);
+            Ok(())
+        } else {
+            tracing::warn!(%sender, %nonce, "rejecting queued transaction because txpool is full");
+            Err(EthExecutorError::TxPoolFull)
+        }
+    }
+
+    /// Evicts the queued transaction with the highest nonce of the sender.
+    fn evict_highest_nonce(&mut self, sender: &Address) {
+        let Some(nonce) = self.queued.get(sender).and_then(|transactions| transactions.keys().next_back().cloned()) else {
+            return;
+        };
+        if let Some(evicted) = remove(&mut self.queued, sender, &nonce) {
+            tracing::info!(hash = %evicted.hash, %sender, %nonce, "evicted queued transaction");
+        }
+        if not(self.queued.contains_key(sender)) {
+            self.queued_heartbeats.remove(sender);
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    // Serialization
+    // -------------------------------------------------------------------------
+
+    /// Serializes itself to `txpool_status` format.
+    pub fn to_txpool_status(&self) -> EthersTxpoolStatus {
+        EthersTxpoolStatus {
+            pending: count(&self.pending).into(),
+            queued: count(&self.queued).into(),
+        }
+    }
+
+    /// Serializes itself to `txpool_content` format.
+    pub fn to_txpool_content(&self) -> EthersTxpoolContent {
+        let render = |transaction: &TransactionInput| -> EthersTransaction { transaction.clone().into() };
+        EthersTxpoolContent {
+            pending: group_by_sender(&self.pending, render),
+            queued: group_by_sender(&self.queued, render),
+        }
+    }
+
+    /// Serializes itself to `txpool_inspect` format.
+    pub fn to_txpool_inspect(&self) -> EthersTxpoolInspect {
+        let render = |transaction: &TransactionInput| EthersTxpoolInspectSummary {
+            to: transaction.to.clone().map_into(),
+            value: transaction.value.clone().into(),
+            gas: transaction.gas.clone().into(),
+            gas_price: transaction.gas_price.clone().into(),
+        };
+        EthersTxpoolInspect {
+            pending: group_by_sender(&self.pending, render),
+            queued: group_by_sender(&self.queued, render),
+        }
+    }
+}
+
+fn insert(transactions: &mut TransactionsBySender, transaction: TransactionInput) {
+    transactions
+        .entry(transaction.signer.clone())
+        .or_default()
+        .insert(transaction.nonce.clone(), transaction);
+}
+
+fn remove(transactions: &mut TransactionsBySender, sender: &Address, nonce: &Nonce) -> Option<TransactionInput> {
+    let sender_transactions = transactions.get_mut(sender)?;
+    let transaction = sender_transactions.remove(nonce);
+    if sender_transactions.is_empty() {
+        transactions.remove(sender);
+    }
+    transaction
+}
+
+fn count(transactions: &TransactionsBySender) -> u64 {
+    transactions.values().map(|transactions| transactions.len() as u64).sum()
+}
+
+/// Groups transactions by sender and nonce, as expected by `txpool_*` methods.
+fn group_by_sender<T>(transactions: &TransactionsBySender, render: impl Fn(&TransactionInput) -> T) -> BTreeMap<H160, BTreeMap<String, T>> {
+    transactions
+        .iter()
+        .map(|(sender, transactions)| {
+            let transactions = transactions
+                .iter()
+                .map(|(nonce, transaction)| (nonce.to_string(), render(transaction)))
+                .collect();
+            (sender.clone().into(), transactions)
+        })
+        .collect()
+}
+
+// -----------------------------------------------------------------------------
+// Tests
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use std::time::Instant;
+
+    use ethereum_types::U256;
+    use fake::Fake;
+    use fake::Faker;
+
+    use crate::eth::primitives::*;
+    use crate::eth::txpool::TransactionPool;
+    use crate::eth::txpool::TransactionPoolLimits;
+    use crate::eth::EthExecutorError;
+
+    fn transaction(sender: &Address, nonce: u64) -> TransactionInput {
+        TransactionInput {
+            signer: sender.clone(),
+            from: sender.clone(),
+            nonce: nonce.into(),
+            ..Faker.fake()
+        }
+    }
+
+    #[test]
+    fn pending_nonce_skips_only_consecutive_nonces() {
+        let sender: Address = Faker.fake();
+        let mut pool = TransactionPool::default();
+        pool.add_pending(transaction(&sender, 1));
+        pool.add_queued(transaction(&sender, 2), &1u64.into(), Instant::now()).unwrap();
+        pool.add_queued(transaction(&sender, 4), &1u64.into(), Instant::now()).unwrap();
+
+        assert_eq!(pool.pending_nonce(&sender, 1u64.into()), 3u64.into());
+        assert_eq!(pool.pending_nonce(&Faker.fake(), 1u64.into()), 1u64.into());
+
+        // queued transactions are not executable until promoted
+        assert_eq!(pool.executable_nonce(&sender, 1u64.into()), 2u64.into());
+    }
+
+    #[test]
+    fn take_queued_releases_transactions_by_nonce() {
+        let sender: Address = Faker.fake();
+        let mut pool = TransactionPool::default();
+        pool.add_queued(transaction(&sender, 3), &1u64.into(), Instant::now()).unwrap();
+        pool.add_queued(transaction(&sender, 2), &1u64.into(), Instant::now()).unwrap();
+
+        assert!(pool.take_queued(&sender, &1u64.into()).is_none());
+        assert_eq!(pool.take_queued(&sender, &2u64.into()).unwrap().nonce, 2u64.into());
+        assert_eq!(pool.take_queued(&sender, &3u64.into()).unwrap().nonce, 3u64.into());
+        assert_eq!(pool.to_txpool_status().queued, 0u64.into());
+    }
+
+    fn limits(max_queued: usize, max_queued_per_sender: usize) -> TransactionPoolLimits {
+        TransactionPoolLimits {
+            max_queued,
+            max_queued_per_sender,
+            max_nonce_distance: 10,
+            queued_lifetime: Duration::from_secs(60),
+        }
+    }
+
+    fn queued_nonces(pool: &TransactionPool, sender: &Address) -> Vec<u64> {
+        pool.to_txpool_content()
+            .queued
+            .get(&sender.clone().into())
+            .map(|transactions| transactions.keys().map(|nonce| nonce.parse().unwrap()).collect())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn add_queued_rejects_nonces_too_far_ahead() {
+        let sender: Address = Faker.fake();
+        let mut pool = TransactionPool::new(limits(10, 10));
+
+        assert!(pool.add_queued(transaction(&sender, 11), &1u64.into(), Instant::now()).is_ok());
+        let result = pool.add_queued(transaction(&sender, 12), &1u64.into(), Instant::now());
+        assert!(matches!(result, Err(EthExecutorError::NonceTooHigh { .. })));
+
+        // nonces above 64 bits are rejected without overflowing
+        let mut overflowing = transaction(&sender, 0);
+        overflowing.nonce = (U256::from(u64::MAX) + 1).into();
+        let result = pool.add_queued(overflowing, &1u64.into(), Instant::now());
+        assert!(matches!(result, Err(EthExecutorError::NonceTooHigh { .. })));
+    }
+
+    #[test]
+    fn add_queued_evicts_highest_nonce_of_full_sender() {
+        let sender: Address = Faker.fake();
+        let mut pool = TransactionPool::new(limits(10, 2));
+        let now = Instant::now();
+        pool.add_queued(transaction(&sender, 3), &1u64.into(), now).unwrap();
+        pool.add_queued(transaction(&sender, 5), &1u64.into(), now).unwrap();
+
+        // lower nonce evicts the highest one
+        pool.add_queued(transaction(&sender, 4), &1u64.into(), now).unwrap();
+        assert_eq!(queued_nonces(&pool, &sender), vec![3, 4]);
+
+        // higher nonce would be evicted itself
+        let result = pool.add_queued(transaction(&sender, 6), &1u64.into(), now);
+        assert!(matches!(result, Err(EthExecutorError::TxPoolFull)));
+
+        // replacement does not need room
+        pool.add_queued(transaction(&sender, 4), &1u64.into(), now).unwrap();
+        assert_eq!(queued_nonces(&pool, &sender), vec![3, 4]);
+    }
+
+    #[test]
+    fn add_queued_evicts_from_oldest_sender_when_pool_is_full() {
+        let oldest: Address = Faker.fake();
+        let newest: Address = Faker.fake();
+        let sender: Address = Faker.fake();
+        let mut pool = TransactionPool::new(limits(3, 10));
+        let now = Instant::now();
+        pool.add_queued(transaction(&oldest, 2), &1u64.into(), now).unwrap();
+        pool.add_queued(transaction(&oldest, 3), &1u64.into(), now).unwrap();
+        pool.add_queued(transaction(&newest, 2), &1u64.into(), now + Duration::from_secs(1)).unwrap();
+
+        pool.add_queued(transaction(&sender, 2), &1u64.into(), now + Duration::from_secs(2)).unwrap();
+        assert_eq!(queued_nonces(&pool, &oldest), vec![2]);
+        assert_eq!(queued_nonces(&pool, &newest), vec![2]);
+        assert_eq!(queued_nonces(&pool, &sender), vec![2]);
+        assert_eq!(pool.to_txpool_status().queued, 3u64.into());
+    }
+
+    #[test]
+    fn remove_expired_drops_senders_without_recent_activity() {
+        let idle: Address = Faker.fake();
+        let active: Address = Faker.fake();
+        let mut pool = TransactionPool::new(limits(10, 10));
+        let now = Instant::now();
+        pool.add_queued(transaction(&idle, 2), &1u64.into(), now).unwrap();
+        pool.add_queued(transaction(&active, 2), &1u64.into(), now).unwrap();
+        pool.add_queued(transaction(&active, 3), &1u64.into(), now + Duration::from_secs(30)).unwrap();
+
+        pool.remove_expired(now + Duration::from_secs(61));
+        assert!(queued_nonces(&pool, &idle).is_empty());
+        assert_eq!(queued_nonces(&pool, &active), vec![2, 3]);
+    }
+}
@@ -31,15 +31,24 @@ metrics! {
     "Ethereum JSON-RPC requests that finished."
     histogram rpc_requests_finished{method, function, success},
 
+    "Ethereum executor conflicts detected between concurrent transactions."
+    counter   executor_conflicts{kind},
+
     "Ethereum storage accounts read."
     histogram storage_accounts_read{point_in_time, success},
 
+    "Ethereum storage block hashes read."
+    histogram storage_block_hashes_read{success},
+
     "Ethereum storage blocks read."
     histogram storage_blocks_read{success},
 
     "Ethereum storage blocks written."
     histogram storage_blocks_written{success},
 
+    "Ethereum storage bytecodes read."
+    histogram storage_bytecodes_read{success},
+
     "Ethereum storage logs read."
     histogram storage_logs_read{success},
 
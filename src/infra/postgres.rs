@@ -4,14 +4,14 @@ use std::time::Duration;
 
 use anyhow::anyhow;
 use anyhow::Context;
+use ethers_core::utils::keccak256;
 use sqlx::postgres::PgPoolOptions;
 use sqlx::types::BigDecimal;
 use sqlx::PgPool;
 
 use crate::eth::miner::BlockMiner;
-use crate::eth::primitives::Account;
 use crate::eth::primitives::BlockNumber;
-use crate::eth::storage::test_accounts;
+use crate::eth::primitives::Genesis;
 use crate::eth::storage::EthStorage;
 
 #[derive(Debug, Clone)]
@@ -20,7 +20,7 @@ pub struct Postgres {
 }
 
 impl Postgres {
-    pub async fn new(url: &str) -> anyhow::Result<Self> {
+    pub async fn new(url: &str, genesis: &Genesis) -> anyhow::Result<Self> {
         tracing::info!(%url, "connecting to postgres");
 
         let connection_pool = PgPoolOptions::new()
@@ -36,27 +36,53 @@ impl Postgres {
 
         let postgres = Self { connection_pool };
 
-        postgres.save_block(BlockMiner::genesis()).await?;
-        postgres.insert_test_accounts_in_genesis(test_accounts()).await?;
+        postgres.save_block(BlockMiner::genesis(genesis)).await?;
+        postgres.insert_genesis_accounts(genesis).await?;
 
         Ok(postgres)
     }
 
-    async fn insert_test_accounts_in_genesis(&self, accounts: Vec<Account>) -> anyhow::Result<()> {
-        tracing::debug!("adding test accounts to genesis block");
+    /// Indexes a deployed bytecode by its hash, so it can be found without knowing the contract address.
+    pub async fn insert_bytecode(&self, bytecode: &[u8]) -> anyhow::Result<()> {
+        sqlx::query_file!("src/eth/storage/postgres/queries/insert_bytecode.sql", &keccak256(bytecode), bytecode)
+            .execute(&self.connection_pool)
+            .await
+            .context("failed to insert bytecode")?;
+        Ok(())
+    }
+
+    async fn insert_genesis_accounts(&self, genesis: &Genesis) -> anyhow::Result<()> {
+        tracing::debug!("adding accounts to genesis block");
 
-        for acc in accounts {
+        let block_number = i64::try_from(BlockNumber::ZERO).context("failed to convert block number")?;
+        for (acc, slots) in genesis.accounts.iter().cloned() {
             sqlx::query_file!(
                 "src/eth/storage/postgres/queries/insert_account.sql",
                 acc.address.as_bytes(),
                 BigDecimal::try_from(acc.nonce)?,
                 BigDecimal::try_from(acc.balance)?,
                 acc.bytecode.as_deref(),
-                i64::try_from(BlockNumber::ZERO).context("failed to convert block number")?
+                block_number
             )
             .execute(&self.connection_pool)
             .await
             .context("failed to insert account")?;
+            if let Some(ref bytecode) = acc.bytecode {
+                self.insert_bytecode(bytecode).await?;
+            }
+
+            for slot in slots {
+                sqlx::query_file!(
+                    "src/eth/storage/postgres/queries/insert_account_slot.sql",
+                    &<[u8; 32]>::from(slot.index),
+                    &<[u8; 32]>::from(slot.value),
+                    acc.address.as_ref(),
+                    block_number
+                )
+                .execute(&self.connection_pool)
+                .await
+                .context("failed to insert slot")?;
+            }
         }
 
         Ok(())
@@ -1,13 +1,18 @@
 use std::sync::Arc;
 use std::time::Duration;
 
+use anyhow::anyhow;
 use clap::Parser;
 use nonempty::NonEmpty;
 use stratus::config::Config;
 use stratus::config::StorageConfig;
 use stratus::eth::evm::revm::Revm;
 use stratus::eth::evm::Evm;
+use stratus::eth::miner::SystemClock;
+use stratus::eth::primitives::ChainId;
+use stratus::eth::primitives::Genesis;
 use stratus::eth::rpc::serve_rpc;
+use stratus::eth::storage::test_accounts;
 use stratus::eth::storage::EthStorage;
 use stratus::eth::storage::InMemoryStorage;
 use stratus::eth::EthExecutor;
@@ -21,6 +26,7 @@ use tokio::sync::broadcast;
 
 fn main() -> anyhow::Result<()> {
     let config = Arc::new(Config::parse());
+    config.validate()?;
 
     infra::init_tracing();
     infra::init_metrics();
@@ -87,30 +93,58 @@ pub fn init_async_runtime(config: &Config) -> Runtime {
 async fn run_rpc_server(config: Arc<Config>, cancel_signal: broadcast::Receiver<()>) -> anyhow::Result<()> {
     tracing::info!("Starting RPC server");
 
+    let genesis = init_genesis(&config)?;
     let storage: Arc<dyn EthStorage> = match &config.storage {
         // init services
-        StorageConfig::InMemory => Arc::new(InMemoryStorage::default().metrified()),
-        StorageConfig::Postgres { url } => Arc::new(Postgres::new(url).await?.metrified()),
+        StorageConfig::InMemory => Arc::new(InMemoryStorage::new(&genesis).metrified()),
+        StorageConfig::Postgres { url } => Arc::new(Postgres::new(url, &genesis).await?.metrified()),
     };
 
     // init executor
-    let evms = init_evms(&config, Arc::clone(&storage));
-    let executor = EthExecutor::new(evms, Arc::clone(&storage));
+    let evms = init_evms(&config, Arc::clone(&storage))?;
+    let executor = Arc::new(EthExecutor::new(evms, Arc::clone(&storage), Arc::new(SystemClock), &config));
+    Arc::clone(&executor).spawn_interval_miner();
+    Arc::clone(&executor).spawn_queue_promoter();
 
-    serve_rpc(executor, storage, config.address, cancel_signal).await?;
+    serve_rpc(executor, storage, Arc::clone(&config), cancel_signal).await?;
 
     tracing::info!("RPC server started");
     Ok(())
 }
 
+/// Inits the initial state of the chain from the genesis file, adding test accounts if enabled.
+fn init_genesis(config: &Config) -> anyhow::Result<Genesis> {
+    let mut genesis = match config.genesis {
+        Some(ref path) => Genesis::from_file(path)?,
+        None => Genesis::default(),
+    };
+
+    if let Some(ref chain_id) = genesis.chain_id {
+        if chain_id != &ChainId::from(config.chain_id) {
+            return Err(anyhow!(
+                "genesis chain id {} is different from configured chain id {}",
+                chain_id,
+                config.chain_id
+            ));
+        }
+    }
+
+    if config.enable_test_accounts {
+        genesis.add_accounts(test_accounts());
+    }
+
+    tracing::info!(accounts = %genesis.accounts.len(), timestamp = *genesis.timestamp, "genesis initialized");
+    Ok(genesis)
+}
+
 /// Inits EVMs that will executes transactions in parallel.
-fn init_evms(config: &Config, storage: Arc<dyn EthStorage>) -> NonEmpty<Box<dyn Evm>> {
+fn init_evms(config: &Config, storage: Arc<dyn EthStorage>) -> anyhow::Result<NonEmpty<Box<dyn Evm>>> {
     let mut evms: Vec<Box<dyn Evm>> = Vec::with_capacity(config.num_evms);
     for _ in 1..=config.num_evms {
-        evms.push(Box::new(Revm::new(Arc::clone(&storage))));
+        evms.push(Box::new(Revm::new(Arc::clone(&storage), config)?));
     }
-    tracing::info!(evms = %config.num_evms, "evms initialized");
-    NonEmpty::from_vec(evms).unwrap()
+    tracing::info!(evms = %config.num_evms, charge_gas = %config.charge_gas, "evms initialized");
+    Ok(NonEmpty::from_vec(evms).unwrap())
 }
 
 #[cfg(feature = "p2p-substrate")]
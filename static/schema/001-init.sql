@@ -3,9 +3,15 @@ CREATE TABLE IF NOT EXISTS blocks (
     number BIGSERIAL NOT NULL CHECK (number >= 0) UNIQUE
     ,hash BYTEA NOT NULL CHECK (LENGTH(hash) = 32) UNIQUE
     ,transactions_root BYTEA NOT NULL CHECK (LENGTH(transactions_root) = 32)
+    ,state_root BYTEA NOT NULL CHECK (LENGTH(state_root) = 32)
+    ,receipts_root BYTEA NOT NULL CHECK (LENGTH(receipts_root) = 32)
     ,gas NUMERIC NOT NULL CHECK (gas >= 0)
+    ,gas_limit NUMERIC NOT NULL CHECK (gas_limit >= 0)
+    ,base_fee NUMERIC NOT NULL CHECK (base_fee >= 0)
     ,logs_bloom BYTEA NOT NULL CHECK (LENGTH(logs_bloom) = 256)
+    ,miner BYTEA NOT NULL CHECK (LENGTH(miner) = 20)
     ,timestamp_in_secs INTEGER NOT NULL CHECK (timestamp_in_secs >= 0) -- UNIQUE
+    ,size BIGINT NOT NULL CHECK (size >= 0)
     ,parent_hash BYTEA NOT NULL CHECK (LENGTH(parent_hash) = 32) UNIQUE
     ,created_at TIMESTAMP NOT NULL
     ,PRIMARY KEY (number, hash)
@@ -15,11 +21,17 @@ CREATE TABLE IF NOT EXISTS accounts (
     address BYTEA NOT NULL CHECK (LENGTH(address) = 20),
     nonce NUMERIC NOT NULL CHECK (nonce >= 0),
     balance NUMERIC NOT NULL CHECK (balance >= 0),
-    bytecode BYTEA CHECK (LENGTH(bytecode) <= 24000),
+    bytecode BYTEA CHECK (LENGTH(bytecode) <= 262144),
     block_number BIGSERIAL NOT NULL REFERENCES blocks (number) ON DELETE CASCADE,
     PRIMARY KEY (address, block_number)
 );
 
+CREATE TABLE IF NOT EXISTS bytecodes (
+    code_hash BYTEA NOT NULL CHECK (LENGTH(code_hash) = 32),
+    bytecode BYTEA NOT NULL,
+    PRIMARY KEY (code_hash)
+);
+
 CREATE TABLE IF NOT EXISTS account_slots (
     idx BYTEA NOT NULL CHECK (LENGTH(idx) = 32),
     value BYTEA NOT NULL CHECK (LENGTH(value) = 32),
@@ -35,7 +47,7 @@ CREATE TABLE IF NOT EXISTS transactions (
     nonce NUMERIC NOT NULL CHECK (nonce >= 0),
     address_from BYTEA NOT NULL CHECK (LENGTH(address_from) = 20),
     address_to BYTEA CHECK  (LENGTH(address_to) = 20),
-    input BYTEA NOT NULL CHECK (LENGTH(input) <= 24000)
+    input BYTEA NOT NULL CHECK (LENGTH(input) <= 524288)
     ,output BYTEA NOT NULL
     ,gas NUMERIC NOT NULL CHECK (gas >= 0)
     ,gas_price NUMERIC NOT NULL CHECK (gas_price >= 0)
@@ -48,6 +60,13 @@ CREATE TABLE IF NOT EXISTS transactions (
     ,s BYTEA NOT NULL
     ,value NUMERIC NOT NULL CHECK (value >= 0)
     ,result TEXT NOT NULL
+    ,cumulative_gas_used NUMERIC NOT NULL CHECK (cumulative_gas_used >= 0)
+    ,logs_bloom BYTEA NOT NULL CHECK (LENGTH(logs_bloom) = 256)
+    ,transaction_type INTEGER NOT NULL CHECK (transaction_type >= 0)
+    ,max_fee_per_gas NUMERIC CHECK (max_fee_per_gas >= 0)
+    ,max_priority_fee_per_gas NUMERIC CHECK (max_priority_fee_per_gas >= 0)
+    ,access_list BYTEA NOT NULL
+    ,effective_gas_price NUMERIC NOT NULL CHECK (effective_gas_price >= 0)
     ,PRIMARY KEY (hash)
 );
 